@@ -3,6 +3,7 @@
 use crate::app::{AppCtx, AppError, AppState};
 use crate::ref_actor::ensure_ref_actor;
 use crate::user_state_updates::{update_ref_for_users, update_user_state};
+use crate::validation::{ModelValidation, validate_document};
 use catcolab_document_types::automerge_json::{
     hydrate_to_json_with_rich_text, populate_automerge_from_json,
 };
@@ -10,6 +11,7 @@ use catcolab_document_types::automerge_util::copy_doc_at_heads;
 use chrono::{DateTime, Utc};
 use samod::DocumentId;
 use serde_json::Value;
+use sqlx::types::Json;
 use uuid::Uuid;
 
 /// Maximum allowed document size in bytes (5MB).
@@ -32,6 +34,7 @@ pub async fn new_ref(ctx: AppCtx, content: Value) -> Result<Uuid, AppError> {
             .map_err(|e| AppError::Invalid(format!("Failed to parse document: {}", e)))?;
 
     let ref_id = Uuid::now_v7();
+    let validation = snapshot_validation(&ctx.state, ref_id, &content).await;
 
     // Create automerge document and populate it with the JSON content
     let mut automerge_doc = automerge::Automerge::new();
@@ -58,8 +61,8 @@ pub async fn new_ref(ctx: AppCtx, content: Value) -> Result<Uuid, AppError> {
     sqlx::query(
         "
         WITH snapshot AS (
-            INSERT INTO snapshots(for_ref, content, created_at, heads)
-            VALUES ($1, $2, NOW(), $4)
+            INSERT INTO snapshots(for_ref, content, created_at, heads, validation)
+            VALUES ($1, $2, NOW(), $4, $5)
         RETURNING id
         )
         INSERT INTO refs(id, current_snapshot, created, doc_id, current_snapshot_updated_at)
@@ -72,6 +75,7 @@ pub async fn new_ref(ctx: AppCtx, content: Value) -> Result<Uuid, AppError> {
     .bind(content)
     .bind(doc_id)
     .bind(&heads)
+    .bind(validation)
     .execute(&mut *txn)
     .await?;
 
@@ -150,12 +154,13 @@ pub async fn create_snapshot(state: AppState, ref_id: Uuid) -> Result<(), AppErr
             .map_err(|e| AppError::Invalid(format!("Failed to serialize document: {:?}", e)))?;
         Ok::<_, AppError>((heads, doc_content))
     })?;
+    let validation = snapshot_validation(&state, ref_id, &doc_content).await;
 
     sqlx::query(
         "
         WITH snapshot AS (
-            INSERT INTO snapshots(for_ref, content, created_at, heads, parent)
-            VALUES ($1, $2, NOW(), $3, (SELECT current_snapshot FROM refs WHERE id = $1))
+            INSERT INTO snapshots(for_ref, content, created_at, heads, parent, validation)
+            VALUES ($1, $2, NOW(), $3, (SELECT current_snapshot FROM refs WHERE id = $1), $4)
             RETURNING id
         )
         UPDATE refs
@@ -167,6 +172,7 @@ pub async fn create_snapshot(state: AppState, ref_id: Uuid) -> Result<(), AppErr
     .bind(ref_id)
     .bind(doc_content)
     .bind(&heads)
+    .bind(validation)
     .execute(&state.db)
     .await?;
 
@@ -177,6 +183,47 @@ pub async fn create_snapshot(state: AppState, ref_id: Uuid) -> Result<(), AppErr
     Ok(())
 }
 
+/// Validates the content of a new snapshot, if it is a model.
+///
+/// Failure to validate is logged but does not prevent the snapshot from being saved.
+async fn snapshot_validation(
+    state: &AppState,
+    ref_id: Uuid,
+    content: &Value,
+) -> Option<Json<ModelValidation>> {
+    match validate_document(&state.db, ref_id, content).await {
+        Ok(validation) => validation.map(Json),
+        Err(e) => {
+            tracing::error!(%ref_id, error = %e, "Failed to validate document");
+            None
+        }
+    }
+}
+
+/// Gets the validation of the current snapshot of a document ref.
+///
+/// Returns `None` if the snapshot was not validated, e.g., because the document
+/// is not a model.
+pub async fn get_validation(
+    state: AppState,
+    ref_id: Uuid,
+) -> Result<Option<ModelValidation>, AppError> {
+    let validation: Option<(Option<Json<ModelValidation>>,)> = sqlx::query_as(
+        "
+        SELECT snapshots.validation FROM refs
+        JOIN snapshots ON snapshots.id = refs.current_snapshot
+        WHERE refs.id = $1
+        ",
+    )
+    .bind(ref_id)
+    .fetch_optional(&state.db)
+    .await?;
+
+    let (validation,) =
+        validation.ok_or_else(|| AppError::NotFound(format!("document ref {ref_id}")))?;
+    Ok(validation.map(|Json(validation)| validation))
+}
+
 /// Set a live Automerge document to a different snapshot's state.
 ///
 /// The document is updated in-place: the target snapshot's state is read from
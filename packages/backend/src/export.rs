@@ -0,0 +1,117 @@
+//! Export of simulation results as tables.
+//!
+//! Batch jobs that run many simulations of a model can send the solutions to
+//! the backend and get back a single table in long format, with the state
+//! variables labelled by the names of the objects in the model document.
+
+use std::collections::HashMap;
+
+use base64::{Engine as _, engine::general_purpose};
+use catcolab_document_types::current::ModelDocumentContent;
+use catlog::stdlib::analyses::ode::ODESolution;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::app::AppError;
+use crate::validation::{load_dependencies, model_namespace, parse_model};
+
+/// Format of an exported table.
+#[qubit::ts]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TableFormat {
+    /// Comma-separated values, as text.
+    Csv,
+    /// Arrow IPC stream, encoded in base64.
+    Arrow,
+}
+
+/// Exports an ensemble of solutions to the ODE system of a model document.
+///
+/// The solutions are labelled using the current snapshot of the document.
+pub async fn export_solutions(
+    db: &PgPool,
+    ref_id: Uuid,
+    solutions: Vec<ODESolution>,
+    format: TableFormat,
+) -> Result<String, AppError> {
+    let content: Option<(Value,)> = sqlx::query_as(
+        "
+        SELECT snapshots.content FROM refs
+        JOIN snapshots ON snapshots.id = refs.current_snapshot
+        WHERE refs.id = $1
+        ",
+    )
+    .bind(ref_id)
+    .fetch_optional(db)
+    .await?;
+    let (content,) = content.ok_or_else(|| AppError::NotFound(format!("document ref {ref_id}")))?;
+
+    let model = parse_model(&content)
+        .ok_or_else(|| AppError::Invalid(format!("Document {ref_id} is not a model")))?;
+    let dependencies = load_dependencies(db, ref_id, &model).await?;
+
+    // Elaboration runs on a blocking thread, as in validation.
+    tokio::task::spawn_blocking(move || {
+        solutions_table(&ref_id.to_string(), &model, &dependencies, &solutions, format)
+    })
+    .await
+    .map_err(|e| AppError::Invalid(format!("Elaboration failed: {e}")))?
+}
+
+/// Builds the table of an ensemble of solutions to the ODE system of a model.
+pub fn solutions_table(
+    ref_id: &str,
+    model: &ModelDocumentContent,
+    dependencies: &HashMap<String, ModelDocumentContent>,
+    solutions: &[ODESolution],
+    format: TableFormat,
+) -> Result<String, AppError> {
+    let namespace = model_namespace(ref_id, model, dependencies)
+        .ok_or_else(|| AppError::Invalid(format!("Model {ref_id} does not elaborate")))?;
+    let df = ODESolution::ensemble_data_frame(solutions, &namespace);
+    Ok(match format {
+        TableFormat::Csv => df.to_csv(),
+        TableFormat::Arrow => general_purpose::STANDARD.encode(df.to_arrow_ipc()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn example(name: &str) -> Value {
+        let path =
+            format!("{}/../document-types/examples/v2/{name}.json", env!("CARGO_MANIFEST_DIR"));
+        serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn export_example_solutions() {
+        let model = parse_model(&example("SEIRV")).unwrap();
+        let solution: ODESolution = serde_json::from_value(json!({
+            "time": [0.0, 1.0],
+            "states": {
+                "0194d7a9-bc26-73ac-b2b8-6eb9a514827b": [10.0, 9.0],
+            },
+        }))
+        .unwrap();
+        let solutions = [solution.clone(), solution];
+        let namespace = model_namespace("seirv", &model, &HashMap::new()).unwrap();
+
+        let csv = solutions_table("seirv", &model, &HashMap::new(), &solutions, TableFormat::Csv)
+            .unwrap();
+        let mut lines = csv.lines();
+        assert_eq!(lines.next(), Some("run,time,variable,value"));
+        assert_eq!(lines.next(), Some("0,0,Susceptible,10"));
+        assert_eq!(lines.count(), 3);
+
+        let arrow =
+            solutions_table("seirv", &model, &HashMap::new(), &solutions, TableFormat::Arrow)
+                .unwrap();
+        let bytes = general_purpose::STANDARD.decode(arrow).unwrap();
+        assert_eq!(bytes, ODESolution::ensemble_data_frame(&solutions, &namespace).to_arrow_ipc());
+    }
+}
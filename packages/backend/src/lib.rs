@@ -15,6 +15,9 @@ pub mod autosurgeon_datetime;
 /// Procedures to create and manipulate documents.
 pub mod document;
 
+/// Export of simulation results as tables.
+pub mod export;
+
 /// RPC service for the backend.
 pub mod rpc;
 
@@ -32,3 +35,6 @@ pub mod user_state;
 
 /// User-state update helpers called from RPC handlers.
 pub mod user_state_updates;
+
+/// Validation of model documents against their theory.
+pub mod validation;
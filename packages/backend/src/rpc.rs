@@ -8,9 +8,11 @@ use uuid::Uuid;
 
 use super::app::{AppCtx, AppError, AppState, RefMsg};
 use super::auth::{NewPermissions, PermissionLevel, Permissions};
+use super::export::TableFormat;
 use super::ref_actor::{ensure_ref_actor, send_to_actor};
 use super::user_state::get_or_create_user_state_doc;
-use super::{auth, document as doc, inference, user};
+use super::validation::ModelValidation;
+use super::{auth, document as doc, export, inference, user};
 
 /// Create router for RPC API.
 pub fn router() -> Router<AppState> {
@@ -21,6 +23,8 @@ pub fn router() -> Router<AppState> {
         .handler(delete_ref)
         .handler(restore_ref)
         .handler(get_permissions)
+        .handler(get_validation)
+        .handler(export_solutions)
         .handler(set_permissions)
         .handler(validate_session)
         .handler(sign_up_or_sign_in)
@@ -127,6 +131,33 @@ async fn get_permissions(ctx: AppCtx, ref_id: Uuid) -> RpcResult<Permissions> {
     auth::permissions(&ctx, ref_id).await.into()
 }
 
+#[handler(query)]
+async fn get_validation(ctx: AppCtx, ref_id: Uuid) -> RpcResult<Option<ModelValidation>> {
+    async {
+        auth::authorize(&ctx, ref_id, PermissionLevel::Read).await?;
+        doc::get_validation(ctx.state, ref_id).await
+    }
+    .await
+    .into()
+}
+
+#[handler(query)]
+async fn export_solutions(
+    ctx: AppCtx,
+    ref_id: Uuid,
+    solutions: Value,
+    format: TableFormat,
+) -> RpcResult<String> {
+    async {
+        auth::authorize(&ctx, ref_id, PermissionLevel::Read).await?;
+        let solutions = serde_json::from_value(solutions)
+            .map_err(|err| AppError::Invalid(format!("Invalid solutions: {err}")))?;
+        export::export_solutions(&ctx.state.db, ref_id, solutions, format).await
+    }
+    .await
+    .into()
+}
+
 #[handler(mutation)]
 async fn set_permissions(ctx: AppCtx, ref_id: Uuid, new: NewPermissions) -> RpcResult<()> {
     async {
@@ -11,6 +11,7 @@ use ts_rs::TS;
 
 use crate::app::{AppError, AppState};
 use crate::autosurgeon_datetime::{datetime_millis, option_datetime_millis};
+use crate::validation::ValidationStatus;
 
 /// Default name for documents without a name.
 pub const DEFAULT_DOC_NAME: &str = "untitled";
@@ -119,6 +120,8 @@ pub struct DocInfo {
     /// the dependent document and the relation type.
     #[autosurgeon(rename = "usedBy")]
     pub used_by: Vec<RelationInfo>,
+    /// Validation status of the current snapshot, if it is a model validated by the backend.
+    pub validation: Option<ValidationStatus>,
 }
 
 /// State associated with a user, synchronized via Automerge.
@@ -338,10 +341,31 @@ pub async fn read_user_state_from_db(user_id: String, db: &PgPool) -> Result<Use
             snapshots,
             depends_on,
             used_by: Vec::new(),
+            validation: None,
         };
         documents.insert(key, info);
     }
 
+    // Fetch the validation status of each document's current snapshot.
+    let ref_ids: Vec<uuid::Uuid> =
+        documents.keys().filter_map(|key| uuid::Uuid::parse_str(key).ok()).collect();
+    let validations: Vec<(uuid::Uuid, sqlx::types::Json<ValidationStatus>)> = sqlx::query_as(
+        "
+        SELECT refs.id, snapshots.validation->'tag'
+        FROM refs
+        JOIN snapshots ON snapshots.id = refs.current_snapshot
+        WHERE refs.id = ANY($1) AND snapshots.validation IS NOT NULL
+        ",
+    )
+    .bind(&ref_ids)
+    .fetch_all(db)
+    .await?;
+    for (ref_id, status) in validations {
+        if let Some(info) = documents.get_mut(&ref_id.to_string()) {
+            info.validation = Some(status.0);
+        }
+    }
+
     // Fetch user info for all users referenced in document permissions.
     let user_ids: Vec<String> = documents
         .values()
@@ -558,6 +582,7 @@ pub mod arbitrary {
                 0i64..253402300799i64,
                 proptest::option::of(0i64..253402300799i64),
                 0i64..253402300799i64,
+                proptest::option::of(any::<ValidationStatus>()),
             )
                 .prop_map(
                     |(
@@ -568,6 +593,7 @@ pub mod arbitrary {
                         seconds,
                         deleted_seconds,
                         updated_seconds,
+                        validation,
                     )| {
                         DocInfo {
                             name: Text::from(name),
@@ -589,6 +615,7 @@ pub mod arbitrary {
                             snapshots: HashMap::new(),
                             depends_on: Vec::new(),
                             used_by: Vec::new(),
+                            validation,
                         }
                     },
                 )
@@ -686,6 +713,7 @@ pub mod arbitrary {
                         // docs
                         depends_on: Vec::new(),
                         used_by: Vec::new(),
+                        validation: None,
                     };
                     (key, info, users)
                 },
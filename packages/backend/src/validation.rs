@@ -0,0 +1,391 @@
+//! Validation of model documents against their theory.
+//!
+//! When a snapshot is saved, model documents are elaborated with catlog along
+//! the same path as the frontend, including any instantiated models, and the
+//! outcome is recorded with the snapshot.
+
+use std::collections::{HashMap, HashSet};
+
+use autosurgeon::{Hydrate, Reconcile};
+use catcolab_document_types::current::{Document, LinkType, ModelDocumentContent, ModelJudgment};
+use catlog::dbl::model::InvalidDblModel;
+use catlog::stdlib::theories;
+use catlog::tt::{
+    modelgen::Model,
+    notebook_elab::Elaborator,
+    theory::{Theory, TheoryDef, std_theories},
+    toplevel::{TopDecl, Toplevel, Type},
+};
+use catlog::validate::Validate;
+use catlog::zero::{NameSegment, Namespace};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::PgPool;
+use ustr::ustr;
+use uuid::Uuid;
+
+#[cfg(feature = "property-tests")]
+use test_strategy::Arbitrary;
+
+use crate::app::AppError;
+
+/// Outcome of validating a model document.
+///
+/// Mirrors the states of a validated model in the frontend.
+#[qubit::ts]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "tag")]
+pub enum ModelValidation {
+    /// The model elaborated without errors.
+    Valid,
+    /// The model elaborated but is not a valid model of its theory.
+    Invalid {
+        /// Errors from elaborating and validating the model.
+        #[ts(as = "Vec<serde_json::Value>")]
+        errors: Vec<InvalidDblModel>,
+    },
+    /// The model could not be elaborated at all.
+    Illformed {
+        /// Description of the problem.
+        error: String,
+    },
+}
+
+impl ModelValidation {
+    /// Gets the status of the validation, without any errors.
+    pub fn status(&self) -> ValidationStatus {
+        match self {
+            ModelValidation::Valid => ValidationStatus::Valid,
+            ModelValidation::Invalid { .. } => ValidationStatus::Invalid,
+            ModelValidation::Illformed { .. } => ValidationStatus::Illformed,
+        }
+    }
+}
+
+/// Status of a validated model document.
+#[qubit::ts]
+#[cfg_attr(feature = "property-tests", derive(Arbitrary))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize, Reconcile, Hydrate)]
+pub enum ValidationStatus {
+    /// See [`ModelValidation::Valid`].
+    Valid,
+    /// See [`ModelValidation::Invalid`].
+    Invalid,
+    /// See [`ModelValidation::Illformed`].
+    Illformed,
+}
+
+/// Gets the double theory for a theory ID used by the frontend.
+///
+/// Returns `None` for theories that the backend does not know about.
+pub fn theory_def(theory_id: &str) -> Option<TheoryDef> {
+    Some(match theory_id {
+        "empty" => TheoryDef::discrete(theories::th_empty()),
+        "simple-olog" => TheoryDef::discrete(theories::th_category()),
+        "simple-schema" => TheoryDef::discrete(theories::th_schema()),
+        "petri-net" => TheoryDef::modal_unital(theories::th_sym_monoidal_category()),
+        "polynomial-ode" => TheoryDef::modal_non_unital(theories::th_polynomial_ode_system()),
+        "signed-polynomial-ode" => {
+            TheoryDef::modal_non_unital(theories::th_signed_polynomial_ode_system())
+        }
+        "causal-loop" | "reg-net" => TheoryDef::discrete(theories::th_signed_category()),
+        "causal-loop-delays" => TheoryDef::discrete(theories::th_delayable_signed_category()),
+        "indeterminate-causal-loop" => TheoryDef::discrete(theories::th_nullable_signed_category()),
+        "primitive-stock-flow" => TheoryDef::discrete_tab(theories::th_category_links()),
+        "primitive-signed-stock-flow" => {
+            TheoryDef::discrete_tab(theories::th_category_signed_links())
+        }
+        "unary-dec" => TheoryDef::discrete(theories::th_category_with_scalars()),
+        "power-system" => TheoryDef::discrete(theories::th_power_system()),
+        _ => return None,
+    })
+}
+
+/// Validates the JSON content of a document with the given ref ID.
+///
+/// Returns `None` if the document is not a model or if its theory is unknown
+/// to the backend. The current snapshots of any instantiated models are loaded
+/// from the database before elaborating.
+pub async fn validate_document(
+    db: &PgPool,
+    ref_id: Uuid,
+    content: &Value,
+) -> Result<Option<ModelValidation>, AppError> {
+    let Some(model) = parse_model(content) else {
+        return Ok(None);
+    };
+    let dependencies = load_dependencies(db, ref_id, &model).await?;
+
+    // Elaboration is CPU-bound and could panic on malformed input, so it runs
+    // on a blocking thread where a panic is caught by the join handle.
+    let validation = tokio::task::spawn_blocking(move || {
+        validate_model(&ref_id.to_string(), &model, &dependencies)
+    })
+    .await
+    .unwrap_or_else(|e| {
+        let error = format!("Elaboration failed: {e}");
+        Some(ModelValidation::Illformed { error })
+    });
+    Ok(validation)
+}
+
+/// Loads the current snapshots of the models instantiated by a model, transitively.
+///
+/// The models are loaded before elaborating anything, since catlog's data
+/// structures are not `Send`. Instantiations of documents that are not models
+/// are skipped.
+pub async fn load_dependencies(
+    db: &PgPool,
+    ref_id: Uuid,
+    model: &ModelDocumentContent,
+) -> Result<HashMap<String, ModelDocumentContent>, AppError> {
+    let mut dependencies = HashMap::new();
+    let mut pending = instantiated_ref_ids(model);
+    while let Some(dep_id) = pending.pop() {
+        if dep_id == ref_id.to_string() || dependencies.contains_key(&dep_id) {
+            continue;
+        }
+        let Ok(dep_uuid) = Uuid::parse_str(&dep_id) else {
+            continue;
+        };
+        let content: Option<(Value,)> = sqlx::query_as(
+            "
+            SELECT snapshots.content FROM refs
+            JOIN snapshots ON snapshots.id = refs.current_snapshot
+            WHERE refs.id = $1
+            ",
+        )
+        .bind(dep_uuid)
+        .fetch_optional(db)
+        .await?;
+        if let Some(dep) = content.and_then(|(content,)| parse_model(&content)) {
+            pending.extend(instantiated_ref_ids(&dep));
+            dependencies.insert(dep_id, dep);
+        }
+    }
+    Ok(dependencies)
+}
+
+/// Validates a model document, given the documents of the models it instantiates.
+///
+/// Instantiated models are looked up in `dependencies` by ref ID and are
+/// elaborated before the model that instantiates them. Returns `None` if the
+/// theory of the model, or of a model it instantiates, is unknown.
+pub fn validate_model(
+    ref_id: &str,
+    model: &ModelDocumentContent,
+    dependencies: &HashMap<String, ModelDocumentContent>,
+) -> Option<ModelValidation> {
+    ModelValidator::new(dependencies).validate(ref_id, model)
+}
+
+/// Gets the namespace labelling the objects and morphisms of a model document.
+///
+/// The model is elaborated as in [`validate_model`]. Returns `None` if the model
+/// or a model it instantiates has an unknown theory or is ill-formed.
+pub fn model_namespace(
+    ref_id: &str,
+    model: &ModelDocumentContent,
+    dependencies: &HashMap<String, ModelDocumentContent>,
+) -> Option<Namespace> {
+    let mut validator = ModelValidator::new(dependencies);
+    match validator.validate(ref_id, model)? {
+        ModelValidation::Illformed { .. } => None,
+        _ => validator.namespaces.remove(ref_id),
+    }
+}
+
+/// Parses the JSON content of a document, if it is a model.
+pub fn parse_model(content: &Value) -> Option<ModelDocumentContent> {
+    let doc: catcolab_document_types::VersionedDocument =
+        serde_json::from_value(content.clone()).ok()?;
+    match doc.to_current() {
+        Document::Model(model) => Some(model),
+        _ => None,
+    }
+}
+
+fn instantiated_ref_ids(model: &ModelDocumentContent) -> Vec<String> {
+    model
+        .notebook
+        .formal_content()
+        .filter_map(|judgment| match judgment {
+            ModelJudgment::Instantiation(inst) => inst.model.as_ref(),
+            _ => None,
+        })
+        .filter(|link| link.r#type == LinkType::Instantiation)
+        .map(|link| link.stable_ref.id.clone())
+        .collect()
+}
+
+/// State of validating a model together with the models it instantiates.
+struct ModelValidator<'a> {
+    dependencies: &'a HashMap<String, ModelDocumentContent>,
+    toplevel: Toplevel,
+    instantiated: HashSet<String>,
+    elaborating: HashSet<String>,
+    namespaces: HashMap<String, Namespace>,
+}
+
+impl<'a> ModelValidator<'a> {
+    fn new(dependencies: &'a HashMap<String, ModelDocumentContent>) -> Self {
+        Self {
+            dependencies,
+            toplevel: Toplevel::new(std_theories()),
+            instantiated: HashSet::new(),
+            elaborating: HashSet::new(),
+            namespaces: HashMap::new(),
+        }
+    }
+
+    fn validate(&mut self, ref_id: &str, model: &ModelDocumentContent) -> Option<ModelValidation> {
+        let theory_def = theory_def(&model.theory)?;
+        self.elaborating.insert(ref_id.to_string());
+        let result = self.elaborate_and_validate(ref_id, model, theory_def);
+        self.elaborating.remove(ref_id);
+        result
+    }
+
+    fn elaborate_and_validate(
+        &mut self,
+        ref_id: &str,
+        model: &ModelDocumentContent,
+        theory_def: TheoryDef,
+    ) -> Option<ModelValidation> {
+        for dep_id in instantiated_ref_ids(model) {
+            if self.instantiated.contains(&dep_id) {
+                continue;
+            }
+            if self.elaborating.contains(&dep_id) {
+                let error = "Model contains a cycle of instantiations".to_string();
+                return Some(ModelValidation::Illformed { error });
+            }
+            let Some(dep) = self.dependencies.get(&dep_id) else {
+                let error = format!("Instantiated model not found: {dep_id}");
+                return Some(ModelValidation::Illformed { error });
+            };
+            if let ModelValidation::Illformed { error } = self.validate(&dep_id, dep)? {
+                let error = format!("Instantiated model is ill-formed: {error}");
+                return Some(ModelValidation::Illformed { error });
+            }
+        }
+
+        let theory = Theory::new(ustr("_").into(), theory_def);
+        let mut elab = Elaborator::new(theory.clone(), &self.toplevel, ustr(ref_id));
+        let (ty_s, ty_v) = elab.notebook(model.notebook.formal_content());
+        let mut errors = elab.errors().to_vec();
+        let (generated, namespace) = Model::from_ty(&self.toplevel, &theory.definition, &ty_v);
+        let result = match &generated {
+            Model::Discrete(model) => model.validate(),
+            Model::DiscreteTab(model) => model.validate(),
+            Model::ModalUnital(model) => model.validate(),
+            Model::ModalNonUnital(model) => model.validate(),
+        };
+        if let Err(errs) = result {
+            for err in errs {
+                if !errors.contains(&err) {
+                    errors.push(err);
+                }
+            }
+        }
+
+        self.toplevel
+            .declarations
+            .insert(NameSegment::Text(ustr(ref_id)), TopDecl::Type(Type::new(theory, ty_s, ty_v)));
+        self.instantiated.insert(ref_id.to_string());
+        self.namespaces.insert(ref_id.to_string(), namespace);
+
+        Some(if errors.is_empty() {
+            ModelValidation::Valid
+        } else {
+            ModelValidation::Invalid { errors }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn example(name: &str) -> Value {
+        let path =
+            format!("{}/../document-types/examples/v2/{name}.json", env!("CARGO_MANIFEST_DIR"));
+        serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn validate_example_model() {
+        let model = parse_model(&example("SEIRV")).unwrap();
+        let result = validate_model("seirv", &model, &HashMap::new());
+        assert_eq!(result, Some(ModelValidation::Valid));
+    }
+
+    #[test]
+    fn validate_invalid_model() {
+        let mut content = example("SEIRV");
+        let cells = content["notebook"]["cellContents"].as_object_mut().unwrap();
+        let mor = cells.values_mut().find(|cell| cell["content"]["tag"] == "morphism").unwrap();
+        mor["content"]["dom"] = json!(null);
+        let model = parse_model(&content).unwrap();
+        let result = validate_model("seirv", &model, &HashMap::new()).unwrap();
+        assert_eq!(result.status(), ValidationStatus::Invalid);
+    }
+
+    fn instantiating(name: &str, theory: &str, ref_id: &str) -> ModelDocumentContent {
+        let cell_id = Uuid::now_v7();
+        let content = json!({
+            "type": "model",
+            "name": name,
+            "theory": theory,
+            "version": "2",
+            "notebook": {
+                "cellOrder": [cell_id],
+                "cellContents": {
+                    cell_id.to_string(): {
+                        "tag": "formal",
+                        "id": cell_id,
+                        "content": {
+                            "tag": "instantiation",
+                            "name": "instance",
+                            "id": Uuid::now_v7(),
+                            "model": {
+                                "_id": ref_id,
+                                "_version": null,
+                                "_server": "catcolab.org",
+                                "type": "instantiation",
+                            },
+                            "specializations": [],
+                        },
+                    },
+                },
+            },
+        });
+        parse_model(&content).unwrap()
+    }
+
+    #[test]
+    fn validate_instantiations() {
+        let seirv = parse_model(&example("SEIRV")).unwrap();
+        let model = instantiating("composite", "primitive-stock-flow", "seirv");
+        let dependencies = HashMap::from([("seirv".to_string(), seirv)]);
+        let result = validate_model("composite", &model, &dependencies);
+        assert_eq!(result, Some(ModelValidation::Valid));
+
+        let result = validate_model("composite", &model, &HashMap::new()).unwrap();
+        assert_eq!(result.status(), ValidationStatus::Illformed);
+
+        let model = instantiating("cycle", "primitive-stock-flow", "cycle");
+        let dependencies = HashMap::from([("cycle".to_string(), model)]);
+        let result = validate_model("cycle", &dependencies["cycle"], &dependencies).unwrap();
+        assert_eq!(result.status(), ValidationStatus::Illformed);
+    }
+
+    #[test]
+    fn validate_unknown_theory() {
+        let mut content = example("SEIRV");
+        content["theory"] = json!("no-such-theory");
+        let model = parse_model(&content).unwrap();
+        assert_eq!(validate_model("seirv", &model, &HashMap::new()), None);
+    }
+}
@@ -16,6 +16,17 @@ use super::result::JsResult;
 #[tsify(into_wasm_abi, from_wasm_abi)]
 pub struct ODEResult(pub JsResult<ode::ODESolution, String>);
 
+/// Several solutions of ODE analyses, such as a parameter sweep or an ensemble.
+#[derive(Serialize, Deserialize, Tsify)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub struct ODESolutions(pub Vec<ode::ODESolution>);
+
+/// The result of a Kuramoto analysis, containing the solution and diagnostics of
+/// synchronization when successful.
+#[derive(Serialize, Deserialize, Tsify)]
+#[tsify(into_wasm_abi, from_wasm_abi)]
+pub struct KuramotoResult(pub JsResult<ode::KuramotoSolution, String>);
+
 /// The result of an ODE analysis including equations in LaTeX with substitutions.
 #[derive(Serialize, Deserialize, Tsify)]
 #[tsify(into_wasm_abi, from_wasm_abi)]
@@ -20,6 +20,8 @@ use catlog::dbl::{
     theory::{self as dbl_theory, ModalObOp, NonUnital, Unital},
 };
 use catlog::one::{Category as _, FgCategory, Path, QualifiedPath};
+use catlog::stdlib::analyses::ode::ODESolution;
+use catlog::stdlib::formats::drawing::{DrawableDblModel, GraphFormat, uwd_drawing};
 use catlog::tt::{
     self,
     notebook_elab::{Elaborator as ElaboratorNext, demote_modality, promote_modality},
@@ -28,6 +30,7 @@ use catlog::tt::{
 use catlog::validate::Validate;
 use catlog::zero::{NameLookup, NameSegment, Namespace, QualifiedLabel, QualifiedName};
 
+use super::analyses::ODESolutions;
 use super::result::JsResult;
 use super::theory::{DblTheory, DblTheoryBox, expect_single_name};
 use super::{model_presentation::*, notation::*, wd::*};
@@ -323,6 +326,10 @@ pub struct DblModel {
     #[wasm_bindgen(skip)]
     pub ty: Option<(tt::stx::TyS, tt::val::TyV)>,
 
+    /// The top-level declarations that the type was elaborated against.
+    #[wasm_bindgen(skip)]
+    pub toplevel: Rc<Toplevel>,
+
     /// The namespace for the objects.
     #[wasm_bindgen(skip)]
     pub ob_namespace: Namespace,
@@ -343,6 +350,7 @@ impl DblModel {
         Self {
             model,
             ty: None,
+            toplevel: Default::default(),
             ob_namespace: Namespace::new_for_uuid(),
             mor_namespace: Namespace::new_for_uuid(),
         }
@@ -353,6 +361,7 @@ impl DblModel {
         Self {
             model,
             ty: self.ty.clone(),
+            toplevel: self.toplevel.clone(),
             ob_namespace: self.ob_namespace.clone(),
             mor_namespace: self.mor_namespace.clone(),
         }
@@ -664,9 +673,54 @@ impl DblModel {
     pub fn composition_pattern(&self) -> Option<UWD> {
         self.ty
             .as_ref()
-            .and_then(|(_, ty_v)| tt::wd::record_to_uwd(ty_v))
+            .and_then(|(_, ty_v)| tt::wd::record_to_uwd(&self.toplevel, ty_v))
             .map(|uwd| serialize_uwd(&uwd))
     }
+
+    /// Renders the model as a graph drawing in DOT or Mermaid.
+    #[wasm_bindgen(js_name = "renderGraph")]
+    pub fn render_graph(&self, format: GraphFormat) -> String {
+        let drawing = all_the_same!(match &self.model {
+            DblModelBox::[Discrete, DiscreteTab, ModalUnital, ModalNonUnital](model) => {
+                model.to_drawing(&self.ob_namespace, &self.mor_namespace)
+            }
+        });
+        drawing.render(format)
+    }
+
+    /// Renders the composition pattern (UWD) of the model in DOT or Mermaid.
+    #[wasm_bindgen(js_name = "renderCompositionPattern")]
+    pub fn render_composition_pattern(&self, format: GraphFormat) -> Option<String> {
+        self.ty
+            .as_ref()
+            .and_then(|(_, ty_v)| tt::wd::record_to_uwd(&self.toplevel, ty_v))
+            .map(|uwd| uwd_drawing(&uwd).render(format))
+    }
+
+    /// Exports a solution of an ODE analysis of the model as CSV.
+    #[wasm_bindgen(js_name = "solutionCsv")]
+    pub fn solution_csv(&self, solution: ODESolution) -> String {
+        solution.to_data_frame(&self.ob_namespace).to_csv()
+    }
+
+    /// Exports a solution of an ODE analysis of the model in Arrow IPC format.
+    #[wasm_bindgen(js_name = "solutionArrow")]
+    pub fn solution_arrow(&self, solution: ODESolution) -> Vec<u8> {
+        solution.to_data_frame(&self.ob_namespace).to_arrow_ipc()
+    }
+
+    /// Exports several solutions of ODE analyses of the model as CSV in long format.
+    #[wasm_bindgen(js_name = "ensembleCsv")]
+    pub fn ensemble_csv(&self, solutions: ODESolutions) -> String {
+        ODESolution::ensemble_data_frame(&solutions.0, &self.ob_namespace).to_csv()
+    }
+
+    /// Exports several solutions of ODE analyses of the model in Arrow IPC format,
+    /// in long format.
+    #[wasm_bindgen(js_name = "ensembleArrow")]
+    pub fn ensemble_arrow(&self, solutions: ODESolutions) -> Vec<u8> {
+        ODESolution::ensemble_data_frame(&solutions.0, &self.ob_namespace).to_arrow_ipc()
+    }
 }
 
 /// Result of validating a model of a double theory.
@@ -753,6 +807,7 @@ pub fn elaborate_model(
         Ok(DblModel {
             model: model.into(),
             ty: Some((ty_s, ty_v)),
+            toplevel: Rc::new(instantiated.toplevel.clone()),
             ob_namespace: namespace.clone(),
             mor_namespace: namespace.clone(),
         })
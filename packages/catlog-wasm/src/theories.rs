@@ -144,6 +144,89 @@ impl ThSignedCategory {
         motifs(&negative_loop, model, options)
     }
 
+    /// Find all feedback loops in a model, ranked by length.
+    #[wasm_bindgen(js_name = "feedbackLoops")]
+    pub fn feedback_loops(
+        &self,
+        model: &DblModel,
+    ) -> Result<Vec<analyses::feedback_loops::FeedbackLoop>, String> {
+        Ok(analyses::feedback_loops::FeedbackLoopAnalysis::default()
+            .add_positive(Path::Id(name("Object")))
+            .add_negative(name("Negative").into())
+            .find_loops(model.discrete()?))
+    }
+
+    /// Find the clusters of objects that lie on common feedback loops, in
+    /// topological order.
+    #[wasm_bindgen(js_name = "feedbackClusters")]
+    pub fn feedback_clusters(
+        &self,
+        model: &DblModel,
+    ) -> Result<Vec<analyses::feedback_loops::FeedbackCluster>, String> {
+        Ok(analyses::feedback_loops::feedback_clusters(model.discrete()?))
+    }
+
+    /// Proposes rules for scaffolding a stock-flow diagram from a model.
+    #[wasm_bindgen(js_name = "proposeStockFlow")]
+    pub fn propose_stock_flow(
+        &self,
+        model: &DblModel,
+    ) -> Result<theory_morphisms::StockFlowScaffold, String> {
+        Ok(theory_morphisms::StockFlowScaffold::propose(model.discrete()?))
+    }
+
+    /// Migrates a causal loop diagram to a stock-flow diagram using the rules.
+    ///
+    /// Fails unless the target theory has the stock and link types used by the
+    /// migrated diagram.
+    #[wasm_bindgen(js_name = "toStockFlow")]
+    pub fn to_stock_flow(
+        boxed: &DblModel,
+        scaffold: theory_morphisms::StockFlowScaffold,
+        th: &DblTheory,
+    ) -> Result<DblModel, String> {
+        let model = scaffold
+            .migrate(boxed.discrete()?, th.discrete_tab()?.clone())
+            .map_err(|err| err.to_string())?;
+        Ok(boxed.replace_box(model.into()))
+    }
+
+    /// Simulate the Boolean network derived from a model and find its attractors.
+    #[wasm_bindgen(js_name = "booleanNetwork")]
+    pub fn boolean_network(
+        &self,
+        model: &DblModel,
+        data: analyses::boolean_network::BooleanNetworkProblemData,
+    ) -> Result<analyses::boolean_network::BooleanNetworkResult, String> {
+        analyses::boolean_network::BooleanNetworkBuilder::new(name("Object"))
+            .add_positive(Path::Id(name("Object")))
+            .add_negative(name("Negative").into())
+            .boolean_network_analysis(model.discrete()?.as_ref(), data)
+            .map_err(|err| err.to_string())
+    }
+
+    /// Decide whether every linear system with the signs of a model is stable.
+    #[wasm_bindgen(js_name = "signStability")]
+    pub fn sign_stability(&self, model: &DblModel) -> Result<analyses::ode::SignStability, String> {
+        Ok(analyses::ode::SignedCoefficientBuilder::new(name("Object"))
+            .add_positive(Path::Id(name("Object")))
+            .add_negative(name("Negative").into())
+            .sign_stability_analysis(model.discrete()?))
+    }
+
+    /// Predict the responses of a model to press perturbations by loop analysis.
+    #[wasm_bindgen(js_name = "pressPerturbation")]
+    pub fn press_perturbation(
+        &self,
+        model: &DblModel,
+    ) -> Result<analyses::ode::PressPerturbationResult, String> {
+        analyses::ode::SignedCoefficientBuilder::new(name("Object"))
+            .add_positive(Path::Id(name("Object")))
+            .add_negative(name("Negative").into())
+            .press_perturbation_analysis(model.discrete()?)
+            .map_err(|err| err.to_string())
+    }
+
     /// Simulate the Lotka-Volterra system derived from a model.
     #[wasm_bindgen(js_name = "lotkaVolterra")]
     pub fn lotka_volterra(
@@ -179,6 +262,19 @@ impl ThSignedCategory {
                 .into(),
         ))
     }
+
+    /// Analyzes the spectrum of the linear ODE system derived from a model.
+    #[wasm_bindgen(js_name = "linearSpectral")]
+    pub fn linear_spectral(
+        &self,
+        model: &DblModel,
+        data: &analyses::ode::LinearSpectralProblemData,
+    ) -> Result<analyses::ode::LinearSpectralResult, String> {
+        Ok(analyses::ode::SignedCoefficientBuilder::new(name("Object"))
+            .add_positive(Path::Id(name("Object")))
+            .add_negative(name("Negative").into())
+            .linear_spectral_analysis(model.discrete()?, data))
+    }
 }
 
 /// The theory of delayable signed categories.
@@ -241,6 +337,55 @@ impl ThDelayableSignedCategory {
         motifs(&delayed_negative_loop, model, options)
     }
 
+    /// Find all feedback loops in a model, fast or delayed, ranked by length.
+    #[wasm_bindgen(js_name = "feedbackLoops")]
+    pub fn feedback_loops(
+        &self,
+        model: &DblModel,
+    ) -> Result<Vec<analyses::feedback_loops::FeedbackLoop>, String> {
+        Ok(analyses::feedback_loops::FeedbackLoopAnalysis::default()
+            .add_positive(Path::Id(name("Object")))
+            .add_negative(name("Negative").into())
+            .add_delayed_positive(name("Slow").into())
+            .add_delayed_positive(name("PositiveSlow").into())
+            .add_delayed_negative(Path::pair(name("Negative"), name("Slow")))
+            .add_delayed_negative(name("NegativeSlow").into())
+            .find_loops(model.discrete()?))
+    }
+
+    /// Find the clusters of objects that lie on common feedback loops, in
+    /// topological order.
+    #[wasm_bindgen(js_name = "feedbackClusters")]
+    pub fn feedback_clusters(
+        &self,
+        model: &DblModel,
+    ) -> Result<Vec<analyses::feedback_loops::FeedbackCluster>, String> {
+        Ok(analyses::feedback_loops::feedback_clusters(model.discrete()?))
+    }
+
+    /// Simulate the linear DDE system derived from a model.
+    #[wasm_bindgen(js_name = "linearDDE")]
+    pub fn linear_dde(
+        &self,
+        model: &DblModel,
+        data: analyses::ode::LinearDDEProblemData,
+    ) -> Result<ODEResult, String> {
+        Ok(ODEResult(
+            analyses::ode::DelayedCoefficientBuilder::new(name("Object"))
+                .add_positive(Path::Id(name("Object")))
+                .add_negative(name("Negative").into())
+                .add_delayed_positive(name("Slow").into())
+                .add_delayed_positive(name("PositiveSlow").into())
+                .add_delayed_negative(Path::pair(name("Negative"), name("Slow")))
+                .add_delayed_negative(name("NegativeSlow").into())
+                .linear_dde_analysis(model.discrete()?, data)
+                .map_err(|err| err.to_string())?
+                .solve_with_defaults()
+                .map_err(|err| err.to_string())
+                .into(),
+        ))
+    }
+
     /// Sigma migrates a delayable signed category to a signed category.
     #[wasm_bindgen(js_name = "toSignedCategory")]
     pub fn to_signed_category(boxed: &DblModel, th: &DblTheory) -> Result<DblModel, String> {
@@ -299,6 +444,53 @@ impl ThNullableSignedCategory {
         let indeterminate_loop = models::indeterminate_loop(self.0.clone());
         motifs(&indeterminate_loop, model, options)
     }
+
+    /// Find all feedback loops in a model, ranked by length.
+    #[wasm_bindgen(js_name = "feedbackLoops")]
+    pub fn feedback_loops(
+        &self,
+        model: &DblModel,
+    ) -> Result<Vec<analyses::feedback_loops::FeedbackLoop>, String> {
+        Ok(analyses::feedback_loops::FeedbackLoopAnalysis::default()
+            .add_positive(Path::Id(name("Object")))
+            .add_negative(name("Negative").into())
+            .add_indeterminate(name("Zero").into())
+            .find_loops(model.discrete()?))
+    }
+
+    /// Find the clusters of objects that lie on common feedback loops, in
+    /// topological order.
+    #[wasm_bindgen(js_name = "feedbackClusters")]
+    pub fn feedback_clusters(
+        &self,
+        model: &DblModel,
+    ) -> Result<Vec<analyses::feedback_loops::FeedbackCluster>, String> {
+        Ok(analyses::feedback_loops::feedback_clusters(model.discrete()?))
+    }
+
+    /// Decide whether every linear system with the signs of a model is stable.
+    #[wasm_bindgen(js_name = "signStability")]
+    pub fn sign_stability(&self, model: &DblModel) -> Result<analyses::ode::SignStability, String> {
+        Ok(analyses::ode::SignedCoefficientBuilder::new(name("Object"))
+            .add_positive(Path::Id(name("Object")))
+            .add_negative(name("Negative").into())
+            .add_indeterminate(name("Zero").into())
+            .sign_stability_analysis(model.discrete()?))
+    }
+
+    /// Predict the responses of a model to press perturbations by loop analysis.
+    #[wasm_bindgen(js_name = "pressPerturbation")]
+    pub fn press_perturbation(
+        &self,
+        model: &DblModel,
+    ) -> Result<analyses::ode::PressPerturbationResult, String> {
+        analyses::ode::SignedCoefficientBuilder::new(name("Object"))
+            .add_positive(Path::Id(name("Object")))
+            .add_negative(name("Negative").into())
+            .add_indeterminate(name("Zero").into())
+            .press_perturbation_analysis(model.discrete()?)
+            .map_err(|err| err.to_string())
+    }
 }
 
 /// The theory of categories with scalars.
@@ -390,6 +582,19 @@ impl ThCategorySignedLinks {
     ) -> Result<LatexEquations, String> {
         mass_action_equations(model, data, MassActionAnalysisLogic::StockFlow)
     }
+
+    /// Sigma migrates a category with signed links to a category with links.
+    #[wasm_bindgen(js_name = "toCategoryLinks")]
+    pub fn to_category_links(boxed: &DblModel, th: &DblTheory) -> Result<DblModel, String> {
+        let (th, mut model) = (th.discrete_tab()?, boxed.discrete_tab()?.as_ref().clone());
+        model
+            .push_forward(
+                &theory_morphisms::th_category_signed_links_to_category_links(),
+                th.clone(),
+            )
+            .map_err(|err| err.to_string())?;
+        Ok(boxed.replace_box(model.into()))
+    }
 }
 
 /// The theory of strict symmetric monoidal categories.
@@ -517,6 +722,19 @@ impl ThSignedPolynomialODE {
         polynomial_ode_simulation(model, data)
     }
 
+    /// Sigma migrates a signed polynomial ODE system to an unsigned one.
+    #[wasm_bindgen(js_name = "toPolynomialODE")]
+    pub fn to_polynomial_ode(boxed: &DblModel, th: &DblTheory) -> Result<DblModel, String> {
+        let (th, mut model) = (th.modal_nonunital()?, boxed.modal_nonunital()?.as_ref().clone());
+        model
+            .push_forward(
+                &theory_morphisms::th_signed_polynomial_ode_system_to_polynomial_ode_system(),
+                th.clone(),
+            )
+            .map_err(|err| err.to_string())?;
+        Ok(boxed.replace_box(model.into()))
+    }
+
     /// Returns the symbolic equations in LaTeX format.
     #[wasm_bindgen(js_name = "polynomialODEEquations")]
     pub fn polynomial_ode_equations(
@@ -552,16 +770,69 @@ impl ThPowerSystem {
         data: &analyses::ode::KuramotoProblemData,
     ) -> Result<ODEResult, String> {
         Ok(ODEResult(
-            analyses::ode::KuramotoAnalysis::new(name("Bus"))
-                // Should we distinguish between lines and transformers?
-                .add_link_type(Path::empty(name("Bus")))
-                .add_link_type(Path::single(name("Passive")))
+            kuramoto_analysis()
                 .build_system(model.discrete()?, data)
                 .solve_with_defaults()
                 .map_err(|err| format!("{err:?}"))
                 .into(),
         ))
     }
+
+    /// Simulates the Kuramoto system derived from a model and diagnoses its
+    /// synchronization.
+    #[wasm_bindgen(js_name = "kuramotoDiagnostics")]
+    pub fn kuramoto_diagnostics(
+        &self,
+        model: &DblModel,
+        data: &analyses::ode::KuramotoProblemData,
+    ) -> Result<KuramotoResult, String> {
+        Ok(KuramotoResult(
+            kuramoto_analysis()
+                .simulate(model.discrete()?, data)
+                .map_err(|err| format!("{err:?}"))
+                .into(),
+        ))
+    }
+
+    /// Solves the linearized (DC) power flow for a model.
+    #[wasm_bindgen(js_name = "dcPowerFlow")]
+    pub fn dc_power_flow(
+        &self,
+        model: &DblModel,
+        data: &analyses::power_flow::PowerFlowProblemData,
+    ) -> Result<analyses::power_flow::PowerFlowResult, String> {
+        power_flow_analysis()
+            .dc_power_flow(model.discrete()?, data)
+            .map_err(|err| err.to_string())
+    }
+
+    /// Solves the full (AC) power flow for a model by the Newton-Raphson method.
+    #[wasm_bindgen(js_name = "acPowerFlow")]
+    pub fn ac_power_flow(
+        &self,
+        model: &DblModel,
+        data: &analyses::power_flow::PowerFlowProblemData,
+    ) -> Result<analyses::power_flow::PowerFlowResult, String> {
+        power_flow_analysis()
+            .ac_power_flow(model.discrete()?, data)
+            .map_err(|err| err.to_string())
+    }
+}
+
+/// Kuramoto analysis for models of power systems.
+fn kuramoto_analysis() -> analyses::ode::KuramotoAnalysis {
+    analyses::ode::KuramotoAnalysis::new(name("Bus"))
+        // Should we distinguish between lines and transformers?
+        .add_link_type(Path::empty(name("Bus")))
+        .add_link_type(Path::single(name("Passive")))
+}
+
+/// Power flow analysis for models of power systems.
+fn power_flow_analysis() -> analyses::power_flow::PowerFlowAnalysis {
+    analyses::power_flow::PowerFlowAnalysis::new(name("Bus"))
+        .add_passive_type(Path::empty(name("Bus")))
+        .add_passive_type(Path::single(name("Passive")))
+        .add_link_type(Path::single(name("Branch")))
 }
 
 #[cfg(test)]
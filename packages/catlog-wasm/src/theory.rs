@@ -246,6 +246,18 @@ impl DblTheory {
         (&self.0).try_into().map_err(|_| "Theory should be discrete".into())
     }
 
+    /// Tries to get a discrete tabulator theory.
+    pub fn discrete_tab(&self) -> Result<&Rc<theory::DiscreteTabTheory>, String> {
+        (&self.0)
+            .try_into()
+            .map_err(|_| "Theory should be a discrete tabulator theory".into())
+    }
+
+    /// Tries to get a modal non-unital double theory.
+    pub fn modal_nonunital(&self) -> Result<&Rc<theory::ModalDblTheory<NonUnital>>, String> {
+        (&self.0).try_into().map_err(|_| "Theory should be a modal theory".into())
+    }
+
     /// Tries to convert into a theory usable by DoubleTT.
     pub fn try_into_tt(&self) -> Option<tt::theory::TheoryDef> {
         match &self.0 {
@@ -1,15 +1,17 @@
 //! Command-line program to run DoubleTT in interactive mode.
 
 use catlog::tt::batch::{self, BatchOutput};
+use catlog::tt::text_gen::notebook_to_text;
 
 use notify::RecursiveMode;
 use notify_debouncer_full::new_debouncer;
 // use prelude::*;
 
-use std::io;
+use std::collections::HashMap;
 use std::path::Path;
 use std::sync::mpsc::channel;
 use std::time::Duration;
+use std::{fs, io};
 
 use clap::Parser;
 
@@ -18,11 +20,33 @@ struct Args {
     path: String,
     #[arg(short, long)]
     watch: bool,
+    /// Print the model document at the path as DoubleTT source.
+    #[arg(long)]
+    from_notebook: bool,
+    /// A model document instantiated by the converted model, referred to by
+    /// its file stem.
+    #[arg(long = "dep")]
+    deps: Vec<String>,
 }
 
 fn main() -> io::Result<()> {
     let args = Args::parse();
 
+    if args.from_notebook {
+        let read =
+            |path: &str| -> io::Result<_> { Ok(serde_json::from_str(&fs::read_to_string(path)?)?) };
+        let mut deps = HashMap::new();
+        for path in &args.deps {
+            let stem = Path::new(path).file_stem().unwrap_or_default();
+            deps.insert(stem.to_string_lossy().into_owned(), read(path)?);
+        }
+        match notebook_to_text(&read(&args.path)?, &deps) {
+            Ok(text) => print!("{text}"),
+            Err(e) => eprintln!("could not convert {}: {}", args.path, e),
+        }
+        return Ok(());
+    }
+
     batch::run(&args.path, &BatchOutput::Interactive)?;
     if args.watch {
         let (tx, rx) = channel();
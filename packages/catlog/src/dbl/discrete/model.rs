@@ -3,6 +3,7 @@
 use std::rc::Rc;
 
 use derivative::Derivative;
+use nonempty::NonEmpty;
 
 use super::theory::DiscreteDblTheory;
 use crate::dbl::{category::*, model::*, theory::DblTheory};
@@ -124,6 +125,79 @@ impl DiscreteDblModel {
         self.mor_types = std::mem::take(&mut self.mor_types).postcompose(f.mor_map());
         self.theory = new_theory;
     }
+
+    /// Migrate model backward along a map between discrete double theories.
+    ///
+    /// The migration is computed on generators. An object or morphism generator
+    /// is kept when its type has a unique preimage under the map among the
+    /// generating types and identities of the new theory, and it is dropped when
+    /// its type has no preimage. This computes the Delta migration when the map is
+    /// an inclusion closed under composition, such as the inclusion of entity
+    /// types into the theory of schemas. A generator whose type has several
+    /// preimages would have to be duplicated, which is not supported, so it is
+    /// reported as invalid, as is a morphism kept without its domain or codomain.
+    pub fn pull_back<F>(
+        &self,
+        f: &F,
+        new_theory: Rc<DiscreteDblTheory>,
+    ) -> Result<Self, NonEmpty<InvalidDblModel>>
+    where
+        F: CategoryMap<
+                DomOb = QualifiedName,
+                DomMor = QualifiedPath,
+                CodOb = QualifiedName,
+                CodMor = QualifiedPath,
+            >,
+    {
+        let ob_types: Vec<_> = new_theory.0.ob_generators().collect();
+        let mor_types: Vec<_> = (ob_types.iter().cloned().map(Path::Id))
+            .chain(new_theory.0.mor_generators().map(Path::single))
+            .collect();
+
+        let mut model = DiscreteDblModel::new(new_theory.clone());
+        let mut errors = Vec::new();
+        for x in self.ob_generators() {
+            let ob_type = self.ob_generator_type(&x);
+            let mut preimage =
+                ob_types.iter().filter(|t| f.apply_ob((*t).clone()).as_ref() == Some(&ob_type));
+            match (preimage.next(), preimage.next()) {
+                (Some(t), None) => model.add_ob(x, t.clone()),
+                (Some(_), Some(_)) => errors.push(InvalidDblModel::ObType(x)),
+                (None, _) => {}
+            }
+        }
+        for e in self.mor_generators() {
+            let mor_type = self.mor_generator_type(&e);
+            let mut preimage = mor_types.iter().filter(|t| {
+                f.apply_mor((*t).clone())
+                    .is_some_and(|m| self.theory.0.morphisms_are_equal(m, mor_type.clone()))
+            });
+            match (preimage.next(), preimage.next()) {
+                (Some(t), None) => {
+                    let (dom, cod) = (self.mor_generator_dom(&e), self.mor_generator_cod(&e));
+                    if !model.has_ob(&dom) {
+                        errors.push(InvalidDblModel::Dom(e.clone()));
+                    }
+                    if !model.has_ob(&cod) {
+                        errors.push(InvalidDblModel::Cod(e.clone()));
+                    }
+                    model.add_mor(e, dom, cod, t.clone());
+                }
+                (Some(_), Some(_)) => errors.push(InvalidDblModel::MorType(e)),
+                (None, _) => {}
+            }
+        }
+        for (lhs, rhs) in self.equations() {
+            let graph = model.generating_graph();
+            if lhs.contained_in(graph) && rhs.contained_in(graph) {
+                model.add_equation(PathEq::new(lhs, rhs));
+            }
+        }
+
+        validate::wrap_errors(errors.into_iter())?;
+        model.validate()?;
+        Ok(model)
+    }
 }
 
 impl Category for DiscreteDblModel {
@@ -344,4 +418,39 @@ mod tests {
         assert_eq!(model.ob_generator_type(&name("x")), name("Entity"));
         assert_eq!(model.mor_generator_type(&name("f")), Path::Id(name("Entity")));
     }
+
+    #[test]
+    fn pullback_migrate() {
+        let th = Rc::new(th_schema());
+        let mut model = walking_attr(th);
+        model.add_ob(name("other"), name("Entity"));
+        model.add_mor(name("f"), name("other"), name("entity"), Path::Id(name("Entity")));
+
+        let functor_data = th_category_to_schema();
+        let th_cat = Rc::new(th_category());
+        let schema_th = model.theory.clone();
+        let restricted = model
+            .pull_back(&functor_data.functor_into(&schema_th.0), th_cat.clone())
+            .unwrap();
+        assert_eq!(restricted.ob_generators().count(), 2);
+        assert_eq!(restricted.mor_generators().collect::<Vec<_>>(), vec![name("f")]);
+        assert_eq!(restricted.ob_generator_type(&name("other")), name("Object"));
+
+        let th_sgn = Rc::new(th_signed_category());
+        let th_null = Rc::new(th_nullable_signed_category());
+        let mut model = DiscreteDblModel::new(th_null.clone());
+        model.add_ob(name("x"), name("Object"));
+        model.add_mor(name("neg"), name("x"), name("x"), name("Negative").into());
+        model.add_mor(name("zero"), name("x"), name("x"), name("Zero").into());
+        let functor_data = th_signed_category_to_nullable_signed_category();
+        let signed = model.pull_back(&functor_data.functor_into(&th_null.0), th_sgn).unwrap();
+        assert_eq!(signed.mor_generators().collect::<Vec<_>>(), vec![name("neg")]);
+
+        let functor_data = th_schema_to_category();
+        let model = walking_attr(Rc::new(th_schema()));
+        let mut cat_model = model.clone();
+        cat_model.push_forward(&functor_data.functor_into(&th_cat.0), th_cat.clone());
+        let result = cat_model.pull_back(&functor_data.functor_into(&th_cat.0), model.theory);
+        assert!(result.is_err());
+    }
 }
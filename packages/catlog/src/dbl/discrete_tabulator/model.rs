@@ -177,6 +177,34 @@ impl DiscreteTabModel {
         self.tabulated(Path::single(TabEdge::Basic(f)))
     }
 
+    /// Migrate model forward along a map between discrete tabulator theories.
+    ///
+    /// Fails, leaving the model unchanged, if the map is not defined on a type
+    /// used in the model.
+    pub fn push_forward(
+        &mut self,
+        f: &DiscreteTabTheoryMap,
+        new_theory: Rc<DiscreteTabTheory>,
+    ) -> Result<(), MigrationError> {
+        let ob_types = (self.ob_types.iter())
+            .map(|(x, typ)| {
+                let typ = f.apply_ob_type(typ).ok_or_else(|| MigrationError::ObType(x.clone()))?;
+                Ok((x, typ))
+            })
+            .collect::<Result<_, _>>()?;
+        let mor_types = (self.mor_types.iter())
+            .map(|(e, typ)| {
+                let typ =
+                    f.apply_mor_type(typ).ok_or_else(|| MigrationError::MorType(e.clone()))?;
+                Ok((e, typ))
+            })
+            .collect::<Result<_, _>>()?;
+        self.ob_types = ob_types;
+        self.mor_types = mor_types;
+        self.theory = new_theory;
+        Ok(())
+    }
+
     /// Iterates over failures of model to be well defined.
     pub fn iter_invalid(&self) -> impl Iterator<Item = InvalidDblModel> + '_ {
         type Invalid = InvalidDblModel;
@@ -400,7 +428,7 @@ mod tests {
 
     use super::*;
     use crate::{
-        stdlib::{models::*, theories::*},
+        stdlib::{models::*, theories::*, theory_morphisms::*},
         zero::name,
     };
 
@@ -424,4 +452,28 @@ mod tests {
             link : y -> f : Link"#]];
         expected.assert_eq(&format!("{model}"));
     }
+
+    #[test]
+    fn pushforward_migrate() {
+        let mut model = negative_backward_link(Rc::new(th_category_signed_links()));
+        let new_th = Rc::new(th_category_links());
+        let functor_data = th_category_signed_links_to_category_links();
+        model.push_forward(&functor_data, new_th.clone()).unwrap();
+        assert!(model.validate().is_ok());
+        assert_eq!(model.mor_generator_type(&name("link")), name("Link").into());
+        assert_eq!(
+            model.mor_generator_type(&name("f")),
+            TabMorType::Hom(Box::new(name("Object").into()))
+        );
+
+        // Unmapped link types are an error, not a generator without a type.
+        let mut model = negative_backward_link(Rc::new(th_category_signed_links()));
+        let mut unmapped = functor_data;
+        unmapped.mor_type_map = [(name("Link"), name("Link").into())].into_iter().collect();
+        assert!(matches!(
+            model.push_forward(&unmapped, new_th),
+            Err(MigrationError::MorType(link)) if link == name("link")
+        ));
+        assert_eq!(model.mor_generator_type(&name("link")), name("NegativeLink").into());
+    }
 }
@@ -3,7 +3,7 @@
 use std::hash::Hash;
 use std::ops::Range;
 
-use derive_more::From;
+use derive_more::{Constructor, From};
 use ref_cast::RefCast;
 
 use crate::dbl::{category::*, graph::ProedgeGraph, tree::DblTree};
@@ -174,6 +174,39 @@ impl DiscreteTabTheory {
     }
 }
 
+/// A map between discrete tabulator theories.
+///
+/// Such a map is determined by where it sends the generating object and morphism
+/// types. It is extended to all types by preserving hom types and tabulators.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Constructor)]
+pub struct DiscreteTabTheoryMap {
+    /// Mapping on generating object types.
+    pub ob_type_map: HashColumn<QualifiedName, TabObType>,
+
+    /// Mapping on generating morphism types.
+    pub mor_type_map: HashColumn<QualifiedName, TabMorType>,
+}
+
+impl DiscreteTabTheoryMap {
+    /// Applies the map to an object type, if it is defined there.
+    pub fn apply_ob_type(&self, ob_type: &TabObType) -> Option<TabObType> {
+        match ob_type {
+            TabObType::Basic(v) => self.ob_type_map.apply_to_ref(v),
+            TabObType::Tabulator(m) => {
+                Some(TabObType::Tabulator(Box::new(self.apply_mor_type(m)?)))
+            }
+        }
+    }
+
+    /// Applies the map to a morphism type, if it is defined there.
+    pub fn apply_mor_type(&self, mor_type: &TabMorType) -> Option<TabMorType> {
+        match mor_type {
+            TabMorType::Basic(e) => self.mor_type_map.apply_to_ref(e),
+            TabMorType::Hom(x) => Some(TabMorType::Hom(Box::new(self.apply_ob_type(x)?))),
+        }
+    }
+}
+
 /// Graph of objects and projection arrows in discrete tabulator theory.
 #[derive(RefCast)]
 #[repr(transparent)]
@@ -96,6 +96,45 @@ impl<Kind: DblTheoryKind> ModalDblModel<Kind> {
         }
     }
 
+    /// Migrate model forward along a map between modal double theories.
+    ///
+    /// Fails, leaving the model unchanged, if the map is not defined on a type or
+    /// object operation used in the model.
+    pub fn push_forward(
+        &mut self,
+        f: &ModalDblTheoryMap,
+        new_theory: Rc<ModalDblTheory<Kind>>,
+    ) -> Result<(), MigrationError> {
+        let ob_types = (self.ob_types.iter())
+            .map(|(x, typ)| {
+                let typ = f.apply_ob_type(typ).ok_or_else(|| MigrationError::ObType(x.clone()))?;
+                Ok((x, typ))
+            })
+            .collect::<Result<_, _>>()?;
+        let mor_types = (self.mor_types.iter())
+            .map(|(e, typ)| {
+                let typ =
+                    f.apply_mor_type(typ).ok_or_else(|| MigrationError::MorType(e.clone()))?;
+                Ok((e, typ))
+            })
+            .collect::<Result<_, _>>()?;
+        let push_ob = |(e, ob): (QualifiedName, &ModalOb)| {
+            let ob =
+                ob.clone().push_forward(f).map_err(|op| MigrationError::ObOp(e.clone(), op))?;
+            Ok((e, ob))
+        };
+        let generators = &self.mor_generators;
+        let src_map = generators.src_map.iter().map(push_ob).collect::<Result<_, _>>()?;
+        let tgt_map = generators.tgt_map.iter().map(push_ob).collect::<Result<_, _>>()?;
+
+        self.ob_types = ob_types;
+        self.mor_types = mor_types;
+        self.mor_generators.src_map = src_map;
+        self.mor_generators.tgt_map = tgt_map;
+        self.theory = new_theory;
+        Ok(())
+    }
+
     /// Gets the computing generating the morphisms of the model.
     fn computad(&self) -> Computad<'_, ModalOb, ModalDblModelObs<Kind>, QualifiedName> {
         Computad::new(ModalDblModelObs::ref_cast(self), &self.mor_generators)
@@ -482,6 +521,23 @@ impl ModalOp {
 }
 
 impl ModalOb {
+    /// Migrates the object forward along a map between modal double theories.
+    ///
+    /// Returns the first object operation on which the map is not defined, if any.
+    fn push_forward(self, f: &ModalDblTheoryMap) -> Result<Self, QualifiedName> {
+        match self {
+            ModalOb::Generator(id) => Ok(ModalOb::Generator(id)),
+            ModalOb::App(ob, op_id) => {
+                let image = f.apply_ob_op(&op_id).ok_or(op_id)?;
+                Ok(ModalOb::App(Box::new(ob.push_forward(f)?), image))
+            }
+            ModalOb::List(list_type, vec) => {
+                let vec: Result<Vec<_>, _> = vec.into_iter().map(|ob| ob.push_forward(f)).collect();
+                Ok(ModalOb::List(list_type, vec?))
+            }
+        }
+    }
+
     /// Extracts an object generator or nothing.
     pub fn generator(self) -> Option<QualifiedName> {
         match self {
@@ -604,7 +660,7 @@ mod tests {
 
     use super::*;
     use crate::dbl::theory::DblTheory;
-    use crate::stdlib::{models::*, theories::*};
+    use crate::stdlib::{models::*, theories::*, theory_morphisms::*};
     use crate::zero::name;
     use crate::{dbl::tree::DblNode, one::tree::OpenTree};
 
@@ -737,4 +793,46 @@ mod tests {
             recover : I -> R : Hom Object"#]];
         expected.assert_eq(&format!("{model}"));
     }
+
+    #[test]
+    fn pushforward_migrate() {
+        let ob_type = ModeApp::new(name("Object"));
+        let mut model = ModalDblModel::new(Rc::new(th_lax_monoidal_category()));
+        model.add_ob(name("x"), ob_type.clone());
+        model.add_ob(name("y"), ob_type.clone());
+        let pair = ModalOb::List(List::Plain, vec![name("x").into(), name("y").into()]);
+        let dom = ModalOb::App(pair.into(), name("tensor"));
+        model.add_mor(name("f"), dom.clone(), name("x").into(), ShortPath::Zero(ob_type.clone()));
+
+        let new_th = Rc::new(th_monoidal_category());
+        let functor_data = th_lax_monoidal_category_to_monoidal_category();
+        model.push_forward(&functor_data, new_th.clone()).unwrap();
+        assert!(model.validate().is_ok());
+        assert_eq!(model.get_dom(&name("f")), Some(&dom));
+
+        // Unmapped object operations are an error, not a dropped boundary.
+        let mut unmapped = functor_data;
+        unmapped.ob_op_map = Default::default();
+        assert!(matches!(
+            model.push_forward(&unmapped, new_th),
+            Err(MigrationError::ObOp(f, op)) if f == name("f") && op == name("tensor")
+        ));
+        assert_eq!(model.get_dom(&name("f")), Some(&dom));
+
+        let state = ModeApp::new(name("State"));
+        let mut model = ModalDblModel::new(Rc::new(th_signed_polynomial_ode_system()));
+        model.add_ob(name("x"), state.clone());
+        let sym_list = ModalOb::List(List::Symmetric, vec![name("x").into()]);
+        let negative = ShortPath::One(ModeApp::new(name("NegativeContribution")));
+        model.add_mor(name("decay"), sym_list, name("x").into(), negative);
+
+        let new_th = Rc::new(th_polynomial_ode_system());
+        let functor_data = th_signed_polynomial_ode_system_to_polynomial_ode_system();
+        model.push_forward(&functor_data, new_th).unwrap();
+        assert!(model.validate().is_ok());
+        assert_eq!(
+            model.mor_generator_type(&name("decay")),
+            ShortPath::One(ModeApp::new(name("Contribution")))
+        );
+    }
 }
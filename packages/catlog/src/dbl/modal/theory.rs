@@ -24,7 +24,7 @@ use std::iter::repeat_n;
 use std::marker::PhantomData;
 
 use derivative::Derivative;
-use derive_more::From;
+use derive_more::{Constructor, From};
 use indexmap::IndexMap;
 use ref_cast::RefCast;
 
@@ -758,3 +758,46 @@ impl<Kind: DblTheoryKind> ModalDblTheory<Kind> {
         self.pro_composites.insert((fst, snd), composite);
     }
 }
+
+/// A map between modal double theories.
+///
+/// Such a map is determined by where it sends the generating object types,
+/// morphism types, and object operations. It is extended to all types by
+/// preserving modalities. Object operations are sent to generating object
+/// operations, which suffices to migrate models, since objects in a model only
+/// refer to generating object operations.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Constructor)]
+pub struct ModalDblTheoryMap {
+    /// Mapping on generating object types.
+    pub ob_type_map: HashColumn<QualifiedName, ModalObType>,
+
+    /// Mapping on generating morphism types.
+    pub mor_type_map: HashColumn<QualifiedName, ModalMorType>,
+
+    /// Mapping on generating object operations.
+    pub ob_op_map: HashColumn<QualifiedName, QualifiedName>,
+}
+
+impl ModalDblTheoryMap {
+    /// Applies the map to an object type, if it is defined there.
+    pub fn apply_ob_type(&self, ob_type: &ModalObType) -> Option<ModalObType> {
+        let image = self.ob_type_map.apply_to_ref(&ob_type.arg)?;
+        Some(image.apply_all(ob_type.modalities.iter().copied()))
+    }
+
+    /// Applies the map to a morphism type, if it is defined there.
+    pub fn apply_mor_type(&self, mor_type: &ModalMorType) -> Option<ModalMorType> {
+        match mor_type {
+            ShortPath::Zero(x) => Some(ShortPath::Zero(self.apply_ob_type(x)?)),
+            ShortPath::One(e) => {
+                let image = self.mor_type_map.apply_to_ref(&e.arg)?;
+                Some(image.apply_all(e.modalities.iter().copied()))
+            }
+        }
+    }
+
+    /// Applies the map to a generating object operation, if it is defined there.
+    pub fn apply_ob_op(&self, op: &QualifiedName) -> Option<QualifiedName> {
+        self.ob_op_map.apply_to_ref(op)
+    }
+}
@@ -35,6 +35,7 @@
 use derivative::Derivative;
 use nonempty::NonEmpty;
 use std::rc::Rc;
+use thiserror::Error;
 
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
@@ -333,6 +334,25 @@ pub enum InvalidModelEqn {
     MorType,
 }
 
+/// An error in migrating a model forward along a map between double theories.
+///
+/// Migration fails when the map is not defined on something used in the model.
+#[derive(Debug, Error)]
+pub enum MigrationError {
+    /// The map is not defined on the type of an object generator.
+    #[error("Theory map is not defined on the type of object `{0}`")]
+    ObType(QualifiedName),
+
+    /// The map is not defined on the type of a morphism generator.
+    #[error("Theory map is not defined on the type of morphism `{0}`")]
+    MorType(QualifiedName),
+
+    /// The map is not defined on an object operation in the boundary of a
+    /// morphism generator.
+    #[error("Theory map is not defined on operation `{1}` used by morphism `{0}`")]
+    ObOp(QualifiedName, QualifiedName),
+}
+
 impl From<InvalidPathEq> for InvalidModelEqn {
     fn from(err: InvalidPathEq) -> Self {
         match err {
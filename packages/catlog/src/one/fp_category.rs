@@ -11,7 +11,9 @@
 //! In general, this congruence relation---the word problem for finitely presented
 //! categories---cannot be decided, though in practice it often can be. The data
 //! structure in this module uses [e-graphs](https://en.wikipedia.org/wiki/E-graph)
-//! to check for equivalence of paths under the congruence.
+//! to check for equivalence of paths under the congruence. To compute normal forms
+//! of paths or enumerate hom-sets, see the [`word_problem`](super::word_problem)
+//! module.
 
 use std::cell::RefCell;
 use std::fmt::Debug;
@@ -385,6 +385,277 @@ where
     Ok(ToposortData::new(finish_stack, cycles))
 }
 
+/// Computes the strongly connected components of a finite graph.
+///
+/// Two vertices are in the same **strongly connected component** if each is
+/// reachable from the other. The components are returned in topological order,
+/// meaning that every edge between distinct components goes from an earlier
+/// component to a later one.
+///
+/// Uses Tarjan's algorithm ([Tarjan 1972](crate::refs::TarjanSCC)), which runs in
+/// linear time.
+pub fn strongly_connected_components<G>(graph: &G) -> Vec<Vec<G::V>>
+where
+    G: FinGraph,
+    G::V: Hash,
+{
+    let mut tarjan = Tarjan {
+        graph,
+        index: HashMap::new(),
+        lowlink: HashMap::new(),
+        stack: Vec::new(),
+        on_stack: HashSet::new(),
+        components: Vec::new(),
+    };
+    for v in graph.vertices() {
+        if !tarjan.index.contains_key(&v) {
+            tarjan.visit(v);
+        }
+    }
+    // Tarjan's algorithm finds components in reverse topological order.
+    tarjan.components.reverse();
+    tarjan.components
+}
+
+/// State of Tarjan's algorithm for strongly connected components.
+///
+/// The depth-first search is performed with an explicit stack, rather than by
+/// recursion, so that large graphs such as state spaces do not overflow the call
+/// stack.
+struct Tarjan<'a, G: FinGraph> {
+    graph: &'a G,
+    index: HashMap<G::V, usize>,
+    lowlink: HashMap<G::V, usize>,
+    stack: Vec<G::V>,
+    on_stack: HashSet<G::V>,
+    components: Vec<Vec<G::V>>,
+}
+
+impl<G> Tarjan<'_, G>
+where
+    G: FinGraph,
+    G::V: Hash,
+{
+    fn visit(&mut self, root: G::V) {
+        let mut call_stack = vec![self.discover(root)];
+        while let Some((v, neighbors)) = call_stack.last_mut() {
+            if let Some(w) = neighbors.pop() {
+                if !self.index.contains_key(&w) {
+                    let frame = self.discover(w);
+                    call_stack.push(frame);
+                } else if self.on_stack.contains(&w) {
+                    let low = self.lowlink[v].min(self.index[&w]);
+                    self.lowlink.insert(v.clone(), low);
+                }
+                continue;
+            }
+
+            let v = v.clone();
+            call_stack.pop();
+            if let Some((parent, _)) = call_stack.last() {
+                let low = self.lowlink[parent].min(self.lowlink[&v]);
+                self.lowlink.insert(parent.clone(), low);
+            }
+            if self.lowlink[&v] == self.index[&v] {
+                let mut component = Vec::new();
+                while let Some(w) = self.stack.pop() {
+                    self.on_stack.remove(&w);
+                    let is_root = w == v;
+                    component.push(w);
+                    if is_root {
+                        break;
+                    }
+                }
+                component.reverse();
+                self.components.push(component);
+            }
+        }
+    }
+
+    fn discover(&mut self, v: G::V) -> (G::V, Vec<G::V>) {
+        let i = self.index.len();
+        self.index.insert(v.clone(), i);
+        self.lowlink.insert(v.clone(), i);
+        self.stack.push(v.clone());
+        self.on_stack.insert(v.clone());
+        let mut neighbors: Vec<_> = self.graph.out_neighbors(&v).collect();
+        neighbors.reverse();
+        (v, neighbors)
+    }
+}
+
+/// The condensation of a graph, obtained by contracting each strongly connected
+/// component to a vertex.
+///
+/// The condensation is always acyclic. Its vertices are indices into the list of
+/// components and its edges are those edges of the original graph that go between
+/// distinct components.
+#[derive(Clone, Debug)]
+pub struct Condensation<V, E> {
+    /// Strongly connected components, in topological order.
+    pub components: Vec<Vec<V>>,
+
+    /// Map from vertices of the original graph to indices of their components.
+    pub component_index: HashMap<V, usize>,
+
+    /// The condensed graph.
+    pub graph: HashGraph<usize, E>,
+}
+
+impl<V: Eq + Hash, E> Condensation<V, E> {
+    /// Are the two vertices in the same strongly connected component?
+    pub fn same_component(&self, v: &V, w: &V) -> bool {
+        self.component_index.get(v) == self.component_index.get(w)
+    }
+}
+
+/// Computes the condensation of a finite graph.
+pub fn condensation<G>(graph: &G) -> Condensation<G::V, G::E>
+where
+    G: FinGraph,
+    G::V: Hash,
+    G::E: Hash,
+{
+    let components = strongly_connected_components(graph);
+    let component_index: HashMap<_, _> = components
+        .iter()
+        .enumerate()
+        .flat_map(|(i, component)| component.iter().map(move |v| (v.clone(), i)))
+        .collect();
+    let mut condensed: HashGraph<usize, G::E> = Default::default();
+    condensed.add_vertices(0..components.len());
+    for e in graph.edges() {
+        let (i, j) = (component_index[&graph.src(&e)], component_index[&graph.tgt(&e)]);
+        if i != j {
+            condensed.add_edge(e, i, j);
+        }
+    }
+    Condensation {
+        components,
+        component_index,
+        graph: condensed,
+    }
+}
+
+/// Enumerates all elementary cycles in a finite graph.
+///
+/// An **elementary cycle** is a nonempty path whose source and target coincide and
+/// which visits no vertex twice. Since graphs may have parallel edges, cycles
+/// through the same vertices along different edges are regarded as distinct. Each
+/// cycle is returned once, starting at its earliest vertex in the iteration order
+/// of the graph's vertices.
+///
+/// Uses Johnson's algorithm ([Johnson 1975](crate::refs::JohnsonCycles)), which
+/// runs in time `O((V + E)(C + 1))`, where `C` is the number of cycles.
+pub fn elementary_cycles<G>(graph: &G) -> Vec<Path<G::V, G::E>>
+where
+    G: FinGraph,
+    G::V: Hash,
+{
+    let vertices: Vec<_> = graph.vertices().collect();
+    let index: HashMap<_, _> = vertices.iter().enumerate().map(|(i, v)| (v.clone(), i)).collect();
+    let out_edges: Vec<Vec<_>> = vertices
+        .iter()
+        .map(|v| graph.out_edges(v).map(|e| (index[&graph.tgt(&e)], e)).collect())
+        .collect();
+
+    let mut cycles = Vec::new();
+    for start in 0..vertices.len() {
+        let mut search = CycleSearch {
+            out_edges: &out_edges,
+            start,
+            component: strong_component_from(&out_edges, start),
+            blocked: vec![false; vertices.len()],
+            blocked_by: vec![Vec::new(); vertices.len()],
+            stack: Vec::new(),
+            cycles: &mut cycles,
+        };
+        search.circuit(start);
+    }
+    cycles
+}
+
+/// State of the search for cycles through a fixed start vertex.
+struct CycleSearch<'a, V, E> {
+    out_edges: &'a [Vec<(usize, E)>],
+    start: usize,
+    component: Vec<bool>,
+    blocked: Vec<bool>,
+    blocked_by: Vec<Vec<usize>>,
+    stack: Vec<E>,
+    cycles: &'a mut Vec<Path<V, E>>,
+}
+
+impl<V, E: Clone> CycleSearch<'_, V, E> {
+    fn circuit(&mut self, v: usize) -> bool {
+        let mut found = false;
+        self.blocked[v] = true;
+        let out_edges = self.out_edges;
+        for (w, e) in out_edges[v].iter() {
+            if !self.component[*w] {
+                continue;
+            }
+            self.stack.push(e.clone());
+            if *w == self.start {
+                self.cycles.push(Path::from_vec(self.stack.clone()).unwrap());
+                found = true;
+            } else if !self.blocked[*w] && self.circuit(*w) {
+                found = true;
+            }
+            self.stack.pop();
+        }
+        if found {
+            self.unblock(v);
+        } else {
+            for (w, _) in out_edges[v].iter().filter(|(w, _)| self.component[*w]) {
+                if !self.blocked_by[*w].contains(&v) {
+                    self.blocked_by[*w].push(v);
+                }
+            }
+        }
+        found
+    }
+
+    fn unblock(&mut self, v: usize) {
+        self.blocked[v] = false;
+        for w in std::mem::take(&mut self.blocked_by[v]) {
+            if self.blocked[w] {
+                self.unblock(w);
+            }
+        }
+    }
+}
+
+/// Computes the strongly connected component of a vertex in the subgraph induced
+/// by that vertex and all later ones.
+fn strong_component_from<E>(out_edges: &[Vec<(usize, E)>], start: usize) -> Vec<bool> {
+    let n = out_edges.len();
+    let mut in_neighbors = vec![Vec::new(); n];
+    for (v, edges) in out_edges.iter().enumerate().skip(start) {
+        for (w, _) in edges.iter().filter(|(w, _)| *w >= start) {
+            in_neighbors[*w].push(v);
+        }
+    }
+    let reach = |neighbors: &dyn Fn(usize) -> Vec<usize>| {
+        let mut reached = vec![false; n];
+        let mut stack = vec![start];
+        reached[start] = true;
+        while let Some(v) = stack.pop() {
+            for w in neighbors(v) {
+                if !reached[w] {
+                    reached[w] = true;
+                    stack.push(w);
+                }
+            }
+        }
+        reached
+    };
+    let forward =
+        reach(&|v| out_edges[v].iter().map(|(w, _)| *w).filter(|w| *w >= start).collect());
+    let backward = reach(&|v| in_neighbors[v].clone());
+    forward.into_iter().zip(backward).map(|(f, b)| f && b).collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::GraphElem::*;
@@ -530,4 +801,57 @@ mod tests {
         dfs.traverse(&g, 0);
         assert_eq!(discovered_order.iter().collect::<HashSet<_>>(), [0, 1].iter().collect());
     }
+
+    #[test]
+    fn find_elementary_cycles() {
+        assert!(elementary_cycles(&SkelGraph::triangle()).is_empty());
+
+        let mut g: HashGraph<char, &str> = Default::default();
+        g.add_vertices(['x', 'y', 'z']);
+        g.add_edge("xx", 'x', 'x');
+        g.add_edge("xy", 'x', 'y');
+        g.add_edge("yx", 'y', 'x');
+        g.add_edge("yx2", 'y', 'x');
+        g.add_edge("yz", 'y', 'z');
+        g.add_edge("zx", 'z', 'x');
+        let mut cycles: Vec<_> = elementary_cycles(&g)
+            .into_iter()
+            .map(|path| {
+                // Rotate each cycle to start at `x`, which every cycle visits.
+                let mut edges: Vec<_> = path.into_iter().collect();
+                let i = edges.iter().position(|e| e.starts_with('x')).unwrap();
+                edges.rotate_left(i);
+                edges
+            })
+            .collect();
+        cycles.sort();
+        assert_eq!(
+            cycles,
+            vec![vec!["xx"], vec!["xy", "yx"], vec!["xy", "yx2"], vec!["xy", "yz", "zx"]]
+        );
+    }
+
+    #[test]
+    fn find_strong_components() {
+        let g = SkelGraph::triangle();
+        assert_eq!(strongly_connected_components(&g), vec![vec![0], vec![1], vec![2]]);
+
+        let mut g: HashGraph<char, &str> = Default::default();
+        g.add_vertices(['a', 'b', 'c', 'd']);
+        g.add_edge("ab", 'a', 'b');
+        g.add_edge("ba", 'b', 'a');
+        g.add_edge("bc", 'b', 'c');
+        g.add_edge("cd", 'c', 'd');
+        g.add_edge("dc", 'd', 'c');
+        g.add_edge("dd", 'd', 'd');
+        let cond = condensation(&g);
+        let mut components = cond.components.clone();
+        components.iter_mut().for_each(|c| c.sort());
+        assert_eq!(components, vec![vec!['a', 'b'], vec!['c', 'd']]);
+        assert!(cond.same_component(&'a', &'b'));
+        assert!(!cond.same_component(&'b', &'c'));
+        assert_eq!(cond.graph.edges().collect::<Vec<_>>(), vec!["bc"]);
+        assert_eq!(cond.graph.src(&"bc"), 0);
+        assert_eq!(cond.graph.tgt(&"bc"), 1);
+    }
 }
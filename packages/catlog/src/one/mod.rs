@@ -9,6 +9,7 @@ pub mod graph_algorithms;
 pub mod path;
 pub mod tree;
 pub mod tree_algorithms;
+pub mod word_problem;
 
 pub use self::category::*;
 pub use self::fp_category::*;
@@ -0,0 +1,495 @@
+//! Word problem for finitely presented categories via rewriting.
+//!
+//! An [`FpCategory`] can check whether two given paths are equivalent by
+//! saturating an e-graph, but that does not produce canonical representatives of
+//! morphisms, nor does it say what the morphisms of the category are. This module
+//! solves the word problem in the stronger sense by *Knuth-Bendix completion*
+//! ([Book & Otto 1993](crate::refs::StringRewriting), Section 2.4), adapted from
+//! strings to paths in a graph. The path equations are oriented into rewriting
+//! rules that decrease paths in the shortlex order, then critical pairs between
+//! the rules are resolved until the rewriting system is confluent.
+//!
+//! When completion succeeds, every morphism in the presented category has a unique
+//! *normal form*, the irreducible path in its equivalence class. The irreducible
+//! paths are those that avoid the left-hand sides of all rules, which makes it
+//! possible to decide whether the category is finite and to enumerate its
+//! hom-sets. Since the word problem is undecidable in general, completion might not
+//! terminate, so it is cut off after a given number of rules.
+
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+
+use thiserror::Error;
+
+use super::{fp_category::FpCategory, graph::*, path::*};
+
+/// A complete rewriting system for a finitely presented category.
+///
+/// Produced by [`FpCategory::complete`]. Internally, vertices and edges are
+/// referred to by their indices in sorted order, which also determines the
+/// shortlex order on paths.
+#[derive(Clone, Debug)]
+pub struct RewritingSystem<V, E> {
+    vertices: Vec<V>,
+    edges: Vec<E>,
+    vertex_index: HashMap<V, usize>,
+    edge_index: HashMap<E, usize>,
+    src: Vec<usize>,
+    tgt: Vec<usize>,
+    rules: Vec<Rule>,
+    next_rule_id: usize,
+}
+
+/// A rewriting rule between sequences of edge indices.
+///
+/// The left-hand side is always nonempty and greater than the right-hand side in
+/// the shortlex order.
+#[derive(Clone, Debug)]
+struct Rule {
+    id: usize,
+    lhs: Vec<usize>,
+    rhs: Vec<usize>,
+}
+
+/// State of the automaton recognizing irreducible paths.
+///
+/// Consists of the current vertex and the last few edges traversed, enough to
+/// detect the left-hand side of any rule ending at the next edge.
+type State = (usize, Vec<usize>);
+
+/// An error in Knuth-Bendix completion.
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum CompletionError {
+    /// Completion did not finish before exceeding the limit on rules.
+    #[error("Completion did not finish within {0} rewriting rules")]
+    TooManyRules(usize),
+}
+
+impl<V, E> FpCategory<V, E>
+where
+    V: Eq + Clone + Hash + Ord,
+    E: Eq + Clone + Hash + Ord,
+{
+    /// Completes the presentation to a confluent rewriting system.
+    ///
+    /// Runs Knuth-Bendix completion on the path equations, giving up once there
+    /// are more than `max_rules` rewriting rules. The presentation is assumed to be
+    /// valid.
+    pub fn complete(&self, max_rules: usize) -> Result<RewritingSystem<V, E>, CompletionError> {
+        let mut system = RewritingSystem::new(self.generators());
+        let equations = self
+            .equations()
+            .map(|eq| (system.edge_indices(&eq.lhs), system.edge_indices(&eq.rhs)))
+            .collect();
+        system.complete(equations, max_rules)?;
+        Ok(system)
+    }
+}
+
+impl<V, E> RewritingSystem<V, E>
+where
+    V: Eq + Clone + Hash + Ord,
+    E: Eq + Clone + Hash + Ord,
+{
+    fn new(graph: &impl FinGraph<V = V, E = E>) -> Self {
+        let mut vertices: Vec<_> = graph.vertices().collect();
+        vertices.sort();
+        let mut edges: Vec<_> = graph.edges().collect();
+        edges.sort();
+        let vertex_index: HashMap<_, _> =
+            vertices.iter().enumerate().map(|(i, v)| (v.clone(), i)).collect();
+        let edge_index = edges.iter().enumerate().map(|(i, e)| (e.clone(), i)).collect();
+        let src = edges.iter().map(|e| vertex_index[&graph.src(e)]).collect();
+        let tgt = edges.iter().map(|e| vertex_index[&graph.tgt(e)]).collect();
+        Self {
+            vertices,
+            edges,
+            vertex_index,
+            edge_index,
+            src,
+            tgt,
+            rules: Vec::new(),
+            next_rule_id: 0,
+        }
+    }
+
+    /// Iterates over the rewriting rules, as path equations oriented left to right.
+    pub fn rules(&self) -> impl Iterator<Item = PathEq<V, E>> + '_ {
+        self.rules.iter().map(|rule| {
+            let src = self.src[rule.lhs[0]];
+            PathEq::new(self.path(src, &rule.lhs), self.path(src, &rule.rhs))
+        })
+    }
+
+    /// Computes the normal form of a path, its canonical representative.
+    pub fn normal_form(&self, path: &Path<V, E>) -> Path<V, E> {
+        let src = self.path_src(path);
+        self.path(src, &self.reduce(self.edge_indices(path)))
+    }
+
+    /// Are the two paths equivalent in the presented category?
+    ///
+    /// Assumes that the paths are parallel.
+    pub fn morphisms_are_equal(&self, path1: &Path<V, E>, path2: &Path<V, E>) -> bool {
+        self.reduce(self.edge_indices(path1)) == self.reduce(self.edge_indices(path2))
+    }
+
+    /// Is the path in normal form?
+    pub fn is_normal(&self, path: &Path<V, E>) -> bool {
+        let edges = self.edge_indices(path);
+        self.rules.iter().all(|rule| find(&edges, &rule.lhs).is_none())
+    }
+
+    /// Is the presented category finite?
+    ///
+    /// The category is finite if and only if there are finitely many irreducible
+    /// paths, which holds if and only if the automaton recognizing them is acyclic.
+    pub fn is_finite(&self) -> bool {
+        let mut visiting = HashSet::new();
+        let mut finished = HashSet::new();
+        (0..self.vertices.len())
+            .all(|v| self.is_acyclic_from((v, Vec::new()), &|_| true, &mut visiting, &mut finished))
+    }
+
+    /// Enumerates the hom-set between two objects.
+    ///
+    /// Returns the normal forms of all morphisms from `x` to `y`, sorted in
+    /// shortlex order, or `None` if there are infinitely many such morphisms.
+    pub fn hom_set(&self, x: &V, y: &V) -> Option<Vec<Path<V, E>>> {
+        let (x, y) = (self.vertex_index[x], self.vertex_index[y]);
+        let start: State = (x, Vec::new());
+
+        // Find the states reachable from the source that can also reach the target.
+        let mut reachable = HashSet::from([start.clone()]);
+        let mut stack = vec![start.clone()];
+        let mut preds: HashMap<State, Vec<State>> = HashMap::new();
+        while let Some(state) = stack.pop() {
+            for (_, next) in self.transitions(&state) {
+                preds.entry(next.clone()).or_default().push(state.clone());
+                if reachable.insert(next.clone()) {
+                    stack.push(next);
+                }
+            }
+        }
+        let mut relevant: HashSet<State> =
+            reachable.into_iter().filter(|state| state.0 == y).collect();
+        let mut stack: Vec<_> = relevant.iter().cloned().collect();
+        while let Some(state) = stack.pop() {
+            for prev in preds.get(&state).into_iter().flatten() {
+                if relevant.insert(prev.clone()) {
+                    stack.push(prev.clone());
+                }
+            }
+        }
+
+        // The hom-set is infinite just when there is a cycle through these states.
+        let is_relevant = |state: &State| relevant.contains(state);
+        if !self.is_acyclic_from(
+            start.clone(),
+            &is_relevant,
+            &mut HashSet::new(),
+            &mut HashSet::new(),
+        ) {
+            return None;
+        }
+
+        let mut paths = Vec::new();
+        let mut stack = vec![(start, Vec::new())];
+        while let Some((state, edges)) = stack.pop() {
+            if state.0 == y {
+                paths.push(edges.clone());
+            }
+            for (e, next) in self.transitions(&state) {
+                if is_relevant(&next) {
+                    let mut edges = edges.clone();
+                    edges.push(e);
+                    stack.push((next, edges));
+                }
+            }
+        }
+        paths.sort_by(|p, q| shortlex(p, q));
+        Some(paths.into_iter().map(|edges| self.path(x, &edges)).collect())
+    }
+
+    /// Runs Knuth-Bendix completion, starting from the given equations.
+    fn complete(
+        &mut self,
+        mut pending: Vec<(Vec<usize>, Vec<usize>)>,
+        max_rules: usize,
+    ) -> Result<(), CompletionError> {
+        let mut checked = HashSet::new();
+        loop {
+            while let Some((lhs, rhs)) = pending.pop() {
+                let (lhs, rhs) = (self.reduce(lhs), self.reduce(rhs));
+                match shortlex(&lhs, &rhs) {
+                    Ordering::Equal => {}
+                    Ordering::Greater => self.add_rule(lhs, rhs, &mut pending),
+                    Ordering::Less => self.add_rule(rhs, lhs, &mut pending),
+                }
+                if self.rules.len() > max_rules {
+                    return Err(CompletionError::TooManyRules(max_rules));
+                }
+            }
+            for rule1 in &self.rules {
+                for rule2 in &self.rules {
+                    if checked.insert((rule1.id, rule2.id)) {
+                        pending.extend(critical_pairs(rule1, rule2));
+                    }
+                }
+            }
+            if pending.is_empty() {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Adds a rule, inter-reducing it with the existing rules.
+    ///
+    /// Rules whose left-hand side becomes reducible are turned back into equations.
+    fn add_rule(
+        &mut self,
+        lhs: Vec<usize>,
+        rhs: Vec<usize>,
+        pending: &mut Vec<(Vec<usize>, Vec<usize>)>,
+    ) {
+        let (removed, kept) = std::mem::take(&mut self.rules)
+            .into_iter()
+            .partition(|rule| find(&rule.lhs, &lhs).is_some());
+        self.rules = kept;
+        pending.extend(removed.into_iter().map(|rule: Rule| (rule.lhs, rule.rhs)));
+
+        self.rules.push(Rule { id: self.next_rule_id, lhs, rhs });
+        self.next_rule_id += 1;
+        for i in 0..self.rules.len() {
+            let rhs = std::mem::take(&mut self.rules[i].rhs);
+            self.rules[i].rhs = self.reduce(rhs);
+        }
+    }
+
+    /// Rewrites a sequence of edges to normal form.
+    fn reduce(&self, mut edges: Vec<usize>) -> Vec<usize> {
+        'rewrite: loop {
+            for rule in &self.rules {
+                if let Some(i) = find(&edges, &rule.lhs) {
+                    edges.splice(i..i + rule.lhs.len(), rule.rhs.iter().copied());
+                    continue 'rewrite;
+                }
+            }
+            return edges;
+        }
+    }
+
+    /// Lists the transitions out of a state of the automaton for irreducible
+    /// paths, as pairs of the edge traversed and the next state.
+    fn transitions(&self, state: &State) -> Vec<(usize, State)> {
+        let (v, suffix) = state;
+        let window = self.rules.iter().map(|rule| rule.lhs.len()).max().unwrap_or(1) - 1;
+        (0..self.edges.len())
+            .filter(|e| self.src[*e] == *v)
+            .filter_map(|e| {
+                let mut word = suffix.clone();
+                word.push(e);
+                if self.rules.iter().any(|rule| word.ends_with(&rule.lhs)) {
+                    return None;
+                }
+                let word = word.split_off(word.len().saturating_sub(window));
+                Some((e, (self.tgt[e], word)))
+            })
+            .collect()
+    }
+
+    /// Checks that no cycle is reachable from a state, through allowed states.
+    fn is_acyclic_from(
+        &self,
+        state: State,
+        allowed: &impl Fn(&State) -> bool,
+        visiting: &mut HashSet<State>,
+        finished: &mut HashSet<State>,
+    ) -> bool {
+        if finished.contains(&state) {
+            return true;
+        }
+        if !visiting.insert(state.clone()) {
+            return false;
+        }
+        for (_, next) in self.transitions(&state) {
+            if allowed(&next) && !self.is_acyclic_from(next, allowed, visiting, finished) {
+                return false;
+            }
+        }
+        visiting.remove(&state);
+        finished.insert(state);
+        true
+    }
+
+    fn edge_indices(&self, path: &Path<V, E>) -> Vec<usize> {
+        path.iter().map(|e| self.edge_index[e]).collect()
+    }
+
+    fn path_src(&self, path: &Path<V, E>) -> usize {
+        match path {
+            Path::Id(v) => self.vertex_index[v],
+            Path::Seq(edges) => self.src[self.edge_index[edges.first()]],
+        }
+    }
+
+    fn path(&self, src: usize, edges: &[usize]) -> Path<V, E> {
+        Path::from_vec(edges.iter().map(|e| self.edges[*e].clone()).collect())
+            .unwrap_or_else(|| Path::Id(self.vertices[src].clone()))
+    }
+}
+
+/// Compares two sequences of edges in the shortlex order.
+fn shortlex(edges1: &[usize], edges2: &[usize]) -> Ordering {
+    edges1.len().cmp(&edges2.len()).then_with(|| edges1.cmp(edges2))
+}
+
+/// Finds the first occurrence of a nonempty pattern in a sequence.
+fn find(edges: &[usize], pattern: &[usize]) -> Option<usize> {
+    edges.windows(pattern.len()).position(|window| window == pattern)
+}
+
+/// Computes the critical pairs of two rules.
+///
+/// These arise from overlaps of a suffix of the first left-hand side with a prefix
+/// of the second and from occurrences of the second left-hand side within the
+/// first.
+fn critical_pairs(rule1: &Rule, rule2: &Rule) -> Vec<(Vec<usize>, Vec<usize>)> {
+    let (l1, l2) = (&rule1.lhs, &rule2.lhs);
+    let mut pairs = Vec::new();
+    for k in 1..l1.len().min(l2.len() + 1) {
+        if l1[l1.len() - k..] == l2[..k] {
+            let lhs = [&rule1.rhs[..], &l2[k..]].concat();
+            let rhs = [&l1[..l1.len() - k], &rule2.rhs[..]].concat();
+            pairs.push((lhs, rhs));
+        }
+    }
+    if rule1.id != rule2.id {
+        for i in 0..(l1.len() + 1).saturating_sub(l2.len()) {
+            if l1[i..i + l2.len()] == l2[..] {
+                let rhs = [&l1[..i], &rule2.rhs[..], &l1[i + l2.len()..]].concat();
+                pairs.push((rule1.rhs.clone(), rhs));
+            }
+        }
+    }
+    pairs
+}
+
+#[cfg(test)]
+mod tests {
+    use nonempty::nonempty;
+
+    use super::super::fp_category::{sch_graph, sch_hgraph, sch_sgraph};
+    use super::*;
+    use crate::zero::{QualifiedName, name};
+
+    type Path = super::Path<QualifiedName, QualifiedName>;
+
+    #[test]
+    fn free_category() {
+        let rws = sch_graph().complete(10).unwrap();
+        assert_eq!(rws.rules().count(), 0);
+        assert!(rws.is_finite());
+        assert_eq!(
+            rws.hom_set(&name("E"), &name("V")),
+            Some(vec![Path::single(name("src")), Path::single(name("tgt"))])
+        );
+        assert_eq!(rws.hom_set(&name("V"), &name("E")), Some(vec![]));
+
+        let mut cat = FpCategory::new();
+        cat.add_ob_generator(name("x"));
+        cat.add_mor_generator(name("f"), name("x"), name("x"));
+        let rws = cat.complete(10).unwrap();
+        assert!(!rws.is_finite());
+        assert_eq!(rws.hom_set(&name("x"), &name("x")), None);
+    }
+
+    #[test]
+    fn sch_sgraph_hom_sets() {
+        let rws = sch_sgraph().complete(10).unwrap();
+        assert!(rws.is_finite());
+        assert_eq!(
+            rws.hom_set(&name("E"), &name("E")),
+            Some(vec![Path::empty(name("E")), Path::single(name("inv"))])
+        );
+        assert_eq!(
+            rws.hom_set(&name("E"), &name("V")),
+            Some(vec![Path::single(name("src")), Path::single(name("tgt"))])
+        );
+        assert_eq!(rws.hom_set(&name("V"), &name("V")), Some(vec![Path::empty(name("V"))]));
+
+        let path = Path::Seq(nonempty![name("inv"), name("inv"), name("inv"), name("src")]);
+        assert!(!rws.is_normal(&path));
+        assert_eq!(rws.normal_form(&path), Path::single(name("tgt")));
+        assert!(rws.morphisms_are_equal(&path, &Path::single(name("tgt"))));
+        assert!(!rws.morphisms_are_equal(&path, &Path::single(name("src"))));
+    }
+
+    #[test]
+    fn sch_hgraph_hom_sets() {
+        let rws = sch_hgraph().complete(10).unwrap();
+        assert!(rws.is_finite());
+        assert_eq!(
+            rws.hom_set(&name("H"), &name("V")),
+            Some(vec![Path::single(name("vert")), Path::pair(name("inv"), name("vert"))])
+        );
+    }
+
+    #[test]
+    fn critical_pairs_resolved() {
+        // The Klein four-group, whose presentation is not already complete.
+        let mut cat = FpCategory::new();
+        cat.add_ob_generator(name("x"));
+        cat.add_mor_generator(name("a"), name("x"), name("x"));
+        cat.add_mor_generator(name("b"), name("x"), name("x"));
+        cat.equate(Path::pair(name("a"), name("a")), Path::empty(name("x")));
+        cat.equate(Path::pair(name("b"), name("b")), Path::empty(name("x")));
+        cat.equate(
+            Path::Seq(nonempty![name("a"), name("b"), name("a"), name("b")]),
+            Path::empty(name("x")),
+        );
+        let rws = cat.complete(10).unwrap();
+        assert!(rws.is_finite());
+        assert_eq!(
+            rws.hom_set(&name("x"), &name("x")),
+            Some(vec![
+                Path::empty(name("x")),
+                Path::single(name("a")),
+                Path::single(name("b")),
+                Path::pair(name("a"), name("b")),
+            ])
+        );
+        assert_eq!(
+            rws.normal_form(&Path::pair(name("b"), name("a"))),
+            Path::pair(name("a"), name("b"))
+        );
+
+        // A monoid with a finite hom-set but an infinite category.
+        let mut cat = FpCategory::new();
+        cat.add_ob_generators([name("x"), name("y")]);
+        cat.add_mor_generator(name("f"), name("x"), name("x"));
+        cat.add_mor_generator(name("g"), name("x"), name("y"));
+        cat.add_mor_generator(name("h"), name("y"), name("y"));
+        cat.equate(Path::repeat_n(name("x"), name("f"), 3), Path::single(name("f")));
+        let rws = cat.complete(10).unwrap();
+        assert!(!rws.is_finite());
+        assert_eq!(rws.hom_set(&name("x"), &name("x")).map(|hom| hom.len()), Some(3));
+        assert_eq!(rws.hom_set(&name("x"), &name("y")), None);
+    }
+
+    #[test]
+    fn completion_cutoff() {
+        // The positive braid monoid on three strands has no finite complete
+        // rewriting system in the shortlex order on these generators.
+        let mut cat = FpCategory::new();
+        cat.add_ob_generator(name("x"));
+        cat.add_mor_generator(name("a"), name("x"), name("x"));
+        cat.add_mor_generator(name("b"), name("x"), name("x"));
+        cat.equate(
+            Path::Seq(nonempty![name("a"), name("b"), name("a")]),
+            Path::Seq(nonempty![name("b"), name("a"), name("b")]),
+        );
+        assert_eq!(cat.complete(20).err(), Some(CompletionError::TooManyRules(20)));
+    }
+}
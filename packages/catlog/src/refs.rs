@@ -149,3 +149,47 @@ pub const HandbookModelChecking: () = ();
 /// - [DOI:10.1088/1367-2630/aa6321](https://doi.org/10.1088/1367-2630/aa6321)
 /// - [arXiv:1612.03654](https://arxiv.org/abs/1612.03654)
 pub const NitzbonNetworkStability: () = ();
+
+/// Reference: Survey of synchronization in networks of phase oscillators.
+///
+/// F. Dörfler and F. Bullo, 2014: Synchronization in complex networks of phase
+/// oscillators: A survey.
+///
+/// - [DOI:10.1016/j.automatica.2014.04.012](https://doi.org/10.1016/j.automatica.2014.04.012)
+pub const DorflerBulloSynchronization: () = ();
+
+/// Reference: *String-rewriting systems*.
+///
+/// Ronald V. Book & Friedrich Otto, 1993. *String-rewriting systems*. Springer.
+///
+/// - [DOI:10.1007/978-1-4613-9771-7](https://doi.org/10.1007/978-1-4613-9771-7)
+pub const StringRewriting: () = ();
+
+/// Reference: Finding all the elementary circuits of a directed graph.
+///
+/// Donald B. Johnson, 1975. Finding all the elementary circuits of a directed
+/// graph. SIAM Journal on Computing.
+///
+/// - [DOI:10.1137/0204007](https://doi.org/10.1137/0204007)
+pub const JohnsonCycles: () = ();
+
+/// Reference: Depth-first search and linear graph algorithms.
+///
+/// Robert Tarjan, 1972. Depth-first search and linear graph algorithms. SIAM
+/// Journal on Computing.
+///
+/// - [DOI:10.1137/0201010](https://doi.org/10.1137/0201010)
+pub const TarjanSCC: () = ();
+
+/// Reference: When is a matrix sign stable?
+///
+/// Clark Jeffries, Victor Klee & Pauline van den Driessche, 1977. When is a
+/// matrix sign stable? Canadian Journal of Mathematics.
+pub const SignStability: () = ();
+
+/// Reference: Qualitative predictions in model ecosystems.
+///
+/// Jeffrey M. Dambacher, Hiram W. Li & Philippe A. Rossignol, 2002. Relevance of
+/// community structure in assessing indeterminacy of ecological predictions.
+/// Ecology.
+pub const LoopAnalysis: () = ();
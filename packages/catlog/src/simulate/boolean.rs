@@ -0,0 +1,406 @@
+//! Simulation of Boolean networks.
+//!
+//! A *Boolean network* is a discrete-time dynamical system whose state is a vector
+//! of Boolean variables, each of which is updated by a Boolean function of the
+//! whole state. Boolean networks are the standard logical models of gene
+//! regulatory networks, complementing continuous models given by ODEs.
+//!
+//! Under *synchronous* update, all variables are updated simultaneously, so the
+//! dynamics are deterministic. Under *asynchronous* update, one variable is
+//! updated at a time, so that each state can have several successors. In either
+//! case, the long-term behavior of the network is described by the *attractors*
+//! of its state transition graph, namely the strongly connected components that
+//! cannot be left.
+
+use thiserror::Error;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "serde-wasm")]
+use tsify::Tsify;
+
+use crate::one::FinGraph;
+use crate::one::graph::SkelGraph;
+use crate::one::graph_algorithms::strongly_connected_components;
+
+/// A Boolean expression in variables of type `Var`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "tag", content = "content"))]
+#[cfg_attr(feature = "serde-wasm", derive(Tsify))]
+#[cfg_attr(feature = "serde-wasm", tsify(into_wasm_abi, from_wasm_abi))]
+pub enum BoolExpr<Var> {
+    /// A constant.
+    Const(bool),
+
+    /// A variable.
+    Var(Var),
+
+    /// Negation of an expression.
+    Not(Box<BoolExpr<Var>>),
+
+    /// Conjunction of expressions, true when empty.
+    And(Vec<BoolExpr<Var>>),
+
+    /// Disjunction of expressions, false when empty.
+    Or(Vec<BoolExpr<Var>>),
+}
+
+impl<Var> BoolExpr<Var> {
+    /// Evaluates the expression, given the values of the variables.
+    pub fn eval(&self, value: &impl Fn(&Var) -> bool) -> bool {
+        match self {
+            BoolExpr::Const(b) => *b,
+            BoolExpr::Var(x) => value(x),
+            BoolExpr::Not(expr) => !expr.eval(value),
+            BoolExpr::And(exprs) => exprs.iter().all(|expr| expr.eval(value)),
+            BoolExpr::Or(exprs) => exprs.iter().any(|expr| expr.eval(value)),
+        }
+    }
+
+    /// Maps over the variables in the expression, possibly failing.
+    pub fn try_map<NewVar, Err>(
+        self,
+        f: &impl Fn(Var) -> Result<NewVar, Err>,
+    ) -> Result<BoolExpr<NewVar>, Err> {
+        Ok(match self {
+            BoolExpr::Const(b) => BoolExpr::Const(b),
+            BoolExpr::Var(x) => BoolExpr::Var(f(x)?),
+            BoolExpr::Not(expr) => BoolExpr::Not(Box::new(expr.try_map(f)?)),
+            BoolExpr::And(exprs) => {
+                BoolExpr::And(exprs.into_iter().map(|e| e.try_map(f)).collect::<Result<_, _>>()?)
+            }
+            BoolExpr::Or(exprs) => {
+                BoolExpr::Or(exprs.into_iter().map(|e| e.try_map(f)).collect::<Result<_, _>>()?)
+            }
+        })
+    }
+}
+
+/// Update function for a single variable of a Boolean network.
+///
+/// Variables are referred to by their indices in the network.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum UpdateFunction {
+    /// A threshold function.
+    ///
+    /// The variable becomes true when the weighted sum of its inputs exceeds the
+    /// threshold, becomes false when the sum is below the threshold, and otherwise
+    /// keeps its current value.
+    Threshold {
+        /// Pairs of input variables and their weights.
+        weights: Vec<(usize, i32)>,
+
+        /// Activation threshold.
+        threshold: i32,
+    },
+
+    /// A function given by an arbitrary Boolean expression.
+    Expr(BoolExpr<usize>),
+}
+
+impl UpdateFunction {
+    /// Evaluates the update function for variable `i` at the given state.
+    pub fn eval(&self, i: usize, state: &[bool]) -> bool {
+        match self {
+            UpdateFunction::Threshold { weights, threshold } => {
+                let sum: i32 = weights.iter().filter(|(j, _)| state[*j]).map(|(_, w)| w).sum();
+                match sum.cmp(threshold) {
+                    std::cmp::Ordering::Greater => true,
+                    std::cmp::Ordering::Less => false,
+                    std::cmp::Ordering::Equal => state[i],
+                }
+            }
+            UpdateFunction::Expr(expr) => expr.eval(&|j| state[*j]),
+        }
+    }
+}
+
+/// Scheme for updating the variables of a Boolean network.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-wasm", derive(Tsify))]
+#[cfg_attr(feature = "serde-wasm", tsify(into_wasm_abi, from_wasm_abi))]
+pub enum UpdateScheme {
+    /// Update all variables simultaneously.
+    #[default]
+    #[cfg_attr(feature = "serde", serde(rename = "synchronous"))]
+    Synchronous,
+
+    /// Update one variable at a time.
+    #[cfg_attr(feature = "serde", serde(rename = "asynchronous"))]
+    Asynchronous,
+}
+
+/// An attractor of a Boolean network.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "tag", content = "content"))]
+#[cfg_attr(feature = "serde-wasm", derive(Tsify))]
+#[cfg_attr(feature = "serde-wasm", tsify(into_wasm_abi, from_wasm_abi))]
+pub enum Attractor {
+    /// A steady state, which is its own unique successor.
+    FixedPoint(Vec<bool>),
+
+    /// A set of two or more states that is never left once entered.
+    ///
+    /// Under synchronous update, the states are listed in the order visited.
+    Cyclic(Vec<Vec<bool>>),
+}
+
+/// Error in analyzing a Boolean network.
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum BooleanNetworkError {
+    /// The state space is too large to explore exhaustively.
+    #[error("State space of {0} variables is too large to explore")]
+    TooManyVariables(usize),
+}
+
+/// A Boolean network.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BooleanNetwork {
+    functions: Vec<UpdateFunction>,
+}
+
+impl BooleanNetwork {
+    /// Maximum number of variables for which the state space is explored.
+    pub const MAX_STATE_SPACE_VARIABLES: usize = 16;
+
+    /// Creates a Boolean network from the update functions of its variables.
+    pub fn new(functions: Vec<UpdateFunction>) -> Self {
+        Self { functions }
+    }
+
+    /// Number of variables in the network.
+    pub fn len(&self) -> usize {
+        self.functions.len()
+    }
+
+    /// Is the network empty?
+    pub fn is_empty(&self) -> bool {
+        self.functions.is_empty()
+    }
+
+    /// Computes the next state under synchronous update.
+    pub fn synchronous_step(&self, state: &[bool]) -> Vec<bool> {
+        (0..self.len()).map(|i| self.functions[i].eval(i, state)).collect()
+    }
+
+    /// Computes the successors of a state under asynchronous update.
+    ///
+    /// These are the states obtained by updating a single variable whose value
+    /// changes. There are no successors when the state is a fixed point.
+    pub fn asynchronous_successors(&self, state: &[bool]) -> Vec<Vec<bool>> {
+        (0..self.len())
+            .filter(|i| self.functions[*i].eval(*i, state) != state[*i])
+            .map(|i| {
+                let mut next = state.to_vec();
+                next[i] = !next[i];
+                next
+            })
+            .collect()
+    }
+
+    /// Simulates the network for a number of steps from an initial state.
+    ///
+    /// Returns the trajectory, including the initial state. Asynchronous update
+    /// is simulated deterministically by updating the variables in round-robin
+    /// order, one per step.
+    pub fn simulate(
+        &self,
+        initial: Vec<bool>,
+        steps: usize,
+        scheme: UpdateScheme,
+    ) -> Vec<Vec<bool>> {
+        let mut trajectory = vec![initial];
+        for step in 0..steps {
+            let state = trajectory.last().unwrap();
+            let next = match scheme {
+                UpdateScheme::Synchronous => self.synchronous_step(state),
+                UpdateScheme::Asynchronous if self.is_empty() => Vec::new(),
+                UpdateScheme::Asynchronous => {
+                    let i = step % self.len();
+                    let mut next = state.clone();
+                    next[i] = self.functions[i].eval(i, state);
+                    next
+                }
+            };
+            trajectory.push(next);
+        }
+        trajectory
+    }
+
+    /// Computes the state transition graph of the network.
+    ///
+    /// The vertices of the graph are the states, encoded as bit vectors. Fixed
+    /// points have a self-loop, so that every state has a successor.
+    pub fn state_transition_graph(
+        &self,
+        scheme: UpdateScheme,
+    ) -> Result<SkelGraph, BooleanNetworkError> {
+        let n = self.len();
+        if n > Self::MAX_STATE_SPACE_VARIABLES {
+            return Err(BooleanNetworkError::TooManyVariables(n));
+        }
+        let mut graph = SkelGraph::default();
+        graph.add_vertices(1 << n);
+        for code in 0..(1 << n) {
+            let state = decode_state(code, n);
+            let successors = match scheme {
+                UpdateScheme::Synchronous => vec![self.synchronous_step(&state)],
+                UpdateScheme::Asynchronous => self.asynchronous_successors(&state),
+            };
+            if successors.is_empty() {
+                graph.add_edge(code, code);
+            }
+            for next in successors {
+                graph.add_edge(code, encode_state(&next));
+            }
+        }
+        Ok(graph)
+    }
+
+    /// Enumerates the attractors of the network.
+    ///
+    /// Explores the full state space, so fails if there are too many variables.
+    /// Attractors are returned with fixed points first, then in order of their
+    /// least state.
+    pub fn attractors(&self, scheme: UpdateScheme) -> Result<Vec<Attractor>, BooleanNetworkError> {
+        let n = self.len();
+        let graph = self.state_transition_graph(scheme)?;
+        let mut attractors: Vec<_> = strongly_connected_components(&graph)
+            .into_iter()
+            .filter(|component| {
+                component.iter().all(|v| graph.out_neighbors(v).all(|w| component.contains(&w)))
+            })
+            .map(|mut component| {
+                if component.len() == 1 {
+                    return Attractor::FixedPoint(decode_state(component[0], n));
+                }
+                component.sort();
+                if scheme == UpdateScheme::Synchronous {
+                    // Follow the cycle from its least state.
+                    let mut v = component[0];
+                    for state in component.iter_mut() {
+                        *state = v;
+                        v = graph.out_neighbors(state).next().unwrap();
+                    }
+                }
+                Attractor::Cyclic(component.into_iter().map(|v| decode_state(v, n)).collect())
+            })
+            .collect();
+        attractors.sort_by_key(|attractor| match attractor {
+            Attractor::FixedPoint(state) => (0, encode_state(state)),
+            Attractor::Cyclic(states) => (1, states.iter().map(|s| encode_state(s)).min().unwrap()),
+        });
+        Ok(attractors)
+    }
+}
+
+/// Encodes a state as a bit vector, with the first variable as the lowest bit.
+fn encode_state(state: &[bool]) -> usize {
+    state.iter().enumerate().filter(|(_, b)| **b).map(|(i, _)| 1 << i).sum()
+}
+
+/// Decodes a state from a bit vector.
+fn decode_state(code: usize, n: usize) -> Vec<bool> {
+    (0..n).map(|i| code & (1 << i) != 0).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A negative feedback loop between an activator and an inhibitor.
+    fn negative_feedback() -> BooleanNetwork {
+        BooleanNetwork::new(vec![
+            UpdateFunction::Expr(BoolExpr::Not(Box::new(BoolExpr::Var(1)))),
+            UpdateFunction::Expr(BoolExpr::Var(0)),
+        ])
+    }
+
+    #[test]
+    fn negative_feedback_dynamics() {
+        let network = negative_feedback();
+        let trajectory = network.simulate(vec![true, false], 4, UpdateScheme::Synchronous);
+        assert_eq!(
+            trajectory,
+            vec![
+                vec![true, false],
+                vec![true, true],
+                vec![false, true],
+                vec![false, false],
+                vec![true, false],
+            ]
+        );
+
+        assert_eq!(
+            network.attractors(UpdateScheme::Synchronous),
+            Ok(vec![Attractor::Cyclic(vec![
+                vec![false, false],
+                vec![true, false],
+                vec![true, true],
+                vec![false, true],
+            ])])
+        );
+        assert_eq!(network.attractors(UpdateScheme::Asynchronous).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn threshold_dynamics() {
+        // A self-activating variable, inhibited by a variable that it activates.
+        let network = BooleanNetwork::new(vec![
+            UpdateFunction::Threshold {
+                weights: vec![(0, 1), (1, -1)],
+                threshold: 0,
+            },
+            UpdateFunction::Threshold { weights: vec![(0, 1)], threshold: 0 },
+        ]);
+        assert_eq!(network.synchronous_step(&[true, false]), vec![true, true]);
+        assert_eq!(network.synchronous_step(&[false, true]), vec![false, true]);
+        assert_eq!(
+            network.attractors(UpdateScheme::Synchronous),
+            Ok(vec![
+                Attractor::FixedPoint(vec![false, false]),
+                Attractor::FixedPoint(vec![false, true]),
+                Attractor::FixedPoint(vec![true, true]),
+            ])
+        );
+    }
+
+    #[test]
+    fn toggle_switch() {
+        // Mutual inhibition, with each variable active unless inhibited.
+        let network = BooleanNetwork::new(vec![
+            UpdateFunction::Expr(BoolExpr::Not(Box::new(BoolExpr::Var(1)))),
+            UpdateFunction::Expr(BoolExpr::Not(Box::new(BoolExpr::Var(0)))),
+        ]);
+        assert_eq!(
+            network.attractors(UpdateScheme::Asynchronous),
+            Ok(vec![
+                Attractor::FixedPoint(vec![true, false]),
+                Attractor::FixedPoint(vec![false, true]),
+            ])
+        );
+        assert_eq!(
+            network.attractors(UpdateScheme::Synchronous),
+            Ok(vec![
+                Attractor::FixedPoint(vec![true, false]),
+                Attractor::FixedPoint(vec![false, true]),
+                Attractor::Cyclic(vec![vec![false, false], vec![true, true]]),
+            ])
+        );
+
+        let trajectory = network.simulate(vec![true, true], 2, UpdateScheme::Asynchronous);
+        assert_eq!(trajectory, vec![vec![true, true], vec![false, true], vec![false, true]]);
+    }
+
+    #[test]
+    fn too_many_variables() {
+        let network = BooleanNetwork::new(vec![UpdateFunction::Expr(BoolExpr::Const(true)); 17]);
+        assert_eq!(
+            network.attractors(UpdateScheme::Synchronous),
+            Err(BooleanNetworkError::TooManyVariables(17))
+        );
+    }
+}
@@ -8,5 +8,7 @@
 //! Julia. If this code does stick around it should eventually become its own crate.
 //! For now it's convenient to keep everything in the same place.
 
+pub mod boolean;
+
 #[cfg(feature = "ode")]
 pub mod ode;
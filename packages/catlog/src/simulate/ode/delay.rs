@@ -0,0 +1,243 @@
+//! Simulation of delay differential equations (DDEs).
+//!
+//! DDEs with constant delays are solved by the *method of steps*. When every delay
+//! is at least `τ`, the delayed states needed on an interval of length `τ` are
+//! already known from the previous intervals, so the DDE reduces on each interval
+//! to an ODE, which is solved by the usual means.
+
+use nalgebra::{DMatrix, DVector};
+use ode_solvers::dop_shared::{IntegrationError, SolverResult};
+use thiserror::Error;
+
+use super::{ODEProblem, ODESystem};
+
+/// A system of delay differential equations with constant delays.
+pub trait DDESystem {
+    /// Delays of the system, which should be positive.
+    fn delays(&self) -> &[f32];
+
+    /// Compute the vector field in place.
+    ///
+    /// Besides the time and current state, the vector field depends on the
+    /// *delayed states*, the states in the past at each of the delays.
+    fn vector_field(
+        &self,
+        dx: &mut DVector<f32>,
+        x: &DVector<f32>,
+        delayed: &[DVector<f32>],
+        t: f32,
+    );
+}
+
+/// Error in solving a DDE problem.
+#[derive(Debug, Error)]
+pub enum DDEError {
+    /// A delay of the system is not a positive, finite number.
+    #[error("Delay must be positive and finite, got {0}")]
+    InvalidDelay(f32),
+
+    /// The solver failed on one of the intervals.
+    #[error(transparent)]
+    Integration(#[from] IntegrationError),
+}
+
+/// A DDE problem ready to be solved.
+///
+/// The history of the system before the start time is taken to be constant, equal
+/// to the initial values.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DDEProblem<Sys> {
+    pub(crate) system: Sys,
+    pub(crate) initial_values: DVector<f32>,
+    pub(crate) start_time: f32,
+    pub(crate) end_time: f32,
+}
+
+impl<Sys> DDEProblem<Sys> {
+    /// Creates a new DDE problem.
+    pub fn new(system: Sys, initial_values: DVector<f32>) -> Self {
+        DDEProblem {
+            system,
+            initial_values,
+            start_time: 0.0,
+            end_time: 0.0,
+        }
+    }
+
+    /// Sets the start time for the problem.
+    pub fn start_time(mut self, t: f32) -> Self {
+        self.start_time = t;
+        self
+    }
+
+    /// Sets the end time for the problem.
+    pub fn end_time(mut self, t: f32) -> Self {
+        self.end_time = t;
+        self
+    }
+}
+
+impl<Sys> DDEProblem<Sys>
+where
+    Sys: DDESystem,
+{
+    /// Solves the DDE system by the method of steps.
+    ///
+    /// On each interval, the reduced ODE system is solved using the Runge-Kutta
+    /// method with the given step size, shrunk if necessary to the shortest delay.
+    /// Delayed states between the computed steps are interpolated linearly.
+    /// Returns an error if any delay is not positive and finite.
+    pub fn solve_method_of_steps(
+        &self,
+        step_size: f32,
+    ) -> Result<SolverResult<f32, DVector<f32>>, DDEError> {
+        if let Some(delay) = self.system.delays().iter().find(|d| !(d.is_finite() && **d > 0.0)) {
+            return Err(DDEError::InvalidDelay(*delay));
+        }
+        let min_delay = self.system.delays().iter().copied().fold(f32::INFINITY, f32::min);
+        let step_size = step_size.min(min_delay);
+        let steps_per_interval = ((min_delay / step_size + 1e-4).floor() as usize).max(1);
+        let total_steps = ((self.end_time - self.start_time) / step_size - 1e-4).ceil().max(0.0);
+
+        let mut result = SolverResult::default();
+        result.push(self.start_time, self.initial_values.clone());
+        let mut steps_taken = 0;
+        while steps_taken < total_steps as usize {
+            let steps = steps_per_interval.min(total_steps as usize - steps_taken);
+            let (t_out, x_out) = result.get();
+            let (t, x) = (*t_out.last().unwrap(), x_out.last().unwrap().clone());
+            let system = MethodOfSteps {
+                system: &self.system,
+                times: t_out,
+                states: x_out,
+            };
+            // End the interval half a step early, so that the solver takes
+            // exactly the given number of steps despite rounding.
+            let end_time = t + (steps as f32 - 0.5) * step_size;
+            let interval = ODEProblem::new(system, x).start_time(t).end_time(end_time);
+            let interval_result = interval.solve_rk4(step_size)?;
+            let (t_new, x_new) = interval_result.get();
+            let interval_result = SolverResult::new(t_new[1..].to_vec(), x_new[1..].to_vec());
+            result.append(interval_result);
+            steps_taken += steps;
+        }
+        Ok(result)
+    }
+}
+
+/// ODE system on a single interval in the method of steps.
+struct MethodOfSteps<'a, Sys> {
+    system: &'a Sys,
+    times: &'a [f32],
+    states: &'a [DVector<f32>],
+}
+
+impl<Sys> MethodOfSteps<'_, Sys> {
+    /// Interpolates the state at a time in the past.
+    fn state_at(&self, t: f32) -> DVector<f32> {
+        let i = self.times.partition_point(|s| *s <= t);
+        if i == 0 {
+            return self.states[0].clone();
+        }
+        if i == self.times.len() {
+            return self.states[i - 1].clone();
+        }
+        let (t0, t1) = (self.times[i - 1], self.times[i]);
+        let s = (t - t0) / (t1 - t0);
+        &self.states[i - 1] * (1.0 - s) + &self.states[i] * s
+    }
+}
+
+impl<Sys: DDESystem> ODESystem for MethodOfSteps<'_, Sys> {
+    fn vector_field(&self, dx: &mut DVector<f32>, x: &DVector<f32>, t: f32) {
+        let delayed: Vec<_> =
+            self.system.delays().iter().map(|delay| self.state_at(t - delay)).collect();
+        self.system.vector_field(dx, x, &delayed, t);
+    }
+}
+
+/// A linear system of DDEs with constant coefficients.
+///
+/// The system has the form `dx/dt = A x(t) + ∑ₖ Bₖ x(t - τₖ)`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LinearDDESystem {
+    /// Coefficient matrix `A` of the current state.
+    pub coefficients: DMatrix<f32>,
+
+    /// Delays `τₖ`, which should be positive.
+    pub delays: Vec<f32>,
+
+    /// Coefficient matrices `Bₖ` of the delayed states.
+    pub delayed_coefficients: Vec<DMatrix<f32>>,
+}
+
+impl DDESystem for LinearDDESystem {
+    fn delays(&self) -> &[f32] {
+        &self.delays
+    }
+
+    fn vector_field(
+        &self,
+        dx: &mut DVector<f32>,
+        x: &DVector<f32>,
+        delayed: &[DVector<f32>],
+        _t: f32,
+    ) {
+        dx.gemv(1.0, &self.coefficients, x, 0.0);
+        for (coefficients, x_delayed) in self.delayed_coefficients.iter().zip(delayed) {
+            dx.gemv(1.0, coefficients, x_delayed, 1.0);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn delayed_decay() {
+        // The DDE `dx/dt = -x(t-1)` with history `x = 1` has the exact solution
+        // `1 - t` on `[0,1]` and `1 - t + (t-1)²/2` on `[1,2]`.
+        let sys = LinearDDESystem {
+            coefficients: DMatrix::zeros(1, 1),
+            delays: vec![1.0],
+            delayed_coefficients: vec![DMatrix::from_element(1, 1, -1.0)],
+        };
+        let problem = DDEProblem::new(sys, DVector::from_element(1, 1.0)).end_time(2.0);
+        let result = problem.solve_method_of_steps(0.01).unwrap();
+        let (t_out, x_out) = result.get();
+        assert_eq!(t_out.len(), 201);
+        assert!((t_out[100] - 1.0).abs() < 1e-4);
+        assert!(x_out[100][0].abs() < 1e-3);
+        assert!((t_out[200] - 2.0).abs() < 1e-4);
+        assert!((x_out[200][0] + 0.5).abs() < 1e-3);
+    }
+
+    #[test]
+    fn without_delays() {
+        // With no delays, the method of steps is a single ODE solve.
+        let sys = LinearDDESystem {
+            coefficients: DMatrix::from_element(1, 1, -1.0),
+            delays: Vec::new(),
+            delayed_coefficients: Vec::new(),
+        };
+        let problem = DDEProblem::new(sys, DVector::from_element(1, 1.0)).end_time(1.0);
+        let result = problem.solve_method_of_steps(0.01).unwrap();
+        let (_, x_out) = result.get();
+        assert_eq!(x_out.len(), 101);
+        assert!((x_out[100][0] - (-1.0f32).exp()).abs() < 1e-4);
+    }
+
+    #[test]
+    fn invalid_delays() {
+        for delay in [0.0, -1.0, f32::NAN, f32::INFINITY] {
+            let sys = LinearDDESystem {
+                coefficients: DMatrix::zeros(1, 1),
+                delays: vec![delay],
+                delayed_coefficients: vec![DMatrix::from_element(1, 1, -1.0)],
+            };
+            let problem = DDEProblem::new(sys, DVector::from_element(1, 1.0)).end_time(1.0);
+            assert!(matches!(problem.solve_method_of_steps(0.01), Err(DDEError::InvalidDelay(_))));
+        }
+    }
+}
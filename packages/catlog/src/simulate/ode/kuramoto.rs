@@ -65,6 +65,96 @@ impl KuramotoSystem {
     }
 }
 
+impl KuramotoSystem {
+    /// Number of oscillators in the system.
+    pub fn num_oscillators(&self) -> usize {
+        self.forcing_params.len()
+    }
+
+    /// Computes the Kuramoto order parameter `r` of a state.
+    ///
+    /// The order parameter is the modulus of the mean of the unit phasors
+    /// `e^{iϕ_j}`. It equals one when all phases coincide and is near zero when
+    /// the phases are spread evenly around the circle.
+    pub fn order_parameter(&self, x: &DVector<f32>) -> f32 {
+        let n = self.num_oscillators();
+        if n == 0 {
+            return 0.0;
+        }
+        let phases = x.rows(0, n);
+        let re: f32 = phases.iter().map(|phi| phi.cos()).sum();
+        let im: f32 = phases.iter().map(|phi| phi.sin()).sum();
+        re.hypot(im) / n as f32
+    }
+
+    /// Computes the instantaneous angular frequencies `dϕ_i/dt` of a state.
+    pub fn frequencies(&self, x: &DVector<f32>, t: f32) -> DVector<f32> {
+        let n = self.num_oscillators();
+        match self.order {
+            KuramotoOrder::First => {
+                let mut dx = DVector::zeros(n);
+                self.vector_field(&mut dx, x, t);
+                dx
+            }
+            KuramotoOrder::Second => x.rows(n, n).into_owned(),
+        }
+    }
+
+    /// Computes the algebraic connectivity of the coupling network.
+    ///
+    /// This is the second smallest eigenvalue `λ_2` of the Laplacian matrix
+    /// `L = D - K` of the coupling matrix, which is positive if and only if the
+    /// network is connected.
+    pub fn algebraic_connectivity(&self) -> f32 {
+        let n = self.num_oscillators();
+        if n < 2 {
+            return 0.0;
+        }
+        let mut laplacian = -self.coupling_coeffs.clone();
+        for i in 0..n {
+            laplacian[(i, i)] =
+                (0..n).filter(|j| *j != i).map(|j| self.coupling_coeffs[(i, j)]).sum();
+        }
+        let mut eigenvalues: Vec<_> = laplacian.symmetric_eigenvalues().iter().copied().collect();
+        eigenvalues.sort_by(f32::total_cmp);
+        eigenvalues[1].max(0.0)
+    }
+
+    /// Estimates the critical coupling strength from the coupling spectrum.
+    ///
+    /// Returns the smallest factor `κ` such that, after scaling the coupling
+    /// matrix by any factor greater than `κ`, the sufficient condition for
+    /// phase-locking `‖Bᵀω‖_2 < λ_2(L)` reviewed in [Dörfler & Bullo
+    /// 2014](crate::refs::DorflerBulloSynchronization) is satisfied. Here `B` is
+    /// the incidence matrix of the coupling network and the inherent frequencies
+    /// are estimated as `ω_i = P_i / α_i`. A factor less than one indicates that
+    /// the system as given is expected to synchronize.
+    ///
+    /// Returns `None` when the coupling network is disconnected, in which case no
+    /// scaling of the coupling suffices.
+    pub fn critical_coupling(&self) -> Option<f32> {
+        let lambda2 = self.algebraic_connectivity();
+        if lambda2 <= f32::EPSILON {
+            return None;
+        }
+        let n = self.num_oscillators();
+        let omega = |i: usize| {
+            let alpha = self.damping_coeffs[i];
+            let p = self.forcing_params[i];
+            if alpha > 0.0 { p / alpha } else { p }
+        };
+        let mut norm_squared = 0.0;
+        for i in 0..n {
+            for j in (i + 1)..n {
+                if self.coupling_coeffs[(i, j)] > 0.0 {
+                    norm_squared += (omega(i) - omega(j)).powi(2);
+                }
+            }
+        }
+        Some(norm_squared.sqrt() / lambda2)
+    }
+}
+
 impl ODESystem for KuramotoSystem {
     fn vector_field(&self, dx: &mut DVector<f32>, x: &DVector<f32>, _t: f32) {
         match self.order {
@@ -150,6 +240,29 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn kuramoto_diagnostics() {
+        let sys = KuramotoSystem::fully_connected_homogeneous(
+            KuramotoOrder::First,
+            DVector::from_column_slice(&[1.0, -1.0, 0.0]),
+        );
+        let synced = DVector::from_element(3, 0.5);
+        assert!((sys.order_parameter(&synced) - 1.0).abs() < 1e-6);
+        let spread = DVector::from_column_slice(&[0.0, TWO_PI / 3.0, 2.0 * TWO_PI / 3.0]);
+        assert!(sys.order_parameter(&spread).abs() < 1e-6);
+        assert_eq!(sys.frequencies(&synced, 0.0), sys.forcing_params);
+
+        // The Laplacian of the complete graph on three nodes has eigenvalues 0, 3, 3.
+        assert!((sys.algebraic_connectivity() - 3.0).abs() < 1e-4);
+        // Frequency differences are 2, 1, 1, so that `‖Bᵀω‖ = √6`.
+        let critical = sys.critical_coupling().unwrap();
+        assert!((critical - 6f32.sqrt() / 3.0).abs() < 1e-4);
+
+        let mut disconnected = sys.clone();
+        disconnected.coupling_coeffs = DMatrix::zeros(3, 3);
+        assert_eq!(disconnected.critical_coupling(), None);
+    }
+
     #[test]
     fn second_order_kuramoto() {
         let sys = KuramotoSystem::fully_connected_homogeneous(
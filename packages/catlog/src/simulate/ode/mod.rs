@@ -157,8 +157,10 @@ pub(crate) fn textplot_mapped_ode_result<Sys>(
     chart.to_string()
 }
 
+pub mod delay;
 pub mod kuramoto;
 pub mod polynomial;
 
+pub use delay::*;
 pub use kuramoto::*;
 pub use polynomial::*;
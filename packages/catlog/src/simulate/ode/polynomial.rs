@@ -123,22 +123,27 @@ pub struct LatexEquation {
     pub rhs: String,
 }
 
-impl<Var, Exp> PolynomialSystem<Var, f32, Exp>
+impl<Var, Coef, Exp> PolynomialSystem<Var, Coef, Exp>
 where
     Var: Clone + Hash + Ord,
+    Coef: Clone + Into<f32>,
     Exp: Clone + Ord + Add<Output = Exp>,
 {
     /// Converts the polynomial system to a numerical one.
     ///
     /// The order of the components in the new system is given by the order of the
-    /// variables in the old one.
+    /// variables in the old one. Coefficients, such as exact
+    /// [rationals](crate::zero::Rational), are converted to floating point.
     pub fn to_numerical(&self) -> NumericalPolynomialSystem<Exp> {
         let indices: IndexMap<Var, usize> =
             self.components.keys().enumerate().map(|(i, var)| (var.clone(), i)).collect();
         let components = self
             .components
             .values()
-            .map(|poly| poly.map_variables(|var| *indices.get(var).unwrap()))
+            .map(|poly| {
+                let poly = poly.clone().extend_scalars(|coef| -> f32 { coef.into() });
+                poly.map_variables(|var| *indices.get(var).unwrap())
+            })
             .collect();
         NumericalPolynomialSystem { components }
     }
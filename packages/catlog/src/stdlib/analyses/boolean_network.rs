@@ -0,0 +1,251 @@
+//! Boolean network analysis of signed models, such as regulatory networks.
+//!
+//! Regulatory networks ([Aduddell et al 2024](crate::refs::RegNets)) are
+//! presented as signed graphs or categories. Besides continuous dynamics, they have
+//! a standard logical semantics as [Boolean networks](crate::simulate::boolean).
+//! By default, each object is updated by a threshold rule that sums its active
+//! positive and negative inputs, but the update rule for any object can also be
+//! given explicitly as a Boolean expression.
+
+use std::collections::HashMap;
+
+use indexmap::IndexMap;
+use thiserror::Error;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "serde-wasm")]
+use tsify::Tsify;
+
+use crate::dbl::model::FpDblModel;
+use crate::simulate::boolean::*;
+use crate::zero::QualifiedName;
+
+/// Data defining a Boolean network problem for a model.
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-wasm", derive(Tsify))]
+#[cfg_attr(
+    feature = "serde-wasm",
+    tsify(into_wasm_abi, from_wasm_abi, hashmap_as_object)
+)]
+pub struct BooleanNetworkProblemData {
+    /// Map from object IDs to initial values, defaulting to false.
+    #[cfg_attr(feature = "serde", serde(rename = "initialValues"))]
+    pub initial_values: HashMap<QualifiedName, bool>,
+
+    /// Map from object IDs to activation thresholds, defaulting to zero.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub thresholds: HashMap<QualifiedName, i32>,
+
+    /// Map from object IDs to update rules, overriding the threshold rule.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub rules: HashMap<QualifiedName, BoolExpr<QualifiedName>>,
+
+    /// How to update the variables.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub scheme: UpdateScheme,
+
+    /// Number of steps to simulate.
+    pub steps: usize,
+}
+
+/// Result of a Boolean network analysis.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-wasm", derive(Tsify))]
+#[cfg_attr(feature = "serde-wasm", tsify(into_wasm_abi, from_wasm_abi))]
+pub struct BooleanNetworkResult {
+    /// Object IDs, in the order of the entries of each state.
+    pub variables: Vec<QualifiedName>,
+
+    /// Simulated trajectory, starting from the initial state.
+    pub trajectory: Vec<Vec<bool>>,
+
+    /// Attractors of the network, if the state space is small enough to explore.
+    pub attractors: Option<Vec<Attractor>>,
+}
+
+/// Error in building a Boolean network from a model.
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum BooleanNetworkBuildError {
+    /// An update rule refers to an object that is not a variable of the network.
+    #[error("Update rule for `{0}` refers to unknown variable `{1}`")]
+    UnknownVariable(QualifiedName, QualifiedName),
+}
+
+/// Builder for Boolean networks derived from signed models.
+pub struct BooleanNetworkBuilder<ObType, MorType> {
+    var_ob_type: ObType,
+    positive_mor_types: Vec<MorType>,
+    negative_mor_types: Vec<MorType>,
+}
+
+impl<ObType, MorType> BooleanNetworkBuilder<ObType, MorType> {
+    /// Creates a new builder for the given object type.
+    pub fn new(var_ob_type: ObType) -> Self {
+        Self {
+            var_ob_type,
+            positive_mor_types: Vec::new(),
+            negative_mor_types: Vec::new(),
+        }
+    }
+
+    /// Adds a morphism type defining an activating interaction between objects.
+    pub fn add_positive(mut self, mor_type: MorType) -> Self {
+        self.positive_mor_types.push(mor_type);
+        self
+    }
+
+    /// Adds a morphism type defining an inhibiting interaction between objects.
+    pub fn add_negative(mut self, mor_type: MorType) -> Self {
+        self.negative_mor_types.push(mor_type);
+        self
+    }
+
+    /// Builds the Boolean network for the given model.
+    ///
+    /// Returns the network along with an ordered map from object generators to
+    /// the indices of their variables.
+    pub fn build_network(
+        &self,
+        model: &impl FpDblModel<
+            ObType = ObType,
+            MorType = MorType,
+            Ob = QualifiedName,
+            ObGen = QualifiedName,
+            MorGen = QualifiedName,
+        >,
+        thresholds: &HashMap<QualifiedName, i32>,
+        rules: &HashMap<QualifiedName, BoolExpr<QualifiedName>>,
+    ) -> Result<(BooleanNetwork, IndexMap<QualifiedName, usize>), BooleanNetworkBuildError> {
+        let ob_index: IndexMap<_, _> = model
+            .ob_generators_with_type(&self.var_ob_type)
+            .enumerate()
+            .map(|(i, x)| (x, i))
+            .collect();
+
+        let mut weights = vec![Vec::new(); ob_index.len()];
+        let signed_mor_types = (self.positive_mor_types.iter().map(|t| (t, 1)))
+            .chain(self.negative_mor_types.iter().map(|t| (t, -1)));
+        for (mor_type, sign) in signed_mor_types {
+            for mor in model.mor_generators_with_type(mor_type) {
+                let i = *ob_index.get(&model.mor_generator_dom(&mor)).unwrap();
+                let j = *ob_index.get(&model.mor_generator_cod(&mor)).unwrap();
+                weights[j].push((i, sign));
+            }
+        }
+
+        let functions = ob_index
+            .keys()
+            .zip(weights)
+            .map(|(ob, weights)| {
+                if let Some(rule) = rules.get(ob) {
+                    let index = |x: QualifiedName| {
+                        ob_index
+                            .get(&x)
+                            .copied()
+                            .ok_or_else(|| BooleanNetworkBuildError::UnknownVariable(ob.clone(), x))
+                    };
+                    Ok(UpdateFunction::Expr(rule.clone().try_map(&index)?))
+                } else {
+                    let threshold = thresholds.get(ob).copied().unwrap_or_default();
+                    Ok(UpdateFunction::Threshold { weights, threshold })
+                }
+            })
+            .collect::<Result<_, _>>()?;
+        Ok((BooleanNetwork::new(functions), ob_index))
+    }
+
+    /// Boolean network analysis for a model.
+    ///
+    /// Simulates the network from the given initial state and, when the state space
+    /// is small enough, enumerates the attractors.
+    pub fn boolean_network_analysis(
+        &self,
+        model: &impl FpDblModel<
+            ObType = ObType,
+            MorType = MorType,
+            Ob = QualifiedName,
+            ObGen = QualifiedName,
+            MorGen = QualifiedName,
+        >,
+        data: BooleanNetworkProblemData,
+    ) -> Result<BooleanNetworkResult, BooleanNetworkBuildError> {
+        let (network, ob_index) = self.build_network(model, &data.thresholds, &data.rules)?;
+        let initial = ob_index
+            .keys()
+            .map(|ob| data.initial_values.get(ob).copied().unwrap_or_default())
+            .collect();
+        Ok(BooleanNetworkResult {
+            variables: ob_index.into_keys().collect(),
+            trajectory: network.simulate(initial, data.steps, data.scheme),
+            attractors: network.attractors(data.scheme).ok(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use super::*;
+    use crate::one::Path;
+    use crate::stdlib::{models::*, theories::*};
+    use crate::zero::name;
+
+    fn builder() -> BooleanNetworkBuilder<QualifiedName, crate::one::QualifiedPath> {
+        BooleanNetworkBuilder::new(name("Object"))
+            .add_positive(Path::Id(name("Object")))
+            .add_negative(name("Negative").into())
+    }
+
+    #[test]
+    fn threshold_rules() {
+        let th = Rc::new(th_signed_category());
+        let model = negative_feedback(th);
+        let data = BooleanNetworkProblemData {
+            initial_values: [(name("x"), true)].into(),
+            steps: 2,
+            ..Default::default()
+        };
+        let result = builder().boolean_network_analysis(&model, data).unwrap();
+        assert_eq!(result.variables, vec![name("x"), name("y")]);
+        assert_eq!(result.trajectory, vec![vec![true, false], vec![true, true], vec![false, true]]);
+        assert_eq!(
+            result.attractors,
+            Some(vec![
+                Attractor::FixedPoint(vec![false, false]),
+                Attractor::FixedPoint(vec![false, true]),
+            ])
+        );
+    }
+
+    #[test]
+    fn explicit_rules() {
+        let th = Rc::new(th_signed_category());
+        let model = negative_feedback(th);
+        let data = BooleanNetworkProblemData {
+            initial_values: [(name("x"), true)].into(),
+            rules: [
+                (name("x"), BoolExpr::Not(Box::new(BoolExpr::Var(name("y"))))),
+                (name("y"), BoolExpr::Var(name("x"))),
+            ]
+            .into(),
+            steps: 4,
+            ..Default::default()
+        };
+        let result = builder().boolean_network_analysis(&model, data).unwrap();
+        assert_eq!(result.trajectory.last(), Some(&vec![true, false]));
+        assert!(matches!(
+            result.attractors.as_deref(),
+            Some([Attractor::Cyclic(states)]) if states.len() == 4
+        ));
+
+        let rules = [(name("x"), BoolExpr::Var(name("z")))].into();
+        assert_eq!(
+            builder().build_network(&model, &HashMap::new(), &rules).err(),
+            Some(BooleanNetworkBuildError::UnknownVariable(name("x"), name("z")))
+        );
+    }
+}
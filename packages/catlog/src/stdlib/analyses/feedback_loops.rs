@@ -0,0 +1,278 @@
+//! Feedback loop analysis of signed models, such as causal loop diagrams.
+
+use std::collections::HashMap;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "serde-wasm")]
+use tsify::Tsify;
+
+use crate::dbl::model::{DiscreteDblModel, FpDblModel};
+use crate::one::graph_algorithms::{condensation, elementary_cycles};
+use crate::one::{FgCategory, FinGraph, Graph, QualifiedPath};
+use crate::zero::QualifiedName;
+
+/// Sign of a feedback loop.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-wasm", derive(Tsify))]
+#[cfg_attr(feature = "serde-wasm", tsify(into_wasm_abi, from_wasm_abi))]
+pub enum LoopSign {
+    /// A positive, or reinforcing, loop.
+    #[cfg_attr(feature = "serde", serde(rename = "positive"))]
+    Positive,
+
+    /// A negative, or balancing, loop.
+    #[cfg_attr(feature = "serde", serde(rename = "negative"))]
+    Negative,
+
+    /// A loop whose sign is indeterminate.
+    #[cfg_attr(feature = "serde", serde(rename = "indeterminate"))]
+    Indeterminate,
+}
+
+impl LoopSign {
+    /// Multiplies two signs, with indeterminacy being absorbing.
+    pub fn product(self, other: Self) -> Self {
+        match (self, other) {
+            (Self::Indeterminate, _) | (_, Self::Indeterminate) => Self::Indeterminate,
+            (Self::Positive, sign) | (sign, Self::Positive) => sign,
+            (Self::Negative, Self::Negative) => Self::Positive,
+        }
+    }
+}
+
+/// A feedback loop in a model, namely an elementary cycle in its generators.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-wasm", derive(Tsify))]
+#[cfg_attr(feature = "serde-wasm", tsify(into_wasm_abi, from_wasm_abi))]
+pub struct FeedbackLoop {
+    /// Object generators visited by the loop, starting from the least one.
+    #[cfg_attr(feature = "serde", serde(rename = "obGenerators"))]
+    pub ob_generators: Vec<QualifiedName>,
+
+    /// Morphism generators traversed by the loop, in order.
+    #[cfg_attr(feature = "serde", serde(rename = "morGenerators"))]
+    pub mor_generators: Vec<QualifiedName>,
+
+    /// Sign of the loop, the product of the signs of its morphisms.
+    pub sign: LoopSign,
+
+    /// Whether any morphism in the loop is delayed.
+    pub delayed: bool,
+}
+
+impl FeedbackLoop {
+    /// Length of the loop, the number of morphisms in it.
+    pub fn len(&self) -> usize {
+        self.mor_generators.len()
+    }
+
+    /// Is the loop empty? Never true for loops found by the analysis.
+    pub fn is_empty(&self) -> bool {
+        self.mor_generators.is_empty()
+    }
+}
+
+/// Feedback loop analysis of a model of a signed theory.
+///
+/// Each morphism type of interest is assigned a sign and a delay status. The
+/// analysis then enumerates every elementary cycle in the generating graph of the
+/// model whose morphisms all have such types, using Johnson's algorithm, in
+/// contrast to motif search, which finds loops only up to a bounded length.
+#[derive(Clone, Debug, Default)]
+pub struct FeedbackLoopAnalysis {
+    mor_types: HashMap<QualifiedPath, (LoopSign, bool)>,
+}
+
+impl FeedbackLoopAnalysis {
+    /// Adds a morphism type with the given sign and delay status.
+    pub fn add_mor_type(mut self, mor_type: QualifiedPath, sign: LoopSign, delayed: bool) -> Self {
+        self.mor_types.insert(mor_type, (sign, delayed));
+        self
+    }
+
+    /// Adds a morphism type defining a positive, undelayed interaction.
+    pub fn add_positive(self, mor_type: QualifiedPath) -> Self {
+        self.add_mor_type(mor_type, LoopSign::Positive, false)
+    }
+
+    /// Adds a morphism type defining a negative, undelayed interaction.
+    pub fn add_negative(self, mor_type: QualifiedPath) -> Self {
+        self.add_mor_type(mor_type, LoopSign::Negative, false)
+    }
+
+    /// Adds a morphism type defining an interaction of indeterminate sign.
+    pub fn add_indeterminate(self, mor_type: QualifiedPath) -> Self {
+        self.add_mor_type(mor_type, LoopSign::Indeterminate, false)
+    }
+
+    /// Adds a morphism type defining a delayed positive interaction.
+    pub fn add_delayed_positive(self, mor_type: QualifiedPath) -> Self {
+        self.add_mor_type(mor_type, LoopSign::Positive, true)
+    }
+
+    /// Adds a morphism type defining a delayed negative interaction.
+    pub fn add_delayed_negative(self, mor_type: QualifiedPath) -> Self {
+        self.add_mor_type(mor_type, LoopSign::Negative, true)
+    }
+
+    /// Finds all feedback loops in a model.
+    ///
+    /// The loops are ranked from shortest to longest, with ties broken by the
+    /// names of their morphisms.
+    pub fn find_loops(&self, model: &DiscreteDblModel) -> Vec<FeedbackLoop> {
+        let mut loops: Vec<_> = elementary_cycles(model.generating_graph())
+            .into_iter()
+            .filter_map(|path| {
+                let mut mors: Vec<_> = path.into_iter().collect();
+                let mut sign = LoopSign::Positive;
+                let mut delayed = false;
+                for mor in &mors {
+                    let (mor_sign, mor_delayed) =
+                        self.mor_types.get(&model.mor_generator_type(mor))?;
+                    sign = sign.product(*mor_sign);
+                    delayed |= mor_delayed;
+                }
+
+                // Start the loop at its least object, so that it is canonical.
+                let start = (0..mors.len())
+                    .min_by_key(|i| model.mor_generator_dom(&mors[*i]))
+                    .unwrap_or_default();
+                mors.rotate_left(start);
+                Some(FeedbackLoop {
+                    ob_generators: mors.iter().map(|f| model.mor_generator_dom(f)).collect(),
+                    mor_generators: mors,
+                    sign,
+                    delayed,
+                })
+            })
+            .collect();
+        loops.sort_by(|l1, l2| (l1.len(), &l1.mor_generators).cmp(&(l2.len(), &l2.mor_generators)));
+        loops
+    }
+}
+
+/// A feedback cluster in a model.
+///
+/// A *feedback cluster* is a strongly connected component of the generating graph
+/// of the model that contains at least one loop, so that each object in the
+/// cluster influences every other, directly or indirectly.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-wasm", derive(Tsify))]
+#[cfg_attr(feature = "serde-wasm", tsify(into_wasm_abi, from_wasm_abi))]
+pub struct FeedbackCluster {
+    /// Object generators in the cluster, sorted.
+    #[cfg_attr(feature = "serde", serde(rename = "obGenerators"))]
+    pub ob_generators: Vec<QualifiedName>,
+
+    /// Morphism generators between objects in the cluster, sorted.
+    #[cfg_attr(feature = "serde", serde(rename = "morGenerators"))]
+    pub mor_generators: Vec<QualifiedName>,
+}
+
+/// Finds the feedback clusters of a model, in topological order.
+pub fn feedback_clusters(model: &DiscreteDblModel) -> Vec<FeedbackCluster> {
+    let graph = model.generating_graph();
+    let cond = condensation(graph);
+    let mut clusters: Vec<_> = cond
+        .components
+        .into_iter()
+        .map(|mut ob_generators| {
+            ob_generators.sort();
+            FeedbackCluster {
+                ob_generators,
+                mor_generators: Vec::new(),
+            }
+        })
+        .collect();
+    for f in graph.edges() {
+        let i = cond.component_index[&graph.src(&f)];
+        if i == cond.component_index[&graph.tgt(&f)] {
+            clusters[i].mor_generators.push(f);
+        }
+    }
+    clusters.retain_mut(|cluster| {
+        cluster.mor_generators.sort();
+        !cluster.mor_generators.is_empty()
+    });
+    clusters
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use super::*;
+    use crate::dbl::model::MutDblModel;
+    use crate::one::Path;
+    use crate::stdlib::{models::*, theories::*};
+    use crate::zero::name;
+
+    #[test]
+    fn signed_loops() {
+        let th = Rc::new(th_signed_category());
+        let model = DiscreteDblModel::new(th.clone());
+        let analysis = FeedbackLoopAnalysis::default()
+            .add_positive(Path::Id(name("Object")))
+            .add_negative(name("Negative").into());
+        assert!(analysis.find_loops(&model).is_empty());
+
+        let mut model = DiscreteDblModel::new(th);
+        model.add_ob(name("x"), name("Object"));
+        model.add_ob(name("y"), name("Object"));
+        model.add_ob(name("z"), name("Object"));
+        model.add_mor(name("f"), name("x"), name("y"), Path::Id(name("Object")));
+        model.add_mor(name("g"), name("y"), name("x"), name("Negative").into());
+        model.add_mor(name("h"), name("y"), name("z"), name("Negative").into());
+        model.add_mor(name("k"), name("z"), name("x"), name("Negative").into());
+        model.add_mor(name("l"), name("z"), name("z"), Path::Id(name("Object")));
+        let loops = analysis.find_loops(&model);
+        assert_eq!(loops.len(), 3);
+        assert_eq!(loops[0].mor_generators, vec![name("l")]);
+        assert_eq!(loops[0].sign, LoopSign::Positive);
+        assert_eq!(loops[1].ob_generators, vec![name("x"), name("y")]);
+        assert_eq!(loops[1].mor_generators, vec![name("f"), name("g")]);
+        assert_eq!(loops[1].sign, LoopSign::Negative);
+        assert_eq!(loops[2].mor_generators, vec![name("f"), name("h"), name("k")]);
+        assert_eq!(loops[2].sign, LoopSign::Positive);
+        assert!(loops.iter().all(|l| !l.delayed));
+
+        model.add_ob(name("w"), name("Object"));
+        model.add_mor(name("m"), name("w"), name("x"), Path::Id(name("Object")));
+        assert_eq!(
+            feedback_clusters(&model),
+            vec![FeedbackCluster {
+                ob_generators: vec![name("x"), name("y"), name("z")],
+                mor_generators: ["f", "g", "h", "k", "l"].into_iter().map(name).collect(),
+            }]
+        );
+    }
+
+    #[test]
+    fn delayed_and_indeterminate_loops() {
+        let th = Rc::new(th_delayable_signed_category());
+        let model = delayed_negative_loop(th);
+        let analysis = FeedbackLoopAnalysis::default()
+            .add_negative(name("Negative").into())
+            .add_delayed_negative(Path::pair(name("Negative"), name("Slow")));
+        assert_eq!(
+            analysis.find_loops(&model),
+            vec![FeedbackLoop {
+                ob_generators: vec![name("x")],
+                mor_generators: vec![name("loop")],
+                sign: LoopSign::Negative,
+                delayed: true,
+            }]
+        );
+
+        let th = Rc::new(th_nullable_signed_category());
+        let model = indeterminate_loop(th);
+        let analysis = FeedbackLoopAnalysis::default().add_indeterminate(name("Zero").into());
+        let loops = analysis.find_loops(&model);
+        assert_eq!(loops.len(), 1);
+        assert_eq!(loops[0].sign, LoopSign::Indeterminate);
+    }
+}
@@ -1,10 +1,15 @@
 //! Various analyses that can be performed on models.
 
+pub mod boolean_network;
+pub mod feedback_loops;
 pub(crate) mod petri;
 
 #[cfg(feature = "ode")]
 pub mod ode;
 
+#[cfg(feature = "power")]
+pub mod power_flow;
+
 pub mod reachability;
 
 #[cfg(feature = "sql")]
@@ -0,0 +1,683 @@
+//! Code generation for polynomial ODE analyses.
+//!
+//! Any [ODE analysis](ODEAnalysis) together with its symbolic [polynomial
+//! system](PolynomialSystem), such as those built by the mass-action, polynomial,
+//! Lotka-Volterra, and linear ODE analyses, can be exported as a standalone
+//! program in another language. The program defines the right-hand
+//! side of the ODE as a function, together with the parameters, initial values,
+//! and time span of the problem, so that it can be simulated with that language's
+//! own solvers.
+//!
+//! Variables are named after the labels of the corresponding objects in the
+//! model. The parameters are those of the symbolic system, such as rate
+//! coefficients of transitions, and are named after their labels, with default
+//! values taken from the problem data. Coefficients combining several parameters
+//! are written out as expressions in them.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "serde-wasm")]
+use tsify::Tsify;
+
+use super::{ODEAnalysis, Parameter};
+use crate::simulate::ode::PolynomialSystem;
+use crate::stdlib::formats::ids::IdGenerator;
+use crate::zero::{Namespace, QualifiedName, Rational};
+
+/// Target language for generated ODE code.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-wasm", derive(Tsify))]
+#[cfg_attr(feature = "serde-wasm", tsify(into_wasm_abi, from_wasm_abi))]
+pub enum CodegenLanguage {
+    /// Python, solved using NumPy and SciPy's `solve_ivp`.
+    Python,
+
+    /// Julia, solved using DifferentialEquations.jl.
+    Julia,
+
+    /// C, with no dependencies beyond the standard library.
+    C,
+}
+
+/// Reserved words that cannot be used as identifiers in some target language.
+///
+/// Includes the keywords of Python, Julia, and C, the names from `<math.h>` used
+/// by the generated C code, and the other names used by the generated code.
+const RESERVED_WORDS: &str = "
+    False None True and as assert async await break class continue def del elif
+    else except finally for from global if import in is lambda nonlocal not or
+    pass raise return try while with yield
+    abstract baremodule begin catch const do elseif end export false function isa
+    let local macro module mutable outer primitive public quote struct true type
+    using where
+    alignas alignof auto bool case char constexpr default double enum extern float
+    goto inline int long nullptr register restrict short signed sizeof static
+    static_assert switch thread_local typedef typeof union unsigned void volatile
+    INFINITY NAN pow
+    DifferentialEquations Float64 Inf NaN NamedTuple ODEProblem collect du dx
+    initial_values np nothing p parameters problem solution solve solve_ivp t tspan
+    u vector_field x x0
+";
+
+/// Product of variables or parameters, given by index-exponent pairs.
+type Factors = Vec<(usize, i32)>;
+
+/// A term of an equation: a coefficient, which is a polynomial in the
+/// parameters, times a monomial in the variables.
+struct Term {
+    coefficient: Vec<(Rational, Factors)>,
+    monomial: Factors,
+}
+
+/// A polynomial ODE problem with variables and parameters named by identifiers.
+struct NamedSystem {
+    /// Identifiers of the state variables, in order.
+    variables: Vec<String>,
+
+    /// Initial values of the state variables.
+    initial_values: Vec<f32>,
+
+    /// Identifiers and values of the parameters.
+    parameters: Vec<(String, f32)>,
+
+    /// Terms of each equation.
+    equations: Vec<Vec<Term>>,
+
+    /// Start and end times.
+    time_span: (f32, f32),
+}
+
+impl NamedSystem {
+    fn new<Sys, Id, Exp>(
+        analysis: &ODEAnalysis<Sys>,
+        system: &PolynomialSystem<QualifiedName, Parameter<Id>, Exp>,
+        parameter: impl Fn(&Id) -> (String, f32),
+        ns: &Namespace,
+    ) -> Self
+    where
+        Id: Clone + Ord,
+        Exp: Clone + Ord + Into<i32>,
+    {
+        let mut ids = IdGenerator::with_reserved(RESERVED_WORDS.split_whitespace());
+        let variables: Vec<_> =
+            system.components.keys().map(|ob| ids.fresh(&ns.label_string(ob))).collect();
+
+        let problem = &analysis.problem;
+        let initial_values = system
+            .components
+            .keys()
+            .map(|ob| {
+                analysis
+                    .variable_index
+                    .get(ob)
+                    .map(|i| problem.initial_values[*i])
+                    .unwrap_or_default()
+            })
+            .collect();
+
+        let mut parameter_index: BTreeMap<Id, usize> = BTreeMap::new();
+        let mut parameters = Vec::new();
+        let equations = system
+            .components
+            .values()
+            .map(|poly| {
+                poly.terms()
+                    .filter_map(|(coef, monomial)| {
+                        let coefficient: Vec<_> = coef
+                            .terms()
+                            .filter(|(q, _)| **q != Rational::default())
+                            .map(|(q, params)| {
+                                let factors = params
+                                    .clone()
+                                    .into_iter()
+                                    .map(|(id, exp)| {
+                                        let k =
+                                            *parameter_index.entry(id).or_insert_with_key(|id| {
+                                                let (label, value) = parameter(id);
+                                                parameters.push((ids.fresh(&label), value));
+                                                parameters.len() - 1
+                                            });
+                                        (k, exp.into())
+                                    })
+                                    .filter(|(_, exp)| *exp != 0)
+                                    .collect();
+                                (*q, factors)
+                            })
+                            .collect();
+                        let monomial = monomial
+                            .clone()
+                            .into_iter()
+                            .map(|(var, exp)| {
+                                let i = system.components.get_index_of(&var);
+                                (i.expect("Variable should have an equation"), exp.into())
+                            })
+                            .filter(|(_, exp)| *exp != 0)
+                            .collect();
+                        (!coefficient.is_empty()).then_some(Term { coefficient, monomial })
+                    })
+                    .collect()
+            })
+            .collect();
+
+        Self {
+            variables,
+            initial_values,
+            parameters,
+            equations,
+            time_span: (problem.start_time, problem.end_time),
+        }
+    }
+
+    /// Writes the right-hand side of an equation as an expression.
+    fn write_rhs(
+        &self,
+        equation: &[Term],
+        language: CodegenLanguage,
+        param: impl Fn(usize) -> String,
+        power: impl Fn(&str, i32) -> String,
+    ) -> String {
+        let product = |factors: &Factors, name: &dyn Fn(usize) -> String| -> Vec<String> {
+            factors
+                .iter()
+                .map(|(k, exp)| {
+                    let x = name(*k);
+                    if *exp == 1 { x } else { power(&x, *exp) }
+                })
+                .collect()
+        };
+        let scaled = |q: Rational, mut factors: Vec<String>| -> (bool, String) {
+            let negative = q.numer() < 0;
+            let magnitude = if negative { -q } else { q };
+            if magnitude != Rational::from_integer(1) {
+                factors.insert(0, rational(magnitude, language));
+            }
+            if factors.is_empty() {
+                factors.push(number(1.0, language));
+            }
+            (negative, factors.join(" * "))
+        };
+        let variable = |i: usize| self.variables[i].clone();
+
+        let terms = equation.iter().map(|term| {
+            let monomial = product(&term.monomial, &variable);
+            match term.coefficient.as_slice() {
+                [(q, params)] => {
+                    let mut factors = product(params, &param);
+                    factors.extend(monomial);
+                    scaled(*q, factors)
+                }
+                coefficient => {
+                    let coefficient = sum(
+                        coefficient.iter().map(|(q, params)| scaled(*q, product(params, &param))),
+                        language,
+                    );
+                    let factors = std::iter::once(format!("({coefficient})")).chain(monomial);
+                    scaled(Rational::from_integer(1), factors.collect())
+                }
+            }
+        });
+        sum(terms, language)
+    }
+
+    fn to_python(&self) -> String {
+        let number = |x: f32| number(x, CodegenLanguage::Python);
+        let mut out = String::new();
+        writeln!(out, "import numpy as np").unwrap();
+        writeln!(out, "from scipy.integrate import solve_ivp").unwrap();
+        writeln!(out).unwrap();
+        writeln!(out, "parameters = {{").unwrap();
+        for (name, value) in self.parameters.iter() {
+            writeln!(out, "    \"{name}\": {},", number(*value)).unwrap();
+        }
+        writeln!(out, "}}").unwrap();
+        writeln!(out).unwrap();
+        writeln!(out, "initial_values = {{").unwrap();
+        for (var, value) in self.variables.iter().zip(self.initial_values.iter()) {
+            writeln!(out, "    \"{var}\": {},", number(*value)).unwrap();
+        }
+        writeln!(out, "}}").unwrap();
+        writeln!(out).unwrap();
+        let (start, end) = self.time_span;
+        writeln!(out, "tspan = ({}, {})", number(start), number(end)).unwrap();
+        writeln!(out).unwrap();
+        writeln!(out).unwrap();
+        writeln!(out, "def vector_field(t, x, p=parameters):").unwrap();
+        if !self.variables.is_empty() {
+            writeln!(out, "    {}, = x", self.variables.join(", ")).unwrap();
+        }
+        writeln!(out, "    return np.array([").unwrap();
+        for equation in self.equations.iter() {
+            let rhs = self.write_rhs(
+                equation,
+                CodegenLanguage::Python,
+                |k| format!("p[\"{}\"]", self.parameters[k].0),
+                |var, exp| format!("{var}**{exp}"),
+            );
+            writeln!(out, "        {rhs},").unwrap();
+        }
+        writeln!(out, "    ])").unwrap();
+        writeln!(out).unwrap();
+        writeln!(out).unwrap();
+        writeln!(out, "if __name__ == \"__main__\":").unwrap();
+        writeln!(out, "    x0 = np.array(list(initial_values.values()))").unwrap();
+        writeln!(out, "    solution = solve_ivp(vector_field, tspan, x0)").unwrap();
+        out
+    }
+
+    fn to_julia(&self) -> String {
+        let number = |x: f32| number(x, CodegenLanguage::Julia);
+        let named_tuple = |pairs: Vec<(&String, f32)>| {
+            let fields: Vec<_> = pairs
+                .into_iter()
+                .map(|(name, value)| format!("{name} = {}", number(value)))
+                .collect();
+            match fields.len() {
+                0 => "NamedTuple()".to_string(),
+                1 => format!("({},)", fields[0]),
+                _ => format!("(\n    {},\n)", fields.join(",\n    ")),
+            }
+        };
+        let mut out = String::new();
+        writeln!(out, "using DifferentialEquations").unwrap();
+        writeln!(out).unwrap();
+        let params = self.parameters.iter().map(|(name, value)| (name, *value)).collect();
+        writeln!(out, "parameters = {}", named_tuple(params)).unwrap();
+        writeln!(out).unwrap();
+        let initial = self.variables.iter().zip(self.initial_values.iter().copied()).collect();
+        writeln!(out, "initial_values = {}", named_tuple(initial)).unwrap();
+        writeln!(out).unwrap();
+        let (start, end) = self.time_span;
+        writeln!(out, "tspan = ({}, {})", number(start), number(end)).unwrap();
+        writeln!(out).unwrap();
+        writeln!(out, "function vector_field!(du, u, p, t)").unwrap();
+        if !self.variables.is_empty() {
+            writeln!(out, "    {}, = u", self.variables.join(", ")).unwrap();
+        }
+        for (i, equation) in self.equations.iter().enumerate() {
+            let rhs = self.write_rhs(
+                equation,
+                CodegenLanguage::Julia,
+                |k| format!("p.{}", self.parameters[k].0),
+                |var, exp| format!("{var}^{exp}"),
+            );
+            writeln!(out, "    du[{}] = {rhs}", i + 1).unwrap();
+        }
+        writeln!(out, "    return nothing").unwrap();
+        writeln!(out, "end").unwrap();
+        writeln!(out).unwrap();
+        writeln!(
+            out,
+            "problem = ODEProblem(vector_field!, collect(Float64, initial_values), tspan, parameters)"
+        )
+        .unwrap();
+        writeln!(out, "solution = solve(problem)").unwrap();
+        out
+    }
+
+    fn to_c(&self) -> String {
+        let number = |x: f32| number(x, CodegenLanguage::C);
+        let list = |items: Vec<String>| {
+            if items.is_empty() {
+                "{0}".to_string()
+            } else {
+                format!("{{{}}}", items.join(", "))
+            }
+        };
+        let quoted = |names: Vec<&String>| names.into_iter().map(|s| format!("\"{s}\"")).collect();
+        let numbers = |values: Vec<f32>| values.into_iter().map(number).collect();
+
+        let mut out = String::new();
+        writeln!(out, "#include <math.h>").unwrap();
+        writeln!(out).unwrap();
+        writeln!(out, "#define NUM_VARIABLES {}", self.variables.len()).unwrap();
+        writeln!(out, "#define NUM_PARAMETERS {}", self.parameters.len()).unwrap();
+        writeln!(out).unwrap();
+        writeln!(
+            out,
+            "static const char *const variable_names[] = {};",
+            list(quoted(self.variables.iter().collect()))
+        )
+        .unwrap();
+        writeln!(
+            out,
+            "static const double initial_values[] = {};",
+            list(numbers(self.initial_values.clone()))
+        )
+        .unwrap();
+        writeln!(
+            out,
+            "static const char *const parameter_names[] = {};",
+            list(quoted(self.parameters.iter().map(|(name, _)| name).collect()))
+        )
+        .unwrap();
+        writeln!(
+            out,
+            "static const double parameters[] = {};",
+            list(numbers(self.parameters.iter().map(|(_, value)| *value).collect()))
+        )
+        .unwrap();
+        let (start, end) = self.time_span;
+        writeln!(out, "static const double tspan[2] = {{{}, {}}};", number(start), number(end))
+            .unwrap();
+        writeln!(out).unwrap();
+        writeln!(
+            out,
+            "void vector_field(double t, const double *x, const double *p, double *dx) {{"
+        )
+        .unwrap();
+        writeln!(out, "    (void)t;").unwrap();
+        for (i, var) in self.variables.iter().enumerate() {
+            writeln!(out, "    const double {var} = x[{i}];").unwrap();
+        }
+        for (i, equation) in self.equations.iter().enumerate() {
+            let rhs = self.write_rhs(
+                equation,
+                CodegenLanguage::C,
+                |k| format!("p[{k}] /* {} */", self.parameters[k].0),
+                |var, exp| format!("pow({var}, {exp})"),
+            );
+            writeln!(out, "    dx[{i}] = {rhs};").unwrap();
+        }
+        writeln!(out, "}}").unwrap();
+        out
+    }
+}
+
+/// Writes a sum of signed terms as an expression.
+fn sum(terms: impl Iterator<Item = (bool, String)>, language: CodegenLanguage) -> String {
+    let mut out = String::new();
+    for (negative, term) in terms {
+        let sign = match (out.is_empty(), negative) {
+            (true, false) => "",
+            (true, true) => "-",
+            (false, false) => " + ",
+            (false, true) => " - ",
+        };
+        write!(out, "{sign}{term}").unwrap();
+    }
+    if out.is_empty() {
+        number(0.0, language)
+    } else {
+        out
+    }
+}
+
+/// Formats a nonnegative rational number exactly as an expression.
+fn rational(q: Rational, language: CodegenLanguage) -> String {
+    let integer = |n: i64| number(n as f32, language);
+    if q.is_integer() {
+        integer(q.numer())
+    } else {
+        format!("({} / {})", integer(q.numer()), integer(q.denom()))
+    }
+}
+
+/// Formats a number as a floating point literal in the target language.
+///
+/// Infinities and NaN have no literals in common, so they are written using the
+/// constants or conversions of each language.
+fn number(x: f32, language: CodegenLanguage) -> String {
+    if x.is_nan() {
+        return match language {
+            CodegenLanguage::Python => "float(\"nan\")",
+            CodegenLanguage::Julia => "NaN",
+            CodegenLanguage::C => "NAN",
+        }
+        .into();
+    }
+    if x.is_infinite() {
+        let infinity = match language {
+            CodegenLanguage::Python => "float(\"inf\")",
+            CodegenLanguage::Julia => "Inf",
+            CodegenLanguage::C => "INFINITY",
+        };
+        return if x < 0.0 {
+            format!("-{infinity}")
+        } else {
+            infinity.into()
+        };
+    }
+    let s = format!("{x:?}");
+    if s.contains(['.', 'e']) {
+        s
+    } else {
+        format!("{s}.0")
+    }
+}
+
+impl<Sys> ODEAnalysis<Sys> {
+    /// Generates code simulating the ODE problem in another language.
+    ///
+    /// The code is generated from the symbolic polynomial system underlying the
+    /// analysis, so that the program has the same parameters as the system. Each
+    /// parameter is given a label and default value by the `parameter` function,
+    /// usually looking up the problem data. The initial values and time span are
+    /// taken from the analysis, and the namespace is used to name the variables
+    /// after the objects of the model.
+    pub fn generate_code<Id, Exp>(
+        &self,
+        system: &PolynomialSystem<QualifiedName, Parameter<Id>, Exp>,
+        parameter: impl Fn(&Id) -> (String, f32),
+        language: CodegenLanguage,
+        namespace: &Namespace,
+    ) -> String
+    where
+        Id: Clone + Ord,
+        Exp: Clone + Ord + Into<i32>,
+    {
+        let system = NamedSystem::new(self, system, parameter, namespace);
+        match language {
+            CodegenLanguage::Python => system.to_python(),
+            CodegenLanguage::Julia => system.to_julia(),
+            CodegenLanguage::C => system.to_c(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::rc::Rc;
+
+    use expect_test::expect;
+    use nalgebra::DVector;
+
+    use super::*;
+    use crate::simulate::ode::ODEProblem;
+    use crate::stdlib::analyses::ode::{
+        FlowParameter, MassActionProblemData, MassConservationType, PetriNetMassActionAnalysis,
+        extend_mass_action_scalars, into_mass_action_analysis,
+    };
+    use crate::stdlib::{models::sir_petri, theories::th_sym_monoidal_category};
+    use crate::zero::{alg::Polynomial, name, rig::Monomial};
+
+    fn sir_data() -> MassActionProblemData {
+        MassActionProblemData {
+            mass_conservation_type: MassConservationType::Balanced,
+            transition_rates: [(name("infect"), 0.5), (name("recover"), 0.25)].into(),
+            transition_consumption_rates: HashMap::new(),
+            transition_production_rates: HashMap::new(),
+            place_consumption_rates: HashMap::new(),
+            place_production_rates: HashMap::new(),
+            initial_values: [(name("S"), 10.0), (name("I"), 1.0)].into(),
+            duration: 5.0,
+        }
+    }
+
+    fn sir_code(language: CodegenLanguage) -> String {
+        let model = sir_petri(Rc::new(th_sym_monoidal_category()));
+        let sys = PetriNetMassActionAnalysis::default()
+            .build_system(&model, MassConservationType::Balanced);
+        let analysis = into_mass_action_analysis(
+            extend_mass_action_scalars(sys.clone(), &sir_data()),
+            sir_data(),
+        );
+        let (data, ns) = (sir_data(), Namespace::new_for_text());
+        let parameter = |flow: &FlowParameter| match flow {
+            FlowParameter::Balanced { transition } => {
+                (ns.label_string(transition), data.transition_rates[transition])
+            }
+            _ => panic!("Flow should be balanced"),
+        };
+        analysis.generate_code(&sys, parameter, language, &ns)
+    }
+
+    #[test]
+    fn python() {
+        let code = sir_code(CodegenLanguage::Python);
+        let expected = expect![[r#"
+            import numpy as np
+            from scipy.integrate import solve_ivp
+
+            parameters = {
+                "infect": 0.5,
+                "recover": 0.25,
+            }
+
+            initial_values = {
+                "S": 10.0,
+                "I": 1.0,
+                "R": 0.0,
+            }
+
+            tspan = (0.0, 5.0)
+
+
+            def vector_field(t, x, p=parameters):
+                S, I, R, = x
+                return np.array([
+                    -p["infect"] * I * S,
+                    -p["recover"] * I + p["infect"] * I * S,
+                    p["recover"] * I,
+                ])
+
+
+            if __name__ == "__main__":
+                x0 = np.array(list(initial_values.values()))
+                solution = solve_ivp(vector_field, tspan, x0)
+        "#]];
+        expected.assert_eq(&code);
+    }
+
+    #[test]
+    fn julia() {
+        let code = sir_code(CodegenLanguage::Julia);
+        let expected = expect![[r#"
+            using DifferentialEquations
+
+            parameters = (
+                infect = 0.5,
+                recover = 0.25,
+            )
+
+            initial_values = (
+                S = 10.0,
+                I = 1.0,
+                R = 0.0,
+            )
+
+            tspan = (0.0, 5.0)
+
+            function vector_field!(du, u, p, t)
+                S, I, R, = u
+                du[1] = -p.infect * I * S
+                du[2] = -p.recover * I + p.infect * I * S
+                du[3] = p.recover * I
+                return nothing
+            end
+
+            problem = ODEProblem(vector_field!, collect(Float64, initial_values), tspan, parameters)
+            solution = solve(problem)
+        "#]];
+        expected.assert_eq(&code);
+    }
+
+    #[test]
+    fn c() {
+        let code = sir_code(CodegenLanguage::C);
+        let expected = expect![[r##"
+            #include <math.h>
+
+            #define NUM_VARIABLES 3
+            #define NUM_PARAMETERS 2
+
+            static const char *const variable_names[] = {"S", "I", "R"};
+            static const double initial_values[] = {10.0, 1.0, 0.0};
+            static const char *const parameter_names[] = {"infect", "recover"};
+            static const double parameters[] = {0.5, 0.25};
+            static const double tspan[2] = {0.0, 5.0};
+
+            void vector_field(double t, const double *x, const double *p, double *dx) {
+                (void)t;
+                const double S = x[0];
+                const double I = x[1];
+                const double R = x[2];
+                dx[0] = -p[0] /* infect */ * I * S;
+                dx[1] = -p[1] /* recover */ * I + p[0] /* infect */ * I * S;
+                dx[2] = p[1] /* recover */ * I;
+            }
+        "##]];
+        expected.assert_eq(&code);
+    }
+
+    #[test]
+    fn names_and_numbers() {
+        let param = |terms: &[(Rational, &str)]| -> Parameter<QualifiedName> {
+            terms.iter().map(|(q, id)| (*q, Monomial::generator(name(*id)))).collect()
+        };
+        let var = |id: &str| Polynomial::<_, Parameter<QualifiedName>, i8>::generator(name(id));
+        let mut sys = PolynomialSystem::new();
+        let half = Rational::new(1, 2);
+        sys.add_term(
+            name("lambda"),
+            var("lambda") * param(&[(half, "a"), (Rational::from_integer(-1), "b")]),
+        );
+        sys.add_term(
+            name("x y"),
+            var("lambda") * var("x y") * param(&[(Rational::from_integer(-1), "a")]),
+        );
+        let x0 = DVector::from_column_slice(&[1.0, f32::INFINITY]);
+        let variable_index = [(name("lambda"), 0), (name("x y"), 1)].into_iter().collect();
+        let analysis = ODEAnalysis::new(ODEProblem::new((), x0).end_time(1.0), variable_index);
+        let values: HashMap<_, _> = [(name("a"), 0.5), (name("b"), f32::NAN)].into();
+        let parameter = |id: &QualifiedName| (id.to_string(), values[id]);
+
+        let ns = Namespace::new_for_text();
+        let code = analysis.generate_code(&sys, parameter, CodegenLanguage::Python, &ns);
+        assert!(code.contains(r#""b": float("nan"),"#));
+        assert!(code.contains(r#""x_y": float("inf"),"#));
+        assert!(code.contains(r#"((1.0 / 2.0) * p["a"] - p["b"]) * lambda_2,"#));
+        assert!(code.contains(r#"-p["a"] * lambda_2 * x_y,"#));
+
+        let code = analysis.generate_code(&sys, parameter, CodegenLanguage::C, &ns);
+        assert!(code.contains("static const double parameters[] = {0.5, NAN};"));
+        assert!(code.contains("static const double initial_values[] = {1.0, INFINITY};"));
+    }
+
+    #[test]
+    fn reserved_words() {
+        let one = Rational::from_integer(1);
+        let param = |id: &str| -> Parameter<QualifiedName> {
+            [(one, Monomial::generator(name(id)))].into_iter().collect()
+        };
+        let var = |id: &str| Polynomial::<_, Parameter<QualifiedName>, i8>::generator(name(id));
+        let mut sys = PolynomialSystem::new();
+        sys.add_term(name("true"), var("catch") * param("bool"));
+        sys.add_term(name("catch"), var("true") * param("pow"));
+        let x0 = DVector::from_column_slice(&[1.0, 2.0]);
+        let variable_index = [(name("true"), 0), (name("catch"), 1)].into_iter().collect();
+        let analysis = ODEAnalysis::new(ODEProblem::new((), x0).end_time(1.0), variable_index);
+        let parameter = |id: &QualifiedName| (id.to_string(), 1.0);
+
+        let ns = Namespace::new_for_text();
+        let code = analysis.generate_code(&sys, parameter, CodegenLanguage::Julia, &ns);
+        assert!(code.contains("    true_2, catch_2, = u"));
+        assert!(code.contains("    du[1] = p.bool_2 * catch_2"));
+        let code = analysis.generate_code(&sys, parameter, CodegenLanguage::C, &ns);
+        assert!(code.contains("    dx[1] = p[1] /* pow_2 */ * true_2;"));
+    }
+}
@@ -10,7 +10,9 @@ use serde::{Deserialize, Serialize};
 #[cfg(feature = "serde-wasm")]
 use tsify::Tsify;
 
-use super::{ODEAnalysis, ODEProblem};
+use ode_solvers::dop_shared::IntegrationError;
+
+use super::{ODEAnalysis, ODEProblem, ODESolution};
 use crate::dbl::model::{DiscreteDblModel, FpDblModel};
 use crate::one::{FgCategory, QualifiedPath};
 use crate::simulate::ode::{KuramotoOrder, KuramotoSystem};
@@ -86,10 +88,73 @@ pub struct CommonKuramotoProblemData {
     duration: f32,
 }
 
+/// Diagnostics of synchronization in a solution to a Kuramoto problem.
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-wasm", derive(Tsify))]
+#[cfg_attr(feature = "serde-wasm", tsify(into_wasm_abi, from_wasm_abi))]
+pub struct KuramotoDiagnostics {
+    /// Kuramoto order parameter `r(t)` at each time of the solution.
+    #[cfg_attr(feature = "serde", serde(rename = "orderParameter"))]
+    pub order_parameter: Vec<f32>,
+
+    /// Time after which the oscillators remain phase-locked, if any.
+    ///
+    /// The oscillators are phase-locked when their instantaneous angular
+    /// frequencies agree up to the tolerance of the analysis, so that the
+    /// differences between their phases are constant.
+    #[cfg_attr(feature = "serde", serde(rename = "phaseLockingTime"))]
+    pub phase_locking_time: Option<f32>,
+
+    /// Clusters of oscillators with a common mean frequency.
+    ///
+    /// Mean frequencies are measured over the second half of the simulation and
+    /// clusters are sorted by increasing frequency.
+    #[cfg_attr(feature = "serde", serde(rename = "frequencyClusters"))]
+    pub frequency_clusters: Vec<FrequencyCluster>,
+
+    /// Algebraic connectivity of the coupling network.
+    #[cfg_attr(feature = "serde", serde(rename = "algebraicConnectivity"))]
+    pub algebraic_connectivity: f32,
+
+    /// Estimate of the critical coupling strength, relative to the given coupling.
+    ///
+    /// See [`KuramotoSystem::critical_coupling`] for details.
+    #[cfg_attr(feature = "serde", serde(rename = "criticalCoupling"))]
+    pub critical_coupling: Option<f32>,
+}
+
+/// Cluster of oscillators rotating at a common mean frequency.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-wasm", derive(Tsify))]
+#[cfg_attr(feature = "serde-wasm", tsify(into_wasm_abi, from_wasm_abi))]
+pub struct FrequencyCluster {
+    /// Mean angular frequency of the oscillators in the cluster.
+    pub frequency: f32,
+
+    /// Object IDs of the oscillators in the cluster.
+    pub nodes: Vec<QualifiedName>,
+}
+
+/// Solution to a Kuramoto problem together with diagnostics of synchronization.
+#[derive(Clone, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-wasm", derive(Tsify))]
+#[cfg_attr(feature = "serde-wasm", tsify(into_wasm_abi, from_wasm_abi))]
+pub struct KuramotoSolution {
+    /// Trajectories of the phases.
+    pub solution: ODESolution,
+
+    /// Diagnostics of synchronization.
+    pub diagnostics: KuramotoDiagnostics,
+}
+
 /// Kuramoto ODE analysis of a model.
 pub struct KuramotoAnalysis {
     node_ob_type: QualifiedName,
     link_mor_types: Vec<QualifiedPath>,
+    tolerance: f32,
 }
 
 impl KuramotoAnalysis {
@@ -98,9 +163,16 @@ impl KuramotoAnalysis {
         Self {
             node_ob_type: ob_type,
             link_mor_types: Default::default(),
+            tolerance: 1e-2,
         }
     }
 
+    /// Sets the tolerance for frequencies to be considered equal.
+    pub fn tolerance(mut self, tolerance: f32) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
     /// Adds a type of morphism to be treated as links between nodes.
     pub fn add_link_type(mut self, mor_type: QualifiedPath) -> Self {
         self.link_mor_types.push(mor_type);
@@ -159,4 +231,144 @@ impl KuramotoAnalysis {
         let problem = ODEProblem::new(system, initial_values).end_time(common.duration);
         ODEAnalysis::new(problem, ob_index)
     }
+
+    /// Simulates the Kuramoto system derived from a model and diagnoses whether
+    /// and how the oscillators synchronize.
+    pub fn simulate(
+        &self,
+        model: &DiscreteDblModel,
+        data: &KuramotoProblemData,
+    ) -> Result<KuramotoSolution, IntegrationError> {
+        let analysis = self.build_system(model, data);
+        if analysis.variable_index.is_empty() {
+            return Ok(Default::default());
+        }
+        let system = &analysis.problem.system;
+        let n = system.num_oscillators();
+
+        let result = analysis.solve_raw_with_defaults()?;
+        let (t_out, x_out) = result.get();
+
+        let order_parameter = x_out.iter().map(|x| system.order_parameter(x)).collect();
+
+        let is_locked = |(t, x): (&f32, &DVector<f32>)| {
+            let freqs = system.frequencies(x, *t);
+            freqs.max() - freqs.min() <= self.tolerance
+        };
+        let unlocked = t_out.iter().zip(x_out).rposition(|tx| !is_locked(tx));
+        let phase_locking_time = match unlocked {
+            None => t_out.first().copied(),
+            Some(i) => t_out.get(i + 1).copied(),
+        };
+
+        // Mean frequencies over the second half of the simulation.
+        let (t_end, x_end) = (*t_out.last().unwrap(), x_out.last().unwrap());
+        let mid = t_out.partition_point(|t| *t < (t_out[0] + t_end) / 2.0).min(t_out.len() - 1);
+        let (t_mid, x_mid) = (t_out[mid], &x_out[mid]);
+        let mut mean_freqs: Vec<_> = if t_end > t_mid {
+            (0..n).map(|i| (i, (x_end[i] - x_mid[i]) / (t_end - t_mid))).collect()
+        } else {
+            let freqs = system.frequencies(x_end, t_end);
+            (0..n).map(|i| (i, freqs[i])).collect()
+        };
+        mean_freqs.sort_by(|(_, f), (_, g)| f.total_cmp(g));
+
+        let ob_ids: Vec<_> = analysis.variable_index.keys().collect();
+        let mut frequency_clusters: Vec<FrequencyCluster> = Vec::new();
+        let mut cluster_sum = 0.0;
+        let mut last_freq = f32::NEG_INFINITY;
+        for (i, freq) in mean_freqs {
+            match frequency_clusters.last_mut() {
+                Some(cluster) if freq - last_freq <= self.tolerance => {
+                    cluster.nodes.push(ob_ids[i].clone());
+                    cluster_sum += freq;
+                    cluster.frequency = cluster_sum / cluster.nodes.len() as f32;
+                }
+                _ => {
+                    frequency_clusters.push(FrequencyCluster {
+                        frequency: freq,
+                        nodes: vec![ob_ids[i].clone()],
+                    });
+                    cluster_sum = freq;
+                }
+            }
+            last_freq = freq;
+        }
+
+        let diagnostics = KuramotoDiagnostics {
+            order_parameter,
+            phase_locking_time,
+            frequency_clusters,
+            algebraic_connectivity: system.algebraic_connectivity(),
+            critical_coupling: system.critical_coupling(),
+        };
+        let solution = ODESolution {
+            time: t_out.clone(),
+            states: analysis
+                .variable_index
+                .iter()
+                .map(|(ob, i)| (ob.clone(), x_out.iter().map(|x| x[*i]).collect()))
+                .collect(),
+        };
+        Ok(KuramotoSolution { solution, diagnostics })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use super::*;
+    use crate::dbl::model::MutDblModel;
+    use crate::one::Path;
+    use crate::stdlib::theories::th_power_system;
+    use crate::zero::name;
+
+    fn analysis() -> KuramotoAnalysis {
+        KuramotoAnalysis::new(name("Bus")).add_link_type(Path::empty(name("Bus")))
+    }
+
+    fn problem_data(forcing_params: &[(&str, f32)]) -> KuramotoProblemData {
+        KuramotoProblemData::FirstOrder(CommonKuramotoProblemData {
+            coupling_coeffs: [(name("line"), 2.0)].into(),
+            damping_coeffs: [("a", 1.0), ("b", 1.0), ("c", 1.0)]
+                .into_iter()
+                .map(|(id, alpha)| (name(id), alpha))
+                .collect(),
+            forcing_params: forcing_params.iter().map(|(id, p)| (name(*id), *p)).collect(),
+            initial_phases: HashMap::new(),
+            duration: 20.0,
+        })
+    }
+
+    #[test]
+    fn synchronization_diagnostics() {
+        let mut model = DiscreteDblModel::new(Rc::new(th_power_system()));
+        model.add_ob(name("a"), name("Bus"));
+        model.add_ob(name("b"), name("Bus"));
+        model.add_mor(name("line"), name("a"), name("b"), Path::empty(name("Bus")));
+
+        // Two coupled buses lock, since the coupling exceeds the critical value.
+        let data = problem_data(&[("a", 1.0), ("b", -1.0)]);
+        let result = analysis().simulate(&model, &data).unwrap();
+        let diagnostics = &result.diagnostics;
+        assert_eq!(result.solution.time.len(), diagnostics.order_parameter.len());
+        assert!(diagnostics.phase_locking_time.is_some_and(|t| t > 0.0 && t < 20.0));
+        assert_eq!(diagnostics.frequency_clusters.len(), 1);
+        assert!((diagnostics.algebraic_connectivity - 4.0).abs() < 1e-4);
+        assert!((diagnostics.critical_coupling.unwrap() - 0.5).abs() < 1e-4);
+        // The locked phases differ by `arcsin(1/2)`, so that `r = cos(π/12)`.
+        let r = *diagnostics.order_parameter.last().unwrap();
+        assert!((r - (std::f32::consts::PI / 12.0).cos()).abs() < 1e-3);
+
+        // An isolated bus rotates at its own frequency.
+        model.add_ob(name("c"), name("Bus"));
+        let data = problem_data(&[("a", 1.0), ("b", -1.0), ("c", 3.0)]);
+        let diagnostics = analysis().simulate(&model, &data).unwrap().diagnostics;
+        assert_eq!(diagnostics.phase_locking_time, None);
+        assert_eq!(diagnostics.critical_coupling, None);
+        let clusters: Vec<_> = diagnostics.frequency_clusters.iter().map(|c| &c.nodes).collect();
+        assert_eq!(clusters, vec![&vec![name("a"), name("b")], &vec![name("c")]]);
+        assert!((diagnostics.frequency_clusters[1].frequency - 3.0).abs() < 1e-3);
+    }
 }
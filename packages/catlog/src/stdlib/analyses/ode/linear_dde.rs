@@ -0,0 +1,258 @@
+//! Constant-coefficient linear delay differential equation analysis of models.
+//!
+//! This analysis extends the [linear ODE analysis](super::linear_ode) to models
+//! with delayed interactions, such as models of a [delayable signed
+//! category](crate::stdlib::theories::th_delayable_signed_category). Each
+//! delayed morphism contributes through the state of its domain at a fixed lag in
+//! the past. The main entry point for this module is
+//! [`linear_dde_analysis`](DelayedCoefficientBuilder::linear_dde_analysis).
+
+use std::collections::HashMap;
+
+use nalgebra::DVector;
+use thiserror::Error;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "serde-wasm")]
+use tsify::Tsify;
+
+use super::{DDEAnalysis, SignedCoefficientBuilder};
+use crate::simulate::ode::{DDEProblem, LinearDDESystem};
+use crate::{dbl::model::DiscreteDblModel, one::QualifiedPath, zero::QualifiedName};
+
+/// Data defining a linear DDE problem for a model.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-wasm", derive(Tsify))]
+#[cfg_attr(
+    feature = "serde-wasm",
+    tsify(into_wasm_abi, from_wasm_abi, hashmap_as_object)
+)]
+pub struct LinearDDEProblemData {
+    /// Map from morphism IDs to interaction coefficients (nonnegative reals).
+    #[cfg_attr(feature = "serde", serde(rename = "coefficients"))]
+    coefficients: HashMap<QualifiedName, f32>,
+
+    /// Map from object IDs to initial values (nonnegative reals).
+    ///
+    /// The history of each object before the start of the simulation is taken to
+    /// be constant at its initial value.
+    #[cfg_attr(feature = "serde", serde(rename = "initialValues"))]
+    initial_values: HashMap<QualifiedName, f32>,
+
+    /// Default lag of delayed interactions (positive real).
+    delay: f32,
+
+    /// Map from morphism IDs to lags of delayed interactions, overriding the
+    /// default lag (positive reals).
+    #[cfg_attr(feature = "serde", serde(default))]
+    delays: HashMap<QualifiedName, f32>,
+
+    /// Duration of simulation.
+    duration: f32,
+}
+
+/// Error in building a linear DDE analysis from problem data.
+#[derive(Debug, Error, PartialEq)]
+pub enum LinearDDEError {
+    /// The lag of a delayed interaction is not a positive, finite number.
+    #[error("Lag of delayed interaction `{0}` must be positive and finite, got {1}")]
+    InvalidLag(QualifiedName, f32),
+}
+
+/// Builder for linear DDE analyses of signed models with delays.
+///
+/// Consists of one [coefficient builder](SignedCoefficientBuilder) for the
+/// instantaneous interactions and another for the delayed interactions.
+pub struct DelayedCoefficientBuilder<ObType, MorType> {
+    instantaneous: SignedCoefficientBuilder<ObType, MorType>,
+    delayed: SignedCoefficientBuilder<ObType, MorType>,
+}
+
+impl<ObType: Clone, MorType> DelayedCoefficientBuilder<ObType, MorType> {
+    /// Creates a new builder for the given object type.
+    pub fn new(var_ob_type: ObType) -> Self {
+        Self {
+            instantaneous: SignedCoefficientBuilder::new(var_ob_type.clone()),
+            delayed: SignedCoefficientBuilder::new(var_ob_type),
+        }
+    }
+
+    /// Adds a morphism type defining a positive instantaneous interaction.
+    pub fn add_positive(mut self, mor_type: MorType) -> Self {
+        self.instantaneous = self.instantaneous.add_positive(mor_type);
+        self
+    }
+
+    /// Adds a morphism type defining a negative instantaneous interaction.
+    pub fn add_negative(mut self, mor_type: MorType) -> Self {
+        self.instantaneous = self.instantaneous.add_negative(mor_type);
+        self
+    }
+
+    /// Adds a morphism type defining a positive delayed interaction.
+    pub fn add_delayed_positive(mut self, mor_type: MorType) -> Self {
+        self.delayed = self.delayed.add_positive(mor_type);
+        self
+    }
+
+    /// Adds a morphism type defining a negative delayed interaction.
+    pub fn add_delayed_negative(mut self, mor_type: MorType) -> Self {
+        self.delayed = self.delayed.add_negative(mor_type);
+        self
+    }
+}
+
+impl DelayedCoefficientBuilder<QualifiedName, QualifiedPath> {
+    /// Linear DDE analysis for a model of a double theory.
+    ///
+    /// Delayed morphisms sharing the same lag are collected into a single matrix of
+    /// coefficients for the state delayed by that lag. Returns an error if the lag
+    /// of any delayed morphism is not positive and finite.
+    pub fn linear_dde_analysis(
+        &self,
+        model: &DiscreteDblModel,
+        data: LinearDDEProblemData,
+    ) -> Result<DDEAnalysis<LinearDDESystem>, LinearDDEError> {
+        let (matrix, ob_index) = self.instantaneous.build_matrix(model);
+        let (delayed_matrix, _) = self.delayed.build_matrix(model);
+        let n = ob_index.len();
+
+        let initial_values = ob_index
+            .keys()
+            .map(|ob| data.initial_values.get(ob).copied().unwrap_or_default());
+        let x0 = DVector::from_iterator(n, initial_values);
+
+        let coefficient =
+            |id: &QualifiedName| data.coefficients.get(id).copied().unwrap_or_default();
+        let lag = |id: &QualifiedName| data.delays.get(id).copied().unwrap_or(data.delay);
+
+        let mut delays = Vec::new();
+        for id in delayed_matrix
+            .iter()
+            .flat_map(|poly| poly.monomials().flat_map(|m| m.variables()))
+        {
+            let delay = lag(id);
+            if !(delay.is_finite() && delay > 0.0) {
+                return Err(LinearDDEError::InvalidLag(id.clone(), delay));
+            }
+            delays.push(delay);
+        }
+        delays.sort_by(f32::total_cmp);
+        delays.dedup();
+
+        let system = LinearDDESystem {
+            coefficients: matrix.map(|poly| poly.eval(coefficient)),
+            delayed_coefficients: delays
+                .iter()
+                .map(|delay| {
+                    delayed_matrix.map(|poly| {
+                        poly.eval(|id| {
+                            if lag(id) == *delay {
+                                coefficient(id)
+                            } else {
+                                0.0
+                            }
+                        })
+                    })
+                })
+                .collect(),
+            delays,
+        };
+        let problem = DDEProblem::new(system, x0).end_time(data.duration);
+        Ok(DDEAnalysis::new(problem, ob_index))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::rc::Rc;
+
+    use nalgebra::DMatrix;
+
+    use super::*;
+    use crate::dbl::model::MutDblModel;
+    use crate::stdlib;
+    use crate::{one::Path, zero::name};
+
+    fn builder() -> DelayedCoefficientBuilder<QualifiedName, QualifiedPath> {
+        DelayedCoefficientBuilder::new(name("Object"))
+            .add_positive(Path::Id(name("Object")))
+            .add_negative(name("Negative").into())
+            .add_delayed_positive(name("Slow").into())
+            .add_delayed_negative(Path::pair(name("Negative"), name("Slow")))
+    }
+
+    #[test]
+    fn delayed_negative_loop() {
+        let th = Rc::new(stdlib::theories::th_delayable_signed_category());
+        let model = stdlib::models::delayed_negative_loop(th);
+        let data = LinearDDEProblemData {
+            coefficients: [(name("loop"), 1.0)].into_iter().collect(),
+            initial_values: [(name("x"), 1.0)].into_iter().collect(),
+            delay: 1.0,
+            delays: HashMap::new(),
+            duration: 10.0,
+        };
+        let analysis = builder().linear_dde_analysis(&model, data).unwrap();
+        let sys = &analysis.problem.system;
+        assert_eq!(sys.coefficients, DMatrix::zeros(1, 1));
+        assert_eq!(sys.delays, vec![1.0]);
+        assert_eq!(sys.delayed_coefficients, vec![DMatrix::from_element(1, 1, -1.0)]);
+
+        // Unlike exponential decay, the delayed negative feedback overshoots.
+        let solution = analysis.solve_with_defaults().unwrap();
+        let x = &solution.states[&name("x")];
+        assert!(x.iter().any(|value| *value < -0.1));
+    }
+
+    #[test]
+    fn separate_lags() {
+        let th = Rc::new(stdlib::theories::th_delayable_signed_category());
+        let mut model = stdlib::models::negative_feedback(th);
+        model.add_mor(name("slow"), name("y"), name("y"), name("Slow").into());
+        model.add_mor(name("slower"), name("x"), name("x"), name("Slow").into());
+        let data = LinearDDEProblemData {
+            coefficients: [("positive", 2.0), ("negative", 1.0), ("slow", 0.5), ("slower", 0.25)]
+                .into_iter()
+                .map(|(id, c)| (name(id), c))
+                .collect(),
+            initial_values: HashMap::new(),
+            delay: 1.0,
+            delays: [(name("slower"), 2.0)].into_iter().collect(),
+            duration: 1.0,
+        };
+        let sys = builder().linear_dde_analysis(&model, data).unwrap().problem.system;
+        assert_eq!(sys.coefficients, DMatrix::from_row_slice(2, 2, &[0.0, -1.0, 2.0, 0.0]));
+        assert_eq!(sys.delays, vec![1.0, 2.0]);
+        assert_eq!(
+            sys.delayed_coefficients,
+            vec![
+                DMatrix::from_row_slice(2, 2, &[0.0, 0.0, 0.0, 0.5]),
+                DMatrix::from_row_slice(2, 2, &[0.25, 0.0, 0.0, 0.0]),
+            ]
+        );
+    }
+
+    #[test]
+    fn invalid_lags() {
+        let th = Rc::new(stdlib::theories::th_delayable_signed_category());
+        let model = stdlib::models::delayed_negative_loop(th);
+        let data = |delay, delays: &[(&str, f32)]| LinearDDEProblemData {
+            coefficients: [(name("loop"), 1.0)].into_iter().collect(),
+            initial_values: HashMap::new(),
+            delay,
+            delays: delays.iter().map(|(id, lag)| (name(*id), *lag)).collect(),
+            duration: 1.0,
+        };
+        assert_eq!(
+            builder().linear_dde_analysis(&model, data(0.0, &[])).err(),
+            Some(LinearDDEError::InvalidLag(name("loop"), 0.0))
+        );
+        assert_eq!(
+            builder().linear_dde_analysis(&model, data(1.0, &[("loop", -1.0)])).err(),
+            Some(LinearDDEError::InvalidLag(name("loop"), -1.0))
+        );
+        assert!(builder().linear_dde_analysis(&model, data(f32::INFINITY, &[])).is_err());
+    }
+}
@@ -0,0 +1,458 @@
+//! Spectral analysis and closed-form solution of linear ODEs.
+//!
+//! Since the [linear ODE system](super::linear_ode) `dx/dt = A x` of a model is
+//! linear with constant coefficients, much can be said about it without numerical
+//! integration. This analysis reports the eigenvalues and eigenvectors of the
+//! coefficient matrix `A`, classifies the stability of the equilibrium at the
+//! origin, computes the exact solution `x(t) = e^{tA} x(0)` at requested times,
+//! and computes transfer functions between chosen input and output objects. The
+//! main entry point for this module is
+//! [`linear_spectral_analysis`](SignedCoefficientBuilder::linear_spectral_analysis).
+
+use std::collections::HashMap;
+
+use nalgebra::{Complex, DMatrix, DVector};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "serde-wasm")]
+use tsify::Tsify;
+
+use super::{ODESolution, SignedCoefficientBuilder};
+use crate::{dbl::model::DiscreteDblModel, one::QualifiedPath, zero::QualifiedName};
+
+/// Tolerance below which real parts of eigenvalues are considered to vanish.
+const TOLERANCE: f64 = 1e-6;
+
+/// Relative tolerance within which eigenvalues are considered repeated.
+///
+/// Repeated eigenvalues of defective matrices are very sensitive to rounding, so
+/// this tolerance is much looser than [`TOLERANCE`].
+const REPEATED_TOLERANCE: f64 = 1e-4;
+
+/// Data defining a linear spectral analysis for a model.
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-wasm", derive(Tsify))]
+#[cfg_attr(
+    feature = "serde-wasm",
+    tsify(into_wasm_abi, from_wasm_abi, hashmap_as_object)
+)]
+pub struct LinearSpectralProblemData {
+    /// Map from morphism IDs to interaction coefficients (nonnegative reals).
+    #[cfg_attr(feature = "serde", serde(rename = "coefficients"))]
+    pub coefficients: HashMap<QualifiedName, f32>,
+
+    /// Map from object IDs to initial values (nonnegative reals).
+    #[cfg_attr(feature = "serde", serde(rename = "initialValues"))]
+    pub initial_values: HashMap<QualifiedName, f32>,
+
+    /// Times at which to evaluate the exact solution.
+    pub times: Vec<f32>,
+
+    /// Object IDs of inputs for transfer functions.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub inputs: Vec<QualifiedName>,
+
+    /// Object IDs of outputs for transfer functions.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub outputs: Vec<QualifiedName>,
+}
+
+/// Complex number, as reported by the linear spectral analysis.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-wasm", derive(Tsify))]
+pub struct ComplexValue {
+    /// Real part.
+    pub re: f32,
+
+    /// Imaginary part.
+    pub im: f32,
+}
+
+impl From<Complex<f64>> for ComplexValue {
+    fn from(z: Complex<f64>) -> Self {
+        Self { re: z.re as f32, im: z.im as f32 }
+    }
+}
+
+/// Eigenmode of a linear ODE system.
+///
+/// A repeated eigenvalue has one mode for each linearly independent eigenvector.
+/// When the eigenvalue has fewer modes than its multiplicity, the coefficient
+/// matrix is defective and the solution has terms growing polynomially in time
+/// that no mode accounts for.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-wasm", derive(Tsify))]
+pub struct LinearMode {
+    /// Eigenvalue of the coefficient matrix.
+    ///
+    /// The real part is the growth rate of the mode and the imaginary part is its
+    /// angular frequency of oscillation.
+    pub eigenvalue: ComplexValue,
+
+    /// Algebraic multiplicity of the eigenvalue.
+    pub multiplicity: usize,
+
+    /// Unit eigenvector, with components ordered as the variables.
+    pub eigenvector: Vec<ComplexValue>,
+}
+
+/// Stability of the equilibrium at the origin of a linear ODE system.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-wasm", derive(Tsify))]
+pub enum LinearStability {
+    /// Every eigenvalue has negative real part, so all solutions decay.
+    #[cfg_attr(feature = "serde", serde(rename = "stable"))]
+    Stable,
+
+    /// No eigenvalue has positive real part but some lie on the imaginary axis.
+    ///
+    /// Solutions are bounded when the eigenvalues on the imaginary axis are
+    /// semisimple and grow polynomially otherwise.
+    #[cfg_attr(feature = "serde", serde(rename = "marginal"))]
+    Marginal,
+
+    /// Some eigenvalue has positive real part, so some solutions grow.
+    #[cfg_attr(feature = "serde", serde(rename = "unstable"))]
+    Unstable,
+}
+
+/// Transfer function from an input object to an output object.
+///
+/// The transfer function is the rational function `G(s) = N(s) / D(s)` relating
+/// the Laplace transform of the output to that of an input added to the
+/// derivative of the input object. Both polynomials are given by their
+/// coefficients in decreasing powers of `s`.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-wasm", derive(Tsify))]
+pub struct TransferFunction {
+    /// Object ID of the input.
+    pub input: QualifiedName,
+
+    /// Object ID of the output.
+    pub output: QualifiedName,
+
+    /// Coefficients of the numerator.
+    pub numerator: Vec<f32>,
+
+    /// Coefficients of the denominator, the characteristic polynomial of `A`.
+    pub denominator: Vec<f32>,
+}
+
+/// Result of a linear spectral analysis.
+#[derive(Clone, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-wasm", derive(Tsify))]
+#[cfg_attr(feature = "serde-wasm", tsify(into_wasm_abi, from_wasm_abi))]
+pub struct LinearSpectralResult {
+    /// Object IDs of the variables, in the order used by eigenvectors.
+    pub variables: Vec<QualifiedName>,
+
+    /// Eigenmodes, sorted by decreasing real part of the eigenvalue.
+    pub modes: Vec<LinearMode>,
+
+    /// Indices of the dominant modes, those whose eigenvalues have the largest
+    /// real part and so govern the long-term behavior.
+    #[cfg_attr(feature = "serde", serde(rename = "dominantModes"))]
+    pub dominant_modes: Vec<usize>,
+
+    /// Stability of the equilibrium at the origin, if there are any variables.
+    pub stability: Option<LinearStability>,
+
+    /// Exact solution at the requested times.
+    pub solution: ODESolution,
+
+    /// Transfer functions for each pair of input and output.
+    #[cfg_attr(feature = "serde", serde(rename = "transferFunctions"))]
+    pub transfer_functions: Vec<TransferFunction>,
+}
+
+/// Computes the eigenmodes of a matrix, sorted by decreasing real part.
+///
+/// Repeated eigenvalues have one mode for each vector in a basis of the
+/// eigenspace, which has fewer vectors than the multiplicity when the matrix is
+/// defective.
+fn eigenmodes(matrix: &DMatrix<f64>) -> Vec<LinearMode> {
+    let n = matrix.nrows();
+    let scale = matrix.norm().max(1.0);
+
+    // Group together numerically repeated eigenvalues.
+    let mut clusters: Vec<Vec<Complex<f64>>> = Vec::new();
+    for lambda in matrix.complex_eigenvalues().iter() {
+        match clusters
+            .iter_mut()
+            .find(|cluster| (cluster[0] - lambda).norm() <= REPEATED_TOLERANCE * scale)
+        {
+            Some(cluster) => cluster.push(*lambda),
+            None => clusters.push(vec![*lambda]),
+        }
+    }
+    let mut eigenvalues: Vec<_> = clusters
+        .into_iter()
+        .map(|cluster| {
+            let m = cluster.len();
+            (cluster.into_iter().sum::<Complex<f64>>() / m as f64, m)
+        })
+        .collect();
+    eigenvalues.sort_by(|(z, _), (w, _)| w.re.total_cmp(&z.re).then(w.im.total_cmp(&z.im)));
+
+    let complex_matrix = matrix.map(|a| Complex::new(a, 0.0));
+    eigenvalues
+        .into_iter()
+        .flat_map(|(lambda, multiplicity)| {
+            // The eigenspace is the kernel of `A - λI`, spanned by the right
+            // singular vectors with negligible singular values, or else by the one
+            // with the smallest singular value.
+            let shifted = &complex_matrix - DMatrix::from_diagonal_element(n, n, lambda);
+            let svd = shifted.svd(false, true);
+            let v_t = svd.v_t.unwrap();
+            let mut kernel: Vec<_> = (0..n)
+                .filter(|k| svd.singular_values[*k] <= REPEATED_TOLERANCE * scale)
+                .take(multiplicity)
+                .collect();
+            if kernel.is_empty() {
+                kernel.push(svd.singular_values.argmin().0);
+            }
+            kernel
+                .into_iter()
+                .map(|k| {
+                    let mut v: DVector<_> = v_t.row(k).adjoint();
+                    // Normalize the phase so that the largest component is real and positive.
+                    let (i, _) = v.iter().enumerate().fold((0, 0.0), |(i, max), (j, z)| {
+                        if z.norm() > max {
+                            (j, z.norm())
+                        } else {
+                            (i, max)
+                        }
+                    });
+                    let phase = v[i] / Complex::new(v[i].norm(), 0.0);
+                    v /= phase;
+                    LinearMode {
+                        eigenvalue: lambda.into(),
+                        multiplicity,
+                        eigenvector: v.iter().map(|z| (*z).into()).collect(),
+                    }
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+/// Computes the characteristic polynomial and adjugate of `sI - A`.
+///
+/// Uses the Faddeev-LeVerrier algorithm. Returns the coefficients `c_k` of the
+/// characteristic polynomial `sⁿ + c_1 sⁿ⁻¹ + ⋯ + c_n`, with `c_0 = 1`, and the
+/// matrices `N_k` such that `adj(sI - A) = ∑ N_k sⁿ⁻¹⁻ᵏ`.
+fn faddeev_leverrier(matrix: &DMatrix<f64>) -> (Vec<f64>, Vec<DMatrix<f64>>) {
+    let n = matrix.nrows();
+    let mut coefficients = vec![1.0];
+    let mut adjugates: Vec<DMatrix<f64>> = Vec::with_capacity(n);
+    let mut current = DMatrix::identity(n, n);
+    for k in 1..=n {
+        let product = matrix * &current;
+        let c = -product.trace() / k as f64;
+        coefficients.push(c);
+        adjugates.push(current);
+        current = product + DMatrix::from_diagonal_element(n, n, c);
+    }
+    (coefficients, adjugates)
+}
+
+impl SignedCoefficientBuilder<QualifiedName, QualifiedPath> {
+    /// Linear spectral analysis for a model of a double theory.
+    ///
+    /// Analyzes the same system of linear ODEs as the
+    /// [linear ODE analysis](Self::linear_ode_analysis), but by linear algebra
+    /// instead of numerical integration.
+    pub fn linear_spectral_analysis(
+        &self,
+        model: &DiscreteDblModel,
+        data: &LinearSpectralProblemData,
+    ) -> LinearSpectralResult {
+        let (matrix, ob_index) = self.build_matrix(model);
+        let n = ob_index.len();
+        if n == 0 {
+            return Default::default();
+        }
+        let matrix = matrix
+            .map(|poly| poly.eval(|id| data.coefficients.get(id).copied().unwrap_or_default()))
+            .cast::<f64>();
+
+        let modes = eigenmodes(&matrix);
+        let max_re = modes[0].eigenvalue.re as f64;
+        let dominant_modes = (0..modes.len())
+            .take_while(|i| max_re - modes[*i].eigenvalue.re as f64 <= TOLERANCE)
+            .collect();
+        let stability = if max_re < -TOLERANCE {
+            LinearStability::Stable
+        } else if max_re <= TOLERANCE {
+            LinearStability::Marginal
+        } else {
+            LinearStability::Unstable
+        };
+
+        let initial_values = ob_index
+            .keys()
+            .map(|ob| data.initial_values.get(ob).copied().unwrap_or_default() as f64);
+        let x0 = DVector::from_iterator(n, initial_values);
+        let states: Vec<_> = data.times.iter().map(|t| (&matrix * *t as f64).exp() * &x0).collect();
+        let solution = ODESolution {
+            time: data.times.clone(),
+            states: ob_index
+                .iter()
+                .map(|(ob, i)| (ob.clone(), states.iter().map(|x| x[*i] as f32).collect()))
+                .collect(),
+        };
+
+        let (char_poly, adjugates) = faddeev_leverrier(&matrix);
+        let denominator: Vec<_> = char_poly.iter().map(|c| *c as f32).collect();
+        let mut transfer_functions = Vec::new();
+        for input in data.inputs.iter() {
+            let Some(j) = ob_index.get(input) else {
+                continue;
+            };
+            for output in data.outputs.iter() {
+                let Some(i) = ob_index.get(output) else {
+                    continue;
+                };
+                let numerator: Vec<_> = adjugates
+                    .iter()
+                    .map(|adj| adj[(*i, *j)] as f32)
+                    .skip_while(|c| c.abs() <= f32::EPSILON)
+                    .collect();
+                transfer_functions.push(TransferFunction {
+                    input: input.clone(),
+                    output: output.clone(),
+                    numerator: if numerator.is_empty() {
+                        vec![0.0]
+                    } else {
+                        numerator
+                    },
+                    denominator: denominator.clone(),
+                });
+            }
+        }
+
+        LinearSpectralResult {
+            variables: ob_index.keys().cloned().collect(),
+            modes,
+            dominant_modes,
+            stability: Some(stability),
+            solution,
+            transfer_functions,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::rc::Rc;
+
+    use super::*;
+    use crate::dbl::model::MutDblModel;
+    use crate::stdlib;
+    use crate::{one::Path, zero::name};
+
+    fn builder() -> SignedCoefficientBuilder<QualifiedName, QualifiedPath> {
+        SignedCoefficientBuilder::new(name("Object"))
+            .add_positive(Path::Id(name("Object")))
+            .add_negative(Path::single(name("Negative")))
+    }
+
+    #[test]
+    fn negative_feedback() {
+        let th = Rc::new(stdlib::theories::th_signed_category());
+        let model = stdlib::models::negative_feedback(th);
+        let data = LinearSpectralProblemData {
+            coefficients: [(name("positive"), 1.0), (name("negative"), 1.0)].into(),
+            initial_values: [(name("x"), 1.0)].into(),
+            times: vec![0.0, std::f32::consts::FRAC_PI_2],
+            inputs: vec![name("x")],
+            outputs: vec![name("y")],
+        };
+        let result = builder().linear_spectral_analysis(&model, &data);
+
+        // The system `dx/dt = -y, dy/dt = x` is a harmonic oscillator.
+        assert_eq!(result.variables, vec![name("x"), name("y")]);
+        assert_eq!(result.stability, Some(LinearStability::Marginal));
+        assert_eq!(result.dominant_modes, vec![0, 1]);
+        let eigenvalue = result.modes[0].eigenvalue;
+        assert!(eigenvalue.re.abs() < 1e-6 && (eigenvalue.im - 1.0).abs() < 1e-6);
+
+        // The exact solution is `x = cos t, y = sin t`.
+        let (x, y) = (&result.solution.states[&name("x")], &result.solution.states[&name("y")]);
+        assert!((x[1] - 0.0).abs() < 1e-5 && (y[1] - 1.0).abs() < 1e-5);
+
+        // The transfer function from `x` to `y` is `1 / (s² + 1)`.
+        let tf = &result.transfer_functions[0];
+        assert_eq!(tf.numerator, vec![1.0]);
+        assert_eq!(tf.denominator, vec![1.0, 0.0, 1.0]);
+    }
+
+    #[test]
+    fn stability() {
+        let th = Rc::new(stdlib::theories::th_signed_category());
+        let data = LinearSpectralProblemData {
+            coefficients: [(name("loop"), 2.0)].into(),
+            ..Default::default()
+        };
+        let result =
+            builder().linear_spectral_analysis(&stdlib::models::negative_loop(th.clone()), &data);
+        assert_eq!(result.stability, Some(LinearStability::Stable));
+        assert!((result.modes[0].eigenvalue.re + 2.0).abs() < 1e-6);
+        assert_eq!(result.modes[0].eigenvector, vec![ComplexValue { re: 1.0, im: 0.0 }]);
+
+        let result = builder().linear_spectral_analysis(&stdlib::models::positive_loop(th), &data);
+        assert_eq!(result.stability, Some(LinearStability::Unstable));
+    }
+
+    #[test]
+    fn repeated_eigenvalues() {
+        let th = Rc::new(stdlib::theories::th_signed_category());
+        let mut model = DiscreteDblModel::new(th);
+        model.add_ob(name("x"), name("Object"));
+        model.add_ob(name("y"), name("Object"));
+        model.add_mor(name("xloop"), name("x"), name("x"), name("Negative").into());
+        model.add_mor(name("yloop"), name("y"), name("y"), name("Negative").into());
+        let data = LinearSpectralProblemData {
+            coefficients: [(name("xloop"), 1.0), (name("yloop"), 1.0)].into(),
+            initial_values: [(name("x"), 1.0)].into(),
+            times: vec![1.0],
+            ..Default::default()
+        };
+
+        // Two independent decays have a repeated eigenvalue with two modes.
+        let result = builder().linear_spectral_analysis(&model, &data);
+        assert_eq!(result.modes.len(), 2);
+        assert!(result.modes.iter().all(|mode| mode.multiplicity == 2));
+        let (u, v) = (&result.modes[0].eigenvector, &result.modes[1].eigenvector);
+        let dot: f32 = u.iter().zip(v).map(|(a, b)| a.re * b.re + a.im * b.im).sum();
+        assert!(dot.abs() < 1e-5);
+        assert_eq!(result.dominant_modes, vec![0, 1]);
+
+        // Coupling the decays makes the coefficient matrix defective, with only one
+        // mode for the repeated eigenvalue.
+        model.add_mor(name("coupling"), name("x"), name("y"), Path::Id(name("Object")));
+        let data = LinearSpectralProblemData {
+            coefficients: [(name("xloop"), 1.0), (name("yloop"), 1.0), (name("coupling"), 1.0)]
+                .into(),
+            ..data
+        };
+        let result = builder().linear_spectral_analysis(&model, &data);
+        assert_eq!(result.modes.len(), 1);
+        let mode = &result.modes[0];
+        assert_eq!(mode.multiplicity, 2);
+        assert!((mode.eigenvalue.re + 1.0).abs() < 1e-4);
+        assert!(mode.eigenvector[0].re.abs() < 1e-4);
+        assert!((mode.eigenvector[1].re - 1.0).abs() < 1e-4);
+
+        // The exact solution is still `x = e^{-t}, y = t e^{-t}`.
+        let y = &result.solution.states[&name("y")];
+        assert!((y[0] - (-1.0f32).exp()).abs() < 1e-5);
+    }
+}
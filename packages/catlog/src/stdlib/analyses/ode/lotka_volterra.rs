@@ -22,7 +22,7 @@ use crate::simulate::ode::{NumericalPolynomialSystem, ODEProblem, PolynomialSyst
 use crate::{
     dbl::model::DiscreteDblModel,
     one::QualifiedPath,
-    zero::{QualifiedName, alg::Polynomial, rig::Monomial},
+    zero::{QualifiedName, Rational, alg::Polynomial, rig::Monomial},
 };
 
 /// Data defining a Lotka-Volterra ODE problem for a model.
@@ -132,7 +132,7 @@ impl SignedCoefficientBuilder<QualifiedName, QualifiedPath> {
 
         let growth_rate_params = ob_index
             .keys()
-            .map(|ob| [(1.0, Monomial::generator(ob.clone()))].into_iter().collect());
+            .map(|ob| [(Rational::one(), Monomial::generator(ob.clone()))].into_iter().collect());
         let b = DVector::from_iterator(n, growth_rate_params);
 
         let system = lotka_volterra_system(&ob_index.keys().cloned().collect_vec(), matrix, b);
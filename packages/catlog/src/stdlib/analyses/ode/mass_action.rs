@@ -156,27 +156,27 @@ pub struct MassActionProblemData {
     /// for the balanced per transition case.
     /// N.B. This is renamed to "rates" in catlog-wasm for backwards compatibility.
     #[cfg_attr(feature = "serde", serde(rename = "rates"))]
-    transition_rates: HashMap<QualifiedName, f32>,
+    pub transition_rates: HashMap<QualifiedName, f32>,
 
     /// Map from morphism IDs to consumption rate coefficients (nonnegative reals),
     /// for the unbalanced per transition case.
     #[cfg_attr(feature = "serde", serde(rename = "transitionConsumptionRates"))]
-    transition_consumption_rates: HashMap<QualifiedName, f32>,
+    pub transition_consumption_rates: HashMap<QualifiedName, f32>,
 
     /// Map from morphism IDs to production rate coefficients (nonnegative reals),
     /// for the unbalanced per transition case.
     #[cfg_attr(feature = "serde", serde(rename = "transitionProductionRates"))]
-    transition_production_rates: HashMap<QualifiedName, f32>,
+    pub transition_production_rates: HashMap<QualifiedName, f32>,
 
     /// Map from morphism IDs to (map from input objects to consumption rate coefficients),
     /// for the unbalanced per place case (nonnegative reals).
     #[cfg_attr(feature = "serde", serde(rename = "placeConsumptionRates"))]
-    place_consumption_rates: HashMap<QualifiedName, HashMap<QualifiedName, f32>>,
+    pub place_consumption_rates: HashMap<QualifiedName, HashMap<QualifiedName, f32>>,
 
     /// Map from morphism IDs to (map from output objects to production rate coefficients),
     /// for the unbalanced per place case (nonnegative reals).
     #[cfg_attr(feature = "serde", serde(rename = "placeProductionRates"))]
-    place_production_rates: HashMap<QualifiedName, HashMap<QualifiedName, f32>>,
+    pub place_production_rates: HashMap<QualifiedName, HashMap<QualifiedName, f32>>,
 
     /// Map from object IDs to initial values (nonnegative reals).
     #[cfg_attr(feature = "serde", serde(rename = "initialValues"))]
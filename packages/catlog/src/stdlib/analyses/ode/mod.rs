@@ -1,22 +1,28 @@
 //! ODE analyses of models.
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use derivative::Derivative;
 use derive_more::Constructor;
 use indexmap::IndexMap;
-use ode_solvers::dop_shared::IntegrationError;
+use nalgebra::DVector;
+use ode_solvers::dop_shared::{IntegrationError, SolverResult};
 
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 #[cfg(feature = "serde-wasm")]
 use tsify::Tsify;
 
-use crate::simulate::ode::{ODEProblem, ODESystem};
-use crate::zero::{QualifiedName, alg::Polynomial};
+use crate::simulate::ode::{DDEError, DDEProblem, DDESystem, ODEProblem, ODESystem};
+use crate::stdlib::formats::table::{Column, DataFrame};
+use crate::zero::{Namespace, QualifiedName, Rational, alg::Polynomial};
 
 /// Symbolic parameter in a polynomial system.
-pub type Parameter<Id> = Polynomial<Id, f32, i8>;
+///
+/// Parameters have exact rational coefficients, so that symbolic manipulations of
+/// polynomial systems do not incur rounding errors. They are converted to floating
+/// point when [evaluated](Polynomial::eval) at numerical values.
+pub type Parameter<Id> = Polynomial<Id, Rational, i8>;
 
 /// Solution to an ODE problem.
 #[derive(Clone, Derivative)]
@@ -32,6 +38,93 @@ pub struct ODESolution {
     pub(in crate::stdlib::analyses) states: HashMap<QualifiedName, Vec<f32>>,
 }
 
+impl ODESolution {
+    /// Gets the values of the time variable.
+    pub fn time(&self) -> &[f32] {
+        &self.time
+    }
+
+    /// Gets the values of a state variable, if it is part of the solution.
+    pub fn state(&self, id: &QualifiedName) -> Option<&[f32]> {
+        self.states.get(id).map(|values| values.as_slice())
+    }
+
+    /// Iterates over the state variables and their values, in no particular order.
+    pub fn states(&self) -> impl Iterator<Item = (&QualifiedName, &[f32])> {
+        self.states.iter().map(|(id, values)| (id, values.as_slice()))
+    }
+
+    /// State variables sorted by their labels, paired with those labels.
+    ///
+    /// Labels are made distinct from each other and from the reserved names by
+    /// suffixing repeated labels with a number, so that they can name columns.
+    fn labelled_states(&self, namespace: &Namespace, reserved: &[&str]) -> Vec<(String, &[f32])> {
+        let mut states: Vec<_> = self
+            .states()
+            .map(|(id, values)| (namespace.label_string(id), id, values))
+            .collect();
+        states.sort_by(|(label1, id1, _), (label2, id2, _)| label1.cmp(label2).then(id1.cmp(id2)));
+
+        let mut used: HashSet<String> = reserved.iter().map(|name| name.to_string()).collect();
+        states
+            .into_iter()
+            .map(|(label, _, values)| {
+                let mut unique = label.clone();
+                let mut i = 1;
+                while used.contains(&unique) {
+                    i += 1;
+                    unique = format!("{label}_{i}");
+                }
+                used.insert(unique.clone());
+                (unique, values)
+            })
+            .collect()
+    }
+
+    /// Converts the solution to a data frame in wide format.
+    ///
+    /// The data frame has a `time` column followed by a column for each state
+    /// variable, named by its label and ordered alphabetically. Repeated labels,
+    /// including any label equal to `time`, are suffixed with a number.
+    pub fn to_data_frame(&self, namespace: &Namespace) -> DataFrame {
+        let mut df = DataFrame::new();
+        df.add_column("time", Column::Float(self.time.clone()));
+        for (label, values) in self.labelled_states(namespace, &["time"]) {
+            df.add_column(label, Column::Float(values.to_vec()));
+        }
+        df
+    }
+
+    /// Converts several solutions, such as a parameter sweep or an ensemble of
+    /// stochastic simulations, to a data frame in long format.
+    ///
+    /// The data frame has columns `run`, the index of the solution, `time`,
+    /// `variable`, the label of the state variable, and `value`. As in the
+    /// [wide format](Self::to_data_frame), repeated labels are suffixed with a
+    /// number.
+    pub fn ensemble_data_frame(solutions: &[ODESolution], namespace: &Namespace) -> DataFrame {
+        let (mut run, mut time, mut variable, mut value) =
+            (Vec::new(), Vec::new(), Vec::new(), Vec::new());
+        for (i, solution) in solutions.iter().enumerate() {
+            let states = solution.labelled_states(namespace, &[]);
+            for (j, t) in solution.time.iter().enumerate() {
+                for (label, values) in states.iter() {
+                    run.push(i as i32);
+                    time.push(*t);
+                    variable.push(label.clone());
+                    value.push(values[j]);
+                }
+            }
+        }
+        let mut df = DataFrame::new();
+        df.add_column("run", Column::Int(run));
+        df.add_column("time", Column::Float(time));
+        df.add_column("variable", Column::Text(variable));
+        df.add_column("value", Column::Float(value));
+        df
+    }
+}
+
 /// Data needed to simulate and interpret an ODE analysis of a model.
 #[derive(Constructor)]
 pub struct ODEAnalysis<Sys> {
@@ -53,9 +146,53 @@ impl<Sys> ODEAnalysis<Sys> {
             return Ok(Default::default());
         }
 
+        let result = self.solve_raw_with_defaults()?;
+        let (t_out, x_out) = result.get();
+        Ok(ODESolution {
+            time: t_out.clone(),
+            states: self
+                .variable_index
+                .into_iter()
+                .map(|(ob, i)| (ob, x_out.iter().map(|x| x[i]).collect()))
+                .collect(),
+        })
+    }
+
+    /// Solves the ODE with reasonable default settings, without collecting results.
+    fn solve_raw_with_defaults(&self) -> Result<SolverResult<f32, DVector<f32>>, IntegrationError>
+    where
+        Sys: ODESystem,
+    {
         let duration = self.problem.end_time - self.problem.start_time;
         let output_step_size = (duration / 100.0).min(0.01f32);
-        let result = self.problem.solve_dopri5(output_step_size)?;
+        self.problem.solve_dopri5(output_step_size)
+    }
+}
+
+/// Data needed to simulate and interpret a DDE analysis of a model.
+#[derive(Constructor)]
+pub struct DDEAnalysis<Sys> {
+    /// DDE problem for the analysis.
+    pub problem: DDEProblem<Sys>,
+
+    /// Map from IDs in model (usually object IDs) to variable indices.
+    pub variable_index: IndexMap<QualifiedName, usize>,
+}
+
+impl<Sys> DDEAnalysis<Sys> {
+    /// Solves the DDE by the method of steps with reasonable default settings and
+    /// collects results.
+    pub fn solve_with_defaults(self) -> Result<ODESolution, DDEError>
+    where
+        Sys: DDESystem,
+    {
+        if self.variable_index.is_empty() {
+            return Ok(Default::default());
+        }
+
+        let duration = self.problem.end_time - self.problem.start_time;
+        let step_size = (duration / 100.0).min(0.01f32);
+        let result = self.problem.solve_method_of_steps(step_size)?;
 
         let (t_out, x_out) = result.get();
         Ok(ODESolution {
@@ -69,16 +206,70 @@ impl<Sys> ODEAnalysis<Sys> {
     }
 }
 
+pub mod codegen;
 pub mod kuramoto;
+pub mod linear_dde;
 pub mod linear_ode;
+pub mod linear_spectral;
 pub mod lotka_volterra;
 pub mod mass_action;
 pub mod polynomial_ode;
+pub mod qualitative_stability;
 pub mod signed_coefficients;
 
+pub use codegen::*;
 pub use kuramoto::*;
+pub use linear_dde::*;
 pub use linear_ode::*;
+pub use linear_spectral::*;
 pub use lotka_volterra::*;
 pub use mass_action::*;
 pub use polynomial_ode::*;
+pub use qualitative_stability::*;
 pub use signed_coefficients::*;
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::zero::{label_seg, name};
+
+    #[test]
+    fn data_frames() {
+        let solution = ODESolution {
+            time: vec![0.0, 1.0],
+            states: [(name("y"), vec![2.0, 3.0]), (name("x"), vec![0.0, 1.0])].into(),
+        };
+        assert_eq!(solution.state(&name("y")), Some([2.0, 3.0].as_slice()));
+
+        let ns = Namespace::new_for_text();
+        let df = solution.to_data_frame(&ns);
+        assert_eq!(df.to_csv(), "time,x,y\n0,0,2\n1,1,3\n");
+
+        let df = ODESolution::ensemble_data_frame(&[solution.clone(), solution], &ns);
+        assert_eq!(df.nrows(), 8);
+        assert_eq!(df.column("run"), Some(&Column::Int(vec![0, 0, 0, 0, 1, 1, 1, 1])));
+        let variables = ["x", "y"].iter().cycle().take(8).map(|s| s.to_string()).collect();
+        assert_eq!(df.column("variable"), Some(&Column::Text(variables)));
+    }
+
+    #[test]
+    fn repeated_labels() {
+        let [a, b, c] = [(); 3].map(|_| Uuid::now_v7());
+        let mut ns = Namespace::new_for_uuid();
+        ns.set_label(a, label_seg("x"));
+        ns.set_label(b, label_seg("x"));
+        ns.set_label(c, label_seg("time"));
+        let solution = ODESolution {
+            time: vec![0.0],
+            states: [(b.into(), vec![2.0]), (a.into(), vec![1.0]), (c.into(), vec![3.0])].into(),
+        };
+        let df = solution.to_data_frame(&ns);
+        assert_eq!(df.to_csv(), "time,time_2,x,x_2\n0,3,1,2\n");
+
+        let df = ODESolution::ensemble_data_frame(&[solution], &ns);
+        let variables = ["time", "x", "x_2"].map(String::from).to_vec();
+        assert_eq!(df.column("variable"), Some(&Column::Text(variables)));
+    }
+}
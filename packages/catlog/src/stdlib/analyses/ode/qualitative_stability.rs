@@ -0,0 +1,688 @@
+//! Qualitative stability analysis of signed models.
+//!
+//! A signed graph, such as a causal loop diagram or a regulatory network,
+//! determines the *sign pattern* of the community matrix of a system linearized
+//! around an equilibrium, without fixing the magnitudes of its entries. This
+//! module implements two classical analyses of sign patterns. The first decides
+//! whether the pattern is *sign stable*, meaning that every matrix with the
+//! pattern is stable, using the conditions of Quirk and Ruppert together with the
+//! color test ([Jeffries, Klee & van den Driessche 1977](crate::refs::SignStability)).
+//! The second predicts the responses of the variables to sustained, or *press*,
+//! perturbations by loop analysis, weighting each prediction by the proportion of
+//! agreeing terms in the inverse community matrix
+//! ([Dambacher et al 2002](crate::refs::LoopAnalysis)).
+
+use std::collections::VecDeque;
+use std::ops::{Add, Mul, Neg};
+
+use nalgebra::DMatrix;
+use thiserror::Error;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "serde-wasm")]
+use tsify::Tsify;
+
+use super::SignedCoefficientBuilder;
+use crate::dbl::model::DiscreteDblModel;
+use crate::one::graph_algorithms::strongly_connected_components;
+use crate::one::{QualifiedPath, SkelGraph};
+use crate::zero::QualifiedName;
+
+/// Sign of a quantity known only qualitatively.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-wasm", derive(Tsify))]
+#[cfg_attr(feature = "serde-wasm", tsify(into_wasm_abi, from_wasm_abi))]
+pub enum Sign {
+    /// The quantity is zero.
+    #[default]
+    #[cfg_attr(feature = "serde", serde(rename = "zero"))]
+    Zero,
+
+    /// The quantity is positive.
+    #[cfg_attr(feature = "serde", serde(rename = "positive"))]
+    Positive,
+
+    /// The quantity is negative.
+    #[cfg_attr(feature = "serde", serde(rename = "negative"))]
+    Negative,
+
+    /// The sign of the quantity cannot be determined.
+    #[cfg_attr(feature = "serde", serde(rename = "indeterminate"))]
+    Indeterminate,
+}
+
+impl Sign {
+    /// Sign of a real number, indeterminate if the number is NaN.
+    pub fn of(x: f32) -> Self {
+        if x > 0.0 {
+            Sign::Positive
+        } else if x < 0.0 {
+            Sign::Negative
+        } else if x.is_nan() {
+            Sign::Indeterminate
+        } else {
+            Sign::Zero
+        }
+    }
+}
+
+impl Add for Sign {
+    type Output = Sign;
+
+    fn add(self, other: Sign) -> Sign {
+        match (self, other) {
+            (Sign::Zero, sign) | (sign, Sign::Zero) => sign,
+            (s, t) if s == t => s,
+            _ => Sign::Indeterminate,
+        }
+    }
+}
+
+impl Mul for Sign {
+    type Output = Sign;
+
+    fn mul(self, other: Sign) -> Sign {
+        match (self, other) {
+            (Sign::Zero, _) | (_, Sign::Zero) => Sign::Zero,
+            (Sign::Indeterminate, _) | (_, Sign::Indeterminate) => Sign::Indeterminate,
+            (s, t) if s == t => Sign::Positive,
+            _ => Sign::Negative,
+        }
+    }
+}
+
+impl Neg for Sign {
+    type Output = Sign;
+
+    fn neg(self) -> Sign {
+        match self {
+            Sign::Positive => Sign::Negative,
+            Sign::Negative => Sign::Positive,
+            sign => sign,
+        }
+    }
+}
+
+/// Outcome of a sign stability analysis.
+///
+/// Unless the sign pattern is stable, the outcome records the first condition for
+/// sign stability that fails, along with the objects witnessing the failure.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "tag", content = "content"))]
+#[cfg_attr(feature = "serde-wasm", derive(Tsify))]
+#[cfg_attr(feature = "serde-wasm", tsify(into_wasm_abi, from_wasm_abi))]
+pub enum SignStability {
+    /// Every system with the sign pattern is asymptotically stable.
+    Stable,
+
+    /// The effect of the first object on the second has indeterminate sign.
+    IndeterminateSign(QualifiedName, QualifiedName),
+
+    /// The object reinforces itself.
+    PositiveSelfRegulation(QualifiedName),
+
+    /// The two objects affect each other with the same sign, rather than as a
+    /// predator and its prey.
+    NonPredatorPreyPair(QualifiedName, QualifiedName),
+
+    /// The objects, in order, form a feedback loop of length at least three.
+    LongLoop(Vec<QualifiedName>),
+
+    /// Every system with the sign pattern has a zero eigenvalue.
+    Singular,
+
+    /// The pattern passes the color test, so that systems with the pattern can
+    /// have purely imaginary eigenvalues, oscillating in the given objects.
+    ColorTest(Vec<QualifiedName>),
+}
+
+impl SignStability {
+    /// Is the sign pattern stable?
+    pub fn is_stable(&self) -> bool {
+        matches!(self, SignStability::Stable)
+    }
+}
+
+/// Decides whether a sign pattern is sign stable.
+///
+/// The entry of the pattern at row `j` and column `i` is the sign of the effect of
+/// variable `i` on variable `j`, whose names are given by `variables`.
+pub fn sign_stability(signs: &DMatrix<Sign>, variables: &[QualifiedName]) -> SignStability {
+    let n = signs.nrows();
+    let var = |i: usize| variables[i].clone();
+
+    for i in 0..n {
+        for j in 0..n {
+            if signs[(j, i)] == Sign::Indeterminate {
+                return SignStability::IndeterminateSign(var(i), var(j));
+            }
+        }
+    }
+    for i in 0..n {
+        if signs[(i, i)] == Sign::Positive {
+            return SignStability::PositiveSelfRegulation(var(i));
+        }
+    }
+    for i in 0..n {
+        for j in (i + 1)..n {
+            if signs[(i, j)] * signs[(j, i)] == Sign::Positive {
+                return SignStability::NonPredatorPreyPair(var(i), var(j));
+            }
+        }
+    }
+    if let Some(cycle) = long_cycle(signs) {
+        return SignStability::LongLoop(cycle.into_iter().map(var).collect());
+    }
+    if !has_nonzero_determinant_term(signs) {
+        return SignStability::Singular;
+    }
+    if let Some(white) = color_test(signs) {
+        return SignStability::ColorTest(white.into_iter().map(var).collect());
+    }
+    SignStability::Stable
+}
+
+/// Does the variable `i` affect the distinct variable `j`?
+fn has_effect(signs: &DMatrix<Sign>, i: usize, j: usize) -> bool {
+    i != j && signs[(j, i)] != Sign::Zero
+}
+
+/// Finds an elementary cycle of length at least three, if there is one.
+///
+/// For each edge `i -> j`, searches breadth-first for a path back from `j` to `i`
+/// that does not use the edge `j -> i`.
+fn long_cycle(signs: &DMatrix<Sign>) -> Option<Vec<usize>> {
+    let n = signs.nrows();
+    for i in 0..n {
+        for j in (0..n).filter(|j| has_effect(signs, i, *j)) {
+            let mut pred = vec![None; n];
+            pred[j] = Some(j);
+            let mut queue = VecDeque::from([j]);
+            while let Some(u) = queue.pop_front() {
+                for v in 0..n {
+                    if pred[v].is_some() || !has_effect(signs, u, v) || (u, v) == (j, i) {
+                        continue;
+                    }
+                    if v == i {
+                        let mut path = Vec::new();
+                        let mut w = u;
+                        while w != j {
+                            path.push(w);
+                            w = pred[w].unwrap();
+                        }
+                        path.push(j);
+                        path.push(i);
+                        path.reverse();
+                        return Some(path);
+                    }
+                    pred[v] = Some(u);
+                    queue.push_back(v);
+                }
+            }
+        }
+    }
+    None
+}
+
+/// Does the expansion of the determinant have a nonzero term?
+///
+/// Equivalently, is there a perfect matching between rows and columns through the
+/// nonzero entries? Finds one using augmenting paths (Kuhn's algorithm).
+fn has_nonzero_determinant_term(signs: &DMatrix<Sign>) -> bool {
+    fn augment(
+        signs: &DMatrix<Sign>,
+        row: usize,
+        visited: &mut [bool],
+        matched: &mut [Option<usize>],
+    ) -> bool {
+        for col in 0..signs.ncols() {
+            if signs[(row, col)] == Sign::Zero || visited[col] {
+                continue;
+            }
+            visited[col] = true;
+            if matched[col].is_none_or(|other| augment(signs, other, visited, matched)) {
+                matched[col] = Some(row);
+                return true;
+            }
+        }
+        false
+    }
+
+    let n = signs.nrows();
+    let mut matched = vec![None; n];
+    (0..n).all(|row| augment(signs, row, &mut vec![false; n], &mut matched))
+}
+
+/// Searches for a coloring that passes the color test.
+///
+/// The vertices with self-regulation must be black, there must be at least one
+/// white vertex, every white vertex must have a white neighbor, and no black vertex
+/// may have exactly one white neighbor. Since the eigenvalues of a matrix are those
+/// of its diagonal blocks, the test is run on each strongly connected component.
+/// Assuming the previous conditions for sign stability hold, each component is a
+/// tree of predator-prey pairs, so the search is by dynamic programming on trees.
+fn color_test(signs: &DMatrix<Sign>) -> Option<Vec<usize>> {
+    let n = signs.nrows();
+    let mut graph = SkelGraph::default();
+    graph.add_vertices(n);
+    for i in 0..n {
+        for j in (0..n).filter(|j| has_effect(signs, i, *j)) {
+            graph.add_edge(i, j);
+        }
+    }
+    strongly_connected_components(&graph)
+        .into_iter()
+        .find_map(|component| color_tree(signs, &component))
+}
+
+/// Feasibility of coloring the subtree at a vertex, indexed by whether the vertex
+/// is white, whether its parent is white, and whether the subtree has a white
+/// vertex.
+type ColorFeasibility = [[[bool; 2]; 2]; 2];
+
+/// Reachable states when coloring the children of a vertex one at a time.
+///
+/// A state records the number of white neighbors of the vertex, capped at two, and
+/// whether any vertex seen so far is white. Each reachable state stores the
+/// previous state and the choice made for the last child.
+type ColorTable = Vec<[Option<(usize, bool, bool)>; 6]>;
+
+fn color_state(white_neighbors: usize, any_white: bool) -> usize {
+    2 * white_neighbors.min(2) + any_white as usize
+}
+
+fn color_table(
+    children: &[usize],
+    feasible: &[ColorFeasibility],
+    white: bool,
+    parent_white: bool,
+) -> ColorTable {
+    let mut table = vec![[None; 6]; children.len() + 1];
+    table[0][color_state(parent_white as usize, white)] = Some((0, false, false));
+    for (k, &child) in children.iter().enumerate() {
+        for state in 0..6 {
+            if table[k][state].is_none() {
+                continue;
+            }
+            let (count, any) = (state / 2, state % 2 == 1);
+            for child_white in [false, true] {
+                for child_any in [false, true] {
+                    if feasible[child][child_white as usize][white as usize][child_any as usize] {
+                        let next = color_state(count + child_white as usize, any || child_any);
+                        table[k + 1][next].get_or_insert((state, child_white, child_any));
+                    }
+                }
+            }
+        }
+    }
+    table
+}
+
+fn color_final_state(table: &ColorTable, white: bool, any_white: bool) -> Option<usize> {
+    (0..6).find(|state| {
+        let count = state / 2;
+        table.last().unwrap()[*state].is_some()
+            && (state % 2 == 1) == any_white
+            && if white { count >= 1 } else { count != 1 }
+    })
+}
+
+fn color_tree(signs: &DMatrix<Sign>, component: &[usize]) -> Option<Vec<usize>> {
+    // Root the tree and order its vertices breadth-first.
+    let mut local = vec![None; signs.nrows()];
+    for (k, v) in component.iter().enumerate() {
+        local[*v] = Some(k);
+    }
+    let mut children = vec![Vec::new(); component.len()];
+    let mut order = vec![0];
+    let mut seen = vec![false; component.len()];
+    seen[0] = true;
+    let mut next = 0;
+    while let Some(&u) = order.get(next) {
+        next += 1;
+        for v in component.iter().filter(|v| has_effect(signs, component[u], **v)) {
+            let v = local[*v].unwrap();
+            if !seen[v] {
+                seen[v] = true;
+                children[u].push(v);
+                order.push(v);
+            }
+        }
+    }
+
+    let mut feasible = vec![ColorFeasibility::default(); component.len()];
+    for &v in order.iter().rev() {
+        let black_only = signs[(component[v], component[v])] != Sign::Zero;
+        for white in [false, true] {
+            if white && black_only {
+                continue;
+            }
+            for parent_white in [false, true] {
+                let table = color_table(&children[v], &feasible, white, parent_white);
+                for any_white in [false, true] {
+                    feasible[v][white as usize][parent_white as usize][any_white as usize] =
+                        color_final_state(&table, white, any_white).is_some();
+                }
+            }
+        }
+    }
+
+    let root_white = [false, true].into_iter().find(|w| feasible[0][*w as usize][0][1])?;
+    let mut white_vertices = Vec::new();
+    let mut stack = vec![(0, root_white, false, true)];
+    while let Some((v, white, parent_white, any_white)) = stack.pop() {
+        if white {
+            white_vertices.push(component[v]);
+        }
+        let table = color_table(&children[v], &feasible, white, parent_white);
+        let mut state = color_final_state(&table, white, any_white).unwrap();
+        for (k, &child) in children[v].iter().enumerate().rev() {
+            let (prev, child_white, child_any) = table[k + 1][state].unwrap();
+            stack.push((child, child_white, white, child_any));
+            state = prev;
+        }
+    }
+    white_vertices.sort();
+    Some(white_vertices)
+}
+
+/// Result of a press perturbation analysis.
+///
+/// Both the predicted responses and their weights are indexed first by the
+/// variable being pressed and then by the responding variable.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-wasm", derive(Tsify))]
+#[cfg_attr(feature = "serde-wasm", tsify(into_wasm_abi, from_wasm_abi))]
+pub struct PressPerturbationResult {
+    /// Object IDs, in the order of the rows and columns of the predictions.
+    pub variables: Vec<QualifiedName>,
+
+    /// Predicted signs of the responses to a sustained increase in each variable.
+    pub responses: Vec<Vec<Sign>>,
+
+    /// Weights of the predictions, the absolute net number of terms in each
+    /// response divided by the total number of terms, between zero and one.
+    pub weights: Vec<Vec<f32>>,
+}
+
+/// Error in a press perturbation analysis.
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum PressPerturbationError {
+    /// The model has too many variables to count the terms in each response.
+    #[error("Press perturbation analysis of {0} variables is too large to compute")]
+    TooManyVariables(usize),
+
+    /// The community matrix is singular, so it has no inverse.
+    #[error("Every system with the sign pattern of the model is singular")]
+    Singular,
+}
+
+/// Maximum number of variables supported by press perturbation analysis.
+pub const MAX_PRESS_PERTURBATION_VARIABLES: usize = 16;
+
+/// Numbers of terms of each sign in the expansion of a determinant.
+#[derive(Clone, Copy, Debug, Default)]
+struct TermCounts {
+    positive: u64,
+    negative: u64,
+    indeterminate: u64,
+}
+
+impl TermCounts {
+    /// Adds the given terms, each multiplied by the given sign.
+    fn add_multiple(&mut self, terms: &TermCounts, sign: Sign) {
+        match sign {
+            Sign::Zero => {}
+            Sign::Positive => {
+                self.positive += terms.positive;
+                self.negative += terms.negative;
+                self.indeterminate += terms.indeterminate;
+            }
+            Sign::Negative => {
+                self.positive += terms.negative;
+                self.negative += terms.positive;
+                self.indeterminate += terms.indeterminate;
+            }
+            Sign::Indeterminate => {
+                self.indeterminate += terms.positive + terms.negative + terms.indeterminate;
+            }
+        }
+    }
+
+    fn total(&self) -> u64 {
+        self.positive + self.negative + self.indeterminate
+    }
+
+    fn sign(&self) -> Sign {
+        match (self.positive, self.negative, self.indeterminate) {
+            (0, 0, 0) => Sign::Zero,
+            (_, 0, 0) => Sign::Positive,
+            (0, _, 0) => Sign::Negative,
+            _ => Sign::Indeterminate,
+        }
+    }
+
+    fn weight(&self) -> f32 {
+        match self.total() {
+            0 => 0.0,
+            total => self.positive.abs_diff(self.negative) as f32 / total as f32,
+        }
+    }
+}
+
+/// Counts the terms of each sign in the determinant of a square submatrix.
+///
+/// Assigns the rows to columns in order, by dynamic programming over the sets of
+/// columns already used.
+fn determinant_terms(signs: &DMatrix<Sign>, rows: &[usize], cols: &[usize]) -> TermCounts {
+    let m = rows.len();
+    let mut terms = vec![TermCounts::default(); 1 << m];
+    terms[0].positive = 1;
+    for used in 0..(1usize << m) {
+        let row = used.count_ones() as usize;
+        if row == m || terms[used].total() == 0 {
+            continue;
+        }
+        let current = terms[used];
+        for (c, col) in cols.iter().enumerate().filter(|(c, _)| used & (1 << c) == 0) {
+            let mut sign = signs[(rows[row], *col)];
+            // Earlier rows assigned to later columns are inversions of the permutation.
+            if (used >> (c + 1)).count_ones() % 2 == 1 {
+                sign = -sign;
+            }
+            terms[used | (1 << c)].add_multiple(&current, sign);
+        }
+    }
+    terms[(1 << m) - 1]
+}
+
+/// Predicts the responses of a sign pattern to press perturbations.
+///
+/// A sustained increase in variable `j` shifts the equilibrium by the column `j` of
+/// the matrix `-A⁻¹`, where `A` is the community matrix. Loop analysis predicts the
+/// sign of each entry from the terms in the expansion of the corresponding
+/// cofactor, assuming that the equilibrium is stable, so that the determinant of
+/// `A` has sign `(-1)ⁿ`.
+pub fn press_perturbation(
+    signs: &DMatrix<Sign>,
+    variables: Vec<QualifiedName>,
+) -> Result<PressPerturbationResult, PressPerturbationError> {
+    let n = signs.nrows();
+    if n > MAX_PRESS_PERTURBATION_VARIABLES {
+        return Err(PressPerturbationError::TooManyVariables(n));
+    }
+    let all: Vec<_> = (0..n).collect();
+    if n > 0 && determinant_terms(signs, &all, &all).total() == 0 {
+        return Err(PressPerturbationError::Singular);
+    }
+
+    let mut responses = vec![vec![Sign::Zero; n]; n];
+    let mut weights = vec![vec![0.0; n]; n];
+    for j in 0..n {
+        for i in 0..n {
+            // The response is the minor without row `j` and column `i`, times the
+            // sign `-(-1)^(i+j)` of the cofactor and inverse, times that of `1/det A`.
+            let rows: Vec<_> = all.iter().copied().filter(|k| *k != j).collect();
+            let cols: Vec<_> = all.iter().copied().filter(|k| *k != i).collect();
+            let terms = determinant_terms(signs, &rows, &cols);
+            let sign = terms.sign();
+            responses[j][i] = if (i + j + n + 1).is_multiple_of(2) {
+                sign
+            } else {
+                -sign
+            };
+            weights[j][i] = terms.weight();
+        }
+    }
+    Ok(PressPerturbationResult { variables, responses, weights })
+}
+
+impl SignedCoefficientBuilder<QualifiedName, QualifiedPath> {
+    /// Sign stability analysis for a model of a double theory.
+    ///
+    /// Decides whether every linear system whose coefficients have the signs of the
+    /// morphisms in the model is asymptotically stable.
+    pub fn sign_stability_analysis(&self, model: &DiscreteDblModel) -> SignStability {
+        let (signs, ob_index) = self.build_sign_pattern(model);
+        let variables: Vec<_> = ob_index.into_keys().collect();
+        sign_stability(&signs, &variables)
+    }
+
+    /// Press perturbation analysis for a model of a double theory.
+    ///
+    /// Predicts how each object responds to a sustained increase in each object,
+    /// assuming that the system is at a stable equilibrium.
+    pub fn press_perturbation_analysis(
+        &self,
+        model: &DiscreteDblModel,
+    ) -> Result<PressPerturbationResult, PressPerturbationError> {
+        let (signs, ob_index) = self.build_sign_pattern(model);
+        press_perturbation(&signs, ob_index.into_keys().collect())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::rc::Rc;
+
+    use super::*;
+    use crate::dbl::model::MutDblModel;
+    use crate::stdlib::{models::*, theories::*};
+    use crate::{one::Path, zero::name};
+
+    fn builder() -> SignedCoefficientBuilder<QualifiedName, QualifiedPath> {
+        SignedCoefficientBuilder::new(name("Object"))
+            .add_positive(Path::Id(name("Object")))
+            .add_negative(Path::single(name("Negative")))
+    }
+
+    #[test]
+    fn predator_prey_stability() {
+        let th = Rc::new(th_signed_category());
+        let mut model = negative_feedback(th);
+        assert_eq!(
+            builder().sign_stability_analysis(&model),
+            SignStability::ColorTest(vec![name("x"), name("y")])
+        );
+
+        model.add_mor(name("damping"), name("x"), name("x"), name("Negative").into());
+        assert!(builder().sign_stability_analysis(&model).is_stable());
+
+        model.add_ob(name("z"), name("Object"));
+        model.add_mor(name("prey"), name("y"), name("z"), Path::Id(name("Object")));
+        model.add_mor(name("predator"), name("z"), name("y"), name("Negative").into());
+        assert!(builder().sign_stability_analysis(&model).is_stable());
+
+        model.add_mor(name("growth"), name("z"), name("z"), Path::Id(name("Object")));
+        assert_eq!(
+            builder().sign_stability_analysis(&model),
+            SignStability::PositiveSelfRegulation(name("z"))
+        );
+    }
+
+    #[test]
+    fn unstable_patterns() {
+        let th = Rc::new(th_signed_category());
+        let model = positive_feedback(th.clone());
+        assert_eq!(
+            builder().sign_stability_analysis(&model),
+            SignStability::NonPredatorPreyPair(name("x"), name("y"))
+        );
+
+        let mut model = DiscreteDblModel::new(th);
+        for x in ["x", "y", "z"] {
+            model.add_ob(name(x), name("Object"));
+            model.add_mor(
+                name(format!("d{x}").as_str()),
+                name(x),
+                name(x),
+                name("Negative").into(),
+            );
+        }
+        model.add_mor(name("f"), name("x"), name("y"), Path::Id(name("Object")));
+        model.add_mor(name("g"), name("y"), name("z"), Path::Id(name("Object")));
+        model.add_mor(name("h"), name("z"), name("x"), Path::Id(name("Object")));
+        assert_eq!(
+            builder().sign_stability_analysis(&model),
+            SignStability::LongLoop(vec![name("x"), name("y"), name("z")])
+        );
+
+        // A predator that is its prey's only link to the rest of the system.
+        let mut model = DiscreteDblModel::new(Rc::new(th_signed_category()));
+        for x in ["x", "y", "z"] {
+            model.add_ob(name(x), name("Object"));
+        }
+        model.add_mor(name("dy"), name("y"), name("y"), name("Negative").into());
+        model.add_mor(name("f"), name("x"), name("y"), Path::Id(name("Object")));
+        model.add_mor(name("g"), name("y"), name("x"), name("Negative").into());
+        model.add_mor(name("h"), name("z"), name("y"), Path::Id(name("Object")));
+        model.add_mor(name("k"), name("y"), name("z"), name("Negative").into());
+        assert_eq!(builder().sign_stability_analysis(&model), SignStability::Singular);
+    }
+
+    #[test]
+    fn press_perturbation_responses() {
+        let th = Rc::new(th_signed_category());
+        let mut model = negative_feedback(th);
+        model.add_mor(name("damping"), name("x"), name("x"), name("Negative").into());
+        let result = builder().press_perturbation_analysis(&model).unwrap();
+        assert_eq!(result.variables, vec![name("x"), name("y")]);
+        assert_eq!(
+            result.responses,
+            vec![vec![Sign::Zero, Sign::Positive], vec![Sign::Negative, Sign::Positive]]
+        );
+        assert_eq!(result.weights, vec![vec![0.0, 1.0], vec![1.0, 1.0]]);
+
+        let model = negative_feedback(Rc::new(th_signed_category()));
+        assert!(builder().press_perturbation_analysis(&model).is_ok());
+        let model = negative_loop(Rc::new(th_signed_category()));
+        assert_eq!(
+            builder().press_perturbation_analysis(&model).map(|result| result.responses),
+            Ok(vec![vec![Sign::Positive]])
+        );
+    }
+
+    #[test]
+    fn indeterminate_links() {
+        let th = Rc::new(th_nullable_signed_category());
+        let model = indeterminate_loop(th);
+        let builder = builder().add_indeterminate(name("Zero").into());
+        assert_eq!(
+            builder.sign_stability_analysis(&model),
+            SignStability::IndeterminateSign(name("x"), name("x"))
+        );
+        let result = builder.press_perturbation_analysis(&model).unwrap();
+        assert_eq!(result.responses, vec![vec![Sign::Positive]]);
+
+        let mut model = negative_feedback(Rc::new(th_nullable_signed_category()));
+        model.add_mor(name("unknown"), name("x"), name("x"), name("Zero").into());
+        let result = builder.press_perturbation_analysis(&model).unwrap();
+        assert_eq!(result.responses[0], vec![Sign::Zero, Sign::Positive]);
+        assert_eq!(result.responses[1], vec![Sign::Negative, Sign::Indeterminate]);
+        assert_eq!(result.weights[1], vec![1.0, 0.0]);
+    }
+}
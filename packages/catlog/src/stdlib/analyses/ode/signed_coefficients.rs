@@ -2,22 +2,24 @@
 
 use indexmap::IndexMap;
 use nalgebra::DMatrix;
-use num_traits::zero;
+use num_traits::{One, zero};
 
-use super::Parameter;
+use super::{Parameter, Sign};
 use crate::{
     dbl::model::FpDblModel,
-    zero::{QualifiedName, rig::Monomial},
+    zero::{QualifiedName, Rational, rig::Monomial},
 };
 
 /// Builder for signed coefficient matrices and analyses based on them.
 ///
 /// Used to construct the [linear](Self::linear_ode_analysis) and
-/// [Lotka-Volterra](Self::lotka_volterra_analysis) ODE analyses.
+/// [Lotka-Volterra](Self::lotka_volterra_analysis) ODE analyses, as well as the
+/// [qualitative stability](Self::sign_stability_analysis) analyses.
 pub struct SignedCoefficientBuilder<ObType, MorType> {
     var_ob_type: ObType,
     positive_mor_types: Vec<MorType>,
     negative_mor_types: Vec<MorType>,
+    indeterminate_mor_types: Vec<MorType>,
 }
 
 impl<ObType, MorType> SignedCoefficientBuilder<ObType, MorType> {
@@ -27,6 +29,7 @@ impl<ObType, MorType> SignedCoefficientBuilder<ObType, MorType> {
             var_ob_type,
             positive_mor_types: Vec::new(),
             negative_mor_types: Vec::new(),
+            indeterminate_mor_types: Vec::new(),
         }
     }
 
@@ -42,6 +45,16 @@ impl<ObType, MorType> SignedCoefficientBuilder<ObType, MorType> {
         self
     }
 
+    /// Adds a morphism type defining an interaction of indeterminate sign.
+    ///
+    /// Such interactions have no numerical coefficient, so they are ignored when
+    /// building the [coefficient matrix](Self::build_matrix) but not the [sign
+    /// pattern](Self::build_sign_pattern).
+    pub fn add_indeterminate(mut self, mor_type: MorType) -> Self {
+        self.indeterminate_mor_types.push(mor_type);
+        self
+    }
+
     /// Builds the matrix of symbolic coefficients for the given model.
     ///
     /// Returns the coefficient matrix along with an ordered map from object
@@ -68,17 +81,48 @@ impl<ObType, MorType> SignedCoefficientBuilder<ObType, MorType> {
             for mor in model.mor_generators_with_type(mor_type) {
                 let i = *ob_index.get(&model.mor_generator_dom(&mor)).unwrap();
                 let j = *ob_index.get(&model.mor_generator_cod(&mor)).unwrap();
-                mat[(j, i)] += (1.0, Monomial::generator(mor));
+                mat[(j, i)] += (Rational::one(), Monomial::generator(mor));
             }
         }
         for mor_type in self.negative_mor_types.iter() {
             for mor in model.mor_generators_with_type(mor_type) {
                 let i = *ob_index.get(&model.mor_generator_dom(&mor)).unwrap();
                 let j = *ob_index.get(&model.mor_generator_cod(&mor)).unwrap();
-                mat[(j, i)] += (-1.0, Monomial::generator(mor));
+                mat[(j, i)] += (-Rational::one(), Monomial::generator(mor));
             }
         }
 
         (mat, ob_index)
     }
+
+    /// Builds the sign pattern of the coefficient matrix for the given model.
+    ///
+    /// An entry is positive or negative when all of its interactions have that
+    /// sign, zero when there are no interactions, and otherwise indeterminate.
+    pub fn build_sign_pattern(
+        &self,
+        model: &impl FpDblModel<
+            ObType = ObType,
+            MorType = MorType,
+            Ob = QualifiedName,
+            ObGen = QualifiedName,
+            MorGen = QualifiedName,
+        >,
+    ) -> (DMatrix<Sign>, IndexMap<QualifiedName, usize>) {
+        let (mat, ob_index) = self.build_matrix(model);
+        let mut signs = mat.map(|entry| {
+            entry
+                .coefficients()
+                .map(|coef| Sign::of(coef.to_f32()))
+                .fold(Sign::Zero, |acc, sign| acc + sign)
+        });
+        for mor_type in self.indeterminate_mor_types.iter() {
+            for mor in model.mor_generators_with_type(mor_type) {
+                let i = *ob_index.get(&model.mor_generator_dom(&mor)).unwrap();
+                let j = *ob_index.get(&model.mor_generator_cod(&mor)).unwrap();
+                signs[(j, i)] = Sign::Indeterminate;
+            }
+        }
+        (signs, ob_index)
+    }
 }
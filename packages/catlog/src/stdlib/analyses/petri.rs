@@ -1,8 +1,8 @@
 //! Helpers for analyses on Petri nets.
 
 use crate::dbl::model::{ModalDblModel, ModalOb, MutDblModel};
-use crate::dbl::theory::Unital;
-use crate::zero::QualifiedName;
+use crate::dbl::theory::{List, Unital};
+use crate::zero::{QualifiedName, name};
 
 /// Gets the inputs and outputs of a transition in a Petri net.
 pub fn transition_interface(
@@ -19,3 +19,12 @@ pub fn transition_interface(
         .unwrap_or_default();
     (inputs, outputs)
 }
+
+/// Constructs the input or output of a transition in a Petri net.
+///
+/// The result is the tensor product of the given places, with repeated places
+/// corresponding to arcs of higher multiplicity.
+pub fn transition_boundary(places: impl IntoIterator<Item = QualifiedName>) -> ModalOb {
+    let obs = places.into_iter().map(ModalOb::from).collect();
+    ModalOb::App(ModalOb::List(List::Symmetric, obs).into(), name("tensor"))
+}
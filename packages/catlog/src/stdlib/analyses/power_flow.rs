@@ -0,0 +1,739 @@
+//! Power flow analysis of power systems.
+//!
+//! Models of the [theory of power systems](crate::stdlib::theories::th_power_system)
+//! have buses as objects, lines and transformers as passive branches, and links as
+//! controllable branches. Following the conventions of
+//! [PyPSA](https://docs.pypsa.org/latest/user-guide/power-flow/), this module
+//! computes the steady-state power flow through such a network, either in the
+//! linearized DC approximation or in full by the Newton-Raphson method. All
+//! quantities are in per-unit and angles are in radians.
+//!
+//! The buses connected by passive branches form *sub-networks*, or islands, which
+//! are electrically independent. Links transfer fixed amounts of active power
+//! between buses, possibly in different sub-networks. Each sub-network has one
+//! slack bus, which absorbs the imbalance between supply and demand and serves as
+//! the reference for voltage angles.
+
+use std::collections::HashMap;
+
+use indexmap::IndexMap;
+use nalgebra::{Complex, DMatrix, DVector};
+use thiserror::Error;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "serde-wasm")]
+use tsify::Tsify;
+
+use crate::dbl::model::{DiscreteDblModel, FpDblModel};
+use crate::one::graph_algorithms::strongly_connected_components;
+use crate::one::{FgCategory, QualifiedPath, SkelGraph};
+use crate::zero::QualifiedName;
+
+/// How the power at a bus is controlled.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-wasm", derive(Tsify))]
+#[cfg_attr(feature = "serde-wasm", tsify(into_wasm_abi, from_wasm_abi))]
+pub enum BusControl {
+    /// Active and reactive power are fixed.
+    #[default]
+    PQ,
+
+    /// Active power and voltage magnitude are fixed.
+    PV,
+
+    /// Voltage magnitude and angle are fixed, absorbing any power imbalance.
+    Slack,
+}
+
+/// Electrical parameters of a passive branch, in per-unit.
+///
+/// A line is the special case of a transformer with tap ratio one and no phase
+/// shift. The tap is on the side of the source bus.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+#[cfg_attr(feature = "serde-wasm", derive(Tsify))]
+#[cfg_attr(feature = "serde-wasm", tsify(into_wasm_abi, from_wasm_abi))]
+pub struct BranchParameters {
+    /// Series resistance.
+    pub r: f64,
+
+    /// Series reactance.
+    pub x: f64,
+
+    /// Shunt conductance.
+    pub g: f64,
+
+    /// Shunt susceptance.
+    pub b: f64,
+
+    /// Ratio of the voltage magnitudes at the source and target.
+    #[cfg_attr(feature = "serde", serde(rename = "tapRatio"))]
+    pub tap_ratio: f64,
+
+    /// Phase shift of the voltage angle from source to target.
+    #[cfg_attr(feature = "serde", serde(rename = "phaseShift"))]
+    pub phase_shift: f64,
+}
+
+impl Default for BranchParameters {
+    fn default() -> Self {
+        Self {
+            r: 0.0,
+            x: 0.0,
+            g: 0.0,
+            b: 0.0,
+            tap_ratio: 1.0,
+            phase_shift: 0.0,
+        }
+    }
+}
+
+impl BranchParameters {
+    /// Admittance matrix of the branch as a two-port network.
+    ///
+    /// Returns the entries `(Y₀₀, Y₀₁, Y₁₀, Y₁₁)` relating the currents injected at
+    /// the source and target to the voltages there.
+    fn admittances(&self) -> [Complex<f64>; 4] {
+        let y = Complex::new(self.r, self.x).inv();
+        let y_shunt = Complex::new(self.g, self.b) / 2.0;
+        let tap = Complex::from_polar(self.tap_ratio, self.phase_shift);
+        [(y + y_shunt) / tap.norm_sqr(), -y / tap.conj(), -y / tap, y + y_shunt]
+    }
+}
+
+/// Data defining a power flow problem for a model.
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-wasm", derive(Tsify))]
+#[cfg_attr(
+    feature = "serde-wasm",
+    tsify(into_wasm_abi, from_wasm_abi, hashmap_as_object)
+)]
+pub struct PowerFlowProblemData {
+    /// Map from bus IDs to control types, defaulting to PQ.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub controls: HashMap<QualifiedName, BusControl>,
+
+    /// Map from bus IDs to net injections of active power, defaulting to zero.
+    #[cfg_attr(feature = "serde", serde(default, rename = "activePower"))]
+    pub active_power: HashMap<QualifiedName, f64>,
+
+    /// Map from bus IDs to net injections of reactive power, defaulting to zero.
+    #[cfg_attr(feature = "serde", serde(default, rename = "reactivePower"))]
+    pub reactive_power: HashMap<QualifiedName, f64>,
+
+    /// Map from bus IDs to voltage magnitude setpoints, defaulting to one.
+    #[cfg_attr(feature = "serde", serde(default, rename = "voltageMagnitudes"))]
+    pub voltage_magnitudes: HashMap<QualifiedName, f64>,
+
+    /// Map from passive branch IDs to electrical parameters.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub branches: HashMap<QualifiedName, BranchParameters>,
+
+    /// Map from link IDs to active power transferred from source to target.
+    #[cfg_attr(feature = "serde", serde(default, rename = "linkPower"))]
+    pub link_power: HashMap<QualifiedName, f64>,
+}
+
+/// Power flow solution at a bus.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-wasm", derive(Tsify))]
+#[cfg_attr(feature = "serde-wasm", tsify(into_wasm_abi, from_wasm_abi))]
+pub struct BusFlow {
+    /// ID of the bus.
+    pub bus: QualifiedName,
+
+    /// Index of the sub-network containing the bus.
+    #[cfg_attr(feature = "serde", serde(rename = "subNetwork"))]
+    pub sub_network: usize,
+
+    /// Voltage magnitude.
+    #[cfg_attr(feature = "serde", serde(rename = "voltageMagnitude"))]
+    pub voltage_magnitude: f64,
+
+    /// Voltage angle.
+    #[cfg_attr(feature = "serde", serde(rename = "voltageAngle"))]
+    pub voltage_angle: f64,
+
+    /// Net injection of active power.
+    #[cfg_attr(feature = "serde", serde(rename = "activePower"))]
+    pub active_power: f64,
+
+    /// Net injection of reactive power, zero in the DC approximation.
+    #[cfg_attr(feature = "serde", serde(rename = "reactivePower"))]
+    pub reactive_power: f64,
+}
+
+/// Power flow solution on a branch.
+///
+/// Powers are those flowing into the branch at each end, so that for a lossless
+/// branch the power at the target is the negative of that at the source.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-wasm", derive(Tsify))]
+#[cfg_attr(feature = "serde-wasm", tsify(into_wasm_abi, from_wasm_abi))]
+pub struct BranchFlow {
+    /// ID of the branch, passive or controllable.
+    pub branch: QualifiedName,
+
+    /// Active power flowing into the branch at its source.
+    pub p0: f64,
+
+    /// Reactive power flowing into the branch at its source.
+    pub q0: f64,
+
+    /// Active power flowing into the branch at its target.
+    pub p1: f64,
+
+    /// Reactive power flowing into the branch at its target.
+    pub q1: f64,
+}
+
+/// Result of a power flow analysis.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-wasm", derive(Tsify))]
+#[cfg_attr(feature = "serde-wasm", tsify(into_wasm_abi, from_wasm_abi))]
+pub struct PowerFlowResult {
+    /// Sub-networks of buses connected by passive branches.
+    #[cfg_attr(feature = "serde", serde(rename = "subNetworks"))]
+    pub sub_networks: Vec<Vec<QualifiedName>>,
+
+    /// Solutions at the buses.
+    pub buses: Vec<BusFlow>,
+
+    /// Solutions on the branches, passive ones followed by links.
+    pub branches: Vec<BranchFlow>,
+
+    /// Number of Newton-Raphson iterations taken, zero for DC power flow.
+    pub iterations: usize,
+}
+
+/// Error in a power flow analysis.
+#[derive(Debug, Error, PartialEq)]
+pub enum PowerFlowError {
+    /// A passive branch has zero series impedance.
+    #[error("Passive branch `{0}` has zero impedance")]
+    ZeroImpedance(QualifiedName),
+
+    /// The linear system for the power flow is singular.
+    #[error("Power flow equations are singular")]
+    Singular,
+
+    /// The Newton-Raphson method did not converge.
+    #[error("Power flow did not converge after {0} iterations")]
+    DidNotConverge(usize),
+}
+
+/// Power flow analysis of a model of a power system.
+pub struct PowerFlowAnalysis {
+    bus_ob_type: QualifiedName,
+    passive_mor_types: Vec<QualifiedPath>,
+    link_mor_types: Vec<QualifiedPath>,
+    tolerance: f64,
+    max_iterations: usize,
+}
+
+/// Power network assembled from a model and data.
+struct Network {
+    bus_index: IndexMap<QualifiedName, usize>,
+    controls: Vec<BusControl>,
+    sub_networks: Vec<Vec<usize>>,
+    sub_network_of: Vec<usize>,
+    branches: Vec<(QualifiedName, usize, usize, BranchParameters)>,
+    links: Vec<(QualifiedName, usize, usize, f64)>,
+    /// Specified injections of power, including transfers through links.
+    injections: Vec<Complex<f64>>,
+    voltage_magnitudes: Vec<f64>,
+}
+
+impl Network {
+    fn len(&self) -> usize {
+        self.bus_index.len()
+    }
+
+    /// Assembles the result from the voltages at the buses.
+    fn result(
+        &self,
+        voltages: &[Complex<f64>],
+        branch_powers: Vec<[Complex<f64>; 2]>,
+        iterations: usize,
+    ) -> PowerFlowResult {
+        let mut bus_powers = vec![Complex::default(); self.len()];
+        let mut branches = Vec::new();
+        for ((id, i, j, _), [s0, s1]) in self.branches.iter().zip(branch_powers) {
+            bus_powers[*i] += s0;
+            bus_powers[*j] += s1;
+            branches.push(BranchFlow {
+                branch: id.clone(),
+                p0: s0.re,
+                q0: s0.im,
+                p1: s1.re,
+                q1: s1.im,
+            });
+        }
+        for (id, i, j, p) in self.links.iter() {
+            bus_powers[*i] += p;
+            bus_powers[*j] -= p;
+            branches.push(BranchFlow {
+                branch: id.clone(),
+                p0: *p,
+                q0: 0.0,
+                p1: -p,
+                q1: 0.0,
+            });
+        }
+        let buses = self
+            .bus_index
+            .keys()
+            .enumerate()
+            .map(|(i, bus)| BusFlow {
+                bus: bus.clone(),
+                sub_network: self.sub_network_of[i],
+                voltage_magnitude: voltages[i].norm(),
+                voltage_angle: voltages[i].arg(),
+                active_power: bus_powers[i].re,
+                reactive_power: bus_powers[i].im,
+            })
+            .collect();
+        let sub_networks = self
+            .sub_networks
+            .iter()
+            .map(|buses| {
+                buses.iter().map(|i| self.bus_index.get_index(*i).unwrap().0.clone()).collect()
+            })
+            .collect();
+        PowerFlowResult {
+            sub_networks,
+            buses,
+            branches,
+            iterations,
+        }
+    }
+}
+
+impl PowerFlowAnalysis {
+    /// Constructs a power flow analysis with buses the objects of given type.
+    pub fn new(ob_type: QualifiedName) -> Self {
+        Self {
+            bus_ob_type: ob_type,
+            passive_mor_types: Default::default(),
+            link_mor_types: Default::default(),
+            tolerance: 1e-8,
+            max_iterations: 30,
+        }
+    }
+
+    /// Adds a type of morphism to be treated as passive branches.
+    pub fn add_passive_type(mut self, mor_type: QualifiedPath) -> Self {
+        self.passive_mor_types.push(mor_type);
+        self
+    }
+
+    /// Adds a type of morphism to be treated as links.
+    pub fn add_link_type(mut self, mor_type: QualifiedPath) -> Self {
+        self.link_mor_types.push(mor_type);
+        self
+    }
+
+    /// Sets the tolerance on power mismatches for the Newton-Raphson method.
+    pub fn tolerance(mut self, tolerance: f64) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    /// Sets the maximum number of iterations of the Newton-Raphson method.
+    pub fn max_iterations(mut self, max_iterations: usize) -> Self {
+        self.max_iterations = max_iterations;
+        self
+    }
+
+    /// Finds the sub-networks of a model, the buses connected by passive branches.
+    ///
+    /// The sub-networks are sorted by their first bus.
+    pub fn sub_networks(&self, model: &DiscreteDblModel) -> Vec<Vec<QualifiedName>> {
+        let network = self.build_network(model, &Default::default());
+        network
+            .result(&vec![Complex::default(); network.len()], Vec::new(), 0)
+            .sub_networks
+    }
+
+    fn build_network(&self, model: &DiscreteDblModel, data: &PowerFlowProblemData) -> Network {
+        let bus_index: IndexMap<_, _> = model
+            .ob_generators_with_type(&self.bus_ob_type)
+            .enumerate()
+            .map(|(i, x)| (x, i))
+            .collect();
+        let n = bus_index.len();
+        let endpoints = |mor: &QualifiedName| {
+            let (dom, cod) = (model.mor_generator_dom(mor), model.mor_generator_cod(mor));
+            (*bus_index.get(&dom).unwrap(), *bus_index.get(&cod).unwrap())
+        };
+
+        let mut branches = Vec::new();
+        for mor_type in self.passive_mor_types.iter() {
+            for mor in model.mor_generators_with_type(mor_type) {
+                let (i, j) = endpoints(&mor);
+                let params = data.branches.get(&mor).copied().unwrap_or_default();
+                branches.push((mor, i, j, params));
+            }
+        }
+        let mut links = Vec::new();
+        for mor_type in self.link_mor_types.iter() {
+            for mor in model.mor_generators_with_type(mor_type) {
+                let (i, j) = endpoints(&mor);
+                let power = data.link_power.get(&mor).copied().unwrap_or_default();
+                links.push((mor, i, j, power));
+            }
+        }
+
+        // Sub-networks are the connected components of the passive branches.
+        let mut graph = SkelGraph::default();
+        graph.add_vertices(n);
+        for (_, i, j, _) in branches.iter() {
+            graph.add_edge(*i, *j);
+            graph.add_edge(*j, *i);
+        }
+        let mut sub_networks = strongly_connected_components(&graph);
+        for buses in sub_networks.iter_mut() {
+            buses.sort();
+        }
+        sub_networks.sort();
+        let mut sub_network_of = vec![0; n];
+        for (k, buses) in sub_networks.iter().enumerate() {
+            for i in buses {
+                sub_network_of[*i] = k;
+            }
+        }
+
+        // Each sub-network has exactly one slack bus, by default its first bus.
+        let mut controls: Vec<_> = bus_index
+            .keys()
+            .map(|bus| data.controls.get(bus).copied().unwrap_or_default())
+            .collect();
+        for buses in sub_networks.iter() {
+            let slack = buses.iter().copied().find(|i| controls[*i] == BusControl::Slack);
+            let slack = slack.unwrap_or(buses[0]);
+            for i in buses {
+                if controls[*i] == BusControl::Slack {
+                    controls[*i] = BusControl::PV;
+                }
+            }
+            controls[slack] = BusControl::Slack;
+        }
+
+        let mut injections: Vec<_> = bus_index
+            .keys()
+            .map(|bus| {
+                let p = data.active_power.get(bus).copied().unwrap_or_default();
+                let q = data.reactive_power.get(bus).copied().unwrap_or_default();
+                Complex::new(p, q)
+            })
+            .collect();
+        for (_, i, j, power) in links.iter() {
+            injections[*i] -= power;
+            injections[*j] += power;
+        }
+        let voltage_magnitudes = bus_index
+            .keys()
+            .map(|bus| data.voltage_magnitudes.get(bus).copied().unwrap_or(1.0))
+            .collect();
+
+        Network {
+            bus_index,
+            controls,
+            sub_networks,
+            sub_network_of,
+            branches,
+            links,
+            injections,
+            voltage_magnitudes,
+        }
+    }
+
+    /// Linearized (DC) power flow for a model.
+    ///
+    /// Assumes that branches are lossless, that voltage magnitudes are one, and
+    /// that differences in voltage angle are small, so that active power flows
+    /// linearly in the angle differences and reactive power is neglected.
+    pub fn dc_power_flow(
+        &self,
+        model: &DiscreteDblModel,
+        data: &PowerFlowProblemData,
+    ) -> Result<PowerFlowResult, PowerFlowError> {
+        let network = self.build_network(model, data);
+        let n = network.len();
+
+        let mut susceptances = Vec::new();
+        let mut matrix = DMatrix::<f64>::zeros(n, n);
+        let mut rhs = DVector::from_iterator(n, network.injections.iter().map(|s| s.re));
+        for (id, i, j, params) in network.branches.iter() {
+            let x = params.x * params.tap_ratio;
+            if x == 0.0 {
+                return Err(PowerFlowError::ZeroImpedance(id.clone()));
+            }
+            let b = 1.0 / x;
+            matrix[(*i, *i)] += b;
+            matrix[(*j, *j)] += b;
+            matrix[(*i, *j)] -= b;
+            matrix[(*j, *i)] -= b;
+            rhs[*i] += b * params.phase_shift;
+            rhs[*j] -= b * params.phase_shift;
+            susceptances.push(b);
+        }
+
+        let unknowns: Vec<_> =
+            (0..n).filter(|i| network.controls[*i] != BusControl::Slack).collect();
+        let reduced_matrix = matrix.select_rows(&unknowns).select_columns(&unknowns);
+        let reduced_rhs = rhs.select_rows(&unknowns);
+        let reduced_angles =
+            reduced_matrix.lu().solve(&reduced_rhs).ok_or(PowerFlowError::Singular)?;
+        let mut angles = vec![0.0; n];
+        for (k, i) in unknowns.iter().enumerate() {
+            angles[*i] = reduced_angles[k];
+        }
+
+        let voltages: Vec<_> =
+            angles.iter().map(|theta| Complex::from_polar(1.0, *theta)).collect();
+        let branch_powers = network
+            .branches
+            .iter()
+            .zip(susceptances)
+            .map(|((_, i, j, params), b)| {
+                let p = b * (angles[*i] - angles[*j] - params.phase_shift);
+                [Complex::new(p, 0.0), Complex::new(-p, 0.0)]
+            })
+            .collect();
+        Ok(network.result(&voltages, branch_powers, 0))
+    }
+
+    /// Full (AC) power flow for a model, solved by the Newton-Raphson method.
+    ///
+    /// Starts from a flat profile, with voltage angles zero and voltage magnitudes
+    /// one except where fixed.
+    pub fn ac_power_flow(
+        &self,
+        model: &DiscreteDblModel,
+        data: &PowerFlowProblemData,
+    ) -> Result<PowerFlowResult, PowerFlowError> {
+        let network = self.build_network(model, data);
+        let n = network.len();
+
+        let mut admittance = DMatrix::<Complex<f64>>::zeros(n, n);
+        let mut branch_admittances = Vec::new();
+        for (id, i, j, params) in network.branches.iter() {
+            if params.r == 0.0 && params.x == 0.0 {
+                return Err(PowerFlowError::ZeroImpedance(id.clone()));
+            }
+            let [y00, y01, y10, y11] = params.admittances();
+            admittance[(*i, *i)] += y00;
+            admittance[(*i, *j)] += y01;
+            admittance[(*j, *i)] += y10;
+            admittance[(*j, *j)] += y11;
+            branch_admittances.push([y00, y01, y10, y11]);
+        }
+
+        let pv_pq: Vec<_> = (0..n).filter(|i| network.controls[*i] != BusControl::Slack).collect();
+        let pq: Vec<_> = (0..n).filter(|i| network.controls[*i] == BusControl::PQ).collect();
+        let (m_angle, m_mag) = (pv_pq.len(), pq.len());
+
+        let mut magnitudes: Vec<_> = (0..n)
+            .map(|i| match network.controls[i] {
+                BusControl::PQ => 1.0,
+                _ => network.voltage_magnitudes[i],
+            })
+            .collect();
+        let mut angles = vec![0.0; n];
+
+        let mut iterations = 0;
+        loop {
+            let voltages: Vec<_> =
+                (0..n).map(|i| Complex::from_polar(magnitudes[i], angles[i])).collect();
+            let v = DVector::from_column_slice(&voltages);
+            let currents = &admittance * &v;
+            let mismatch: Vec<_> = (0..n)
+                .map(|i| voltages[i] * currents[i].conj() - network.injections[i])
+                .collect();
+            let residual = DVector::from_iterator(
+                m_angle + m_mag,
+                pv_pq.iter().map(|i| mismatch[*i].re).chain(pq.iter().map(|i| mismatch[*i].im)),
+            );
+
+            if residual.amax() < self.tolerance {
+                let branch_powers = network
+                    .branches
+                    .iter()
+                    .zip(branch_admittances)
+                    .map(|((_, i, j, _), [y00, y01, y10, y11])| {
+                        let (v0, v1) = (voltages[*i], voltages[*j]);
+                        let s0 = v0 * (y00 * v0 + y01 * v1).conj();
+                        let s1 = v1 * (y10 * v0 + y11 * v1).conj();
+                        [s0, s1]
+                    })
+                    .collect();
+                return Ok(network.result(&voltages, branch_powers, iterations));
+            }
+            if iterations == self.max_iterations {
+                return Err(PowerFlowError::DidNotConverge(iterations));
+            }
+            iterations += 1;
+
+            // Derivatives of the complex power injections with respect to the voltage
+            // angles and magnitudes.
+            let d_angle = |i: usize, k: usize| {
+                let mut d = -admittance[(i, k)] * voltages[k];
+                if i == k {
+                    d += currents[i];
+                }
+                Complex::<f64>::i() * voltages[i] * d.conj()
+            };
+            let d_mag = |i: usize, k: usize| {
+                let unit = voltages[k] / magnitudes[k];
+                let mut d = voltages[i] * (admittance[(i, k)] * unit).conj();
+                if i == k {
+                    d += currents[i].conj() * unit;
+                }
+                d
+            };
+            let jacobian = DMatrix::from_fn(m_angle + m_mag, m_angle + m_mag, |r, c| {
+                let i = if r < m_angle {
+                    pv_pq[r]
+                } else {
+                    pq[r - m_angle]
+                };
+                let d = if c < m_angle {
+                    d_angle(i, pv_pq[c])
+                } else {
+                    d_mag(i, pq[c - m_angle])
+                };
+                if r < m_angle { d.re } else { d.im }
+            });
+            let step = jacobian.lu().solve(&residual).ok_or(PowerFlowError::Singular)?;
+            for (k, i) in pv_pq.iter().enumerate() {
+                angles[*i] -= step[k];
+            }
+            for (k, i) in pq.iter().enumerate() {
+                magnitudes[*i] -= step[m_angle + k];
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use super::*;
+    use crate::dbl::model::MutDblModel;
+    use crate::one::Path;
+    use crate::stdlib::theories::th_power_system;
+    use crate::zero::name;
+
+    fn analysis() -> PowerFlowAnalysis {
+        PowerFlowAnalysis::new(name("Bus"))
+            .add_passive_type(Path::empty(name("Bus")))
+            .add_passive_type(name("Passive").into())
+            .add_link_type(name("Branch").into())
+    }
+
+    fn two_bus_model() -> DiscreteDblModel {
+        let mut model = DiscreteDblModel::new(Rc::new(th_power_system()));
+        model.add_ob(name("a"), name("Bus"));
+        model.add_ob(name("b"), name("Bus"));
+        model.add_mor(name("line"), name("a"), name("b"), Path::empty(name("Bus")));
+        model
+    }
+
+    fn two_bus_data(r: f64) -> PowerFlowProblemData {
+        PowerFlowProblemData {
+            active_power: [(name("b"), -0.5)].into(),
+            branches: [(name("line"), BranchParameters { r, x: 0.1, ..Default::default() })].into(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn dc_two_buses() {
+        let result = analysis().dc_power_flow(&two_bus_model(), &two_bus_data(0.0)).unwrap();
+        assert_eq!(result.sub_networks, vec![vec![name("a"), name("b")]]);
+        assert!((result.buses[0].active_power - 0.5).abs() < 1e-12);
+        assert!((result.buses[1].voltage_angle + 0.05).abs() < 1e-12);
+        assert!((result.branches[0].p0 - 0.5).abs() < 1e-12);
+        assert!((result.branches[0].p1 + 0.5).abs() < 1e-12);
+    }
+
+    #[test]
+    fn ac_two_buses() {
+        // A lossless line with a purely active load at the end.
+        let result = analysis().ac_power_flow(&two_bus_model(), &two_bus_data(0.0)).unwrap();
+        assert!(result.iterations > 0);
+        let (a, b) = (&result.buses[0], &result.buses[1]);
+        assert!((a.active_power - 0.5).abs() < 1e-8);
+        assert!((b.active_power + 0.5).abs() < 1e-8);
+        assert!(b.reactive_power.abs() < 1e-8);
+        assert!(a.reactive_power > 0.0);
+        assert!(b.voltage_magnitude < 1.0 && b.voltage_angle < 0.0);
+
+        // Check the power flow equations at the load.
+        let (v, theta) = (b.voltage_magnitude, b.voltage_angle);
+        assert!((v * theta.sin() / 0.1 + 0.5).abs() < 1e-8);
+
+        // With resistance, the slack bus also supplies the losses.
+        let result = analysis().ac_power_flow(&two_bus_model(), &two_bus_data(0.01)).unwrap();
+        let line = &result.branches[0];
+        assert!(result.buses[0].active_power > 0.5);
+        assert!(line.p0 + line.p1 > 0.0);
+    }
+
+    #[test]
+    fn islands_and_links() {
+        let mut model = two_bus_model();
+        model.add_ob(name("c"), name("Bus"));
+        model.add_ob(name("d"), name("Bus"));
+        model.add_mor(name("transformer"), name("c"), name("d"), name("Passive").into());
+        model.add_mor(name("link"), name("b"), name("c"), name("Branch").into());
+        assert_eq!(
+            analysis().sub_networks(&model),
+            vec![vec![name("a"), name("b")], vec![name("c"), name("d")]]
+        );
+
+        let mut data = two_bus_data(0.0);
+        data.active_power.insert(name("d"), -0.25);
+        data.link_power.insert(name("link"), 0.5);
+        data.controls.insert(name("d"), BusControl::Slack);
+        data.branches.insert(
+            name("transformer"),
+            BranchParameters {
+                x: 0.2,
+                tap_ratio: 1.05,
+                ..Default::default()
+            },
+        );
+
+        for result in [
+            analysis().dc_power_flow(&model, &data).unwrap(),
+            analysis().ac_power_flow(&model, &data).unwrap(),
+        ] {
+            let p: Vec<_> = result.buses.iter().map(|bus| bus.active_power).collect();
+            let sub_networks: Vec<_> = result.buses.iter().map(|bus| bus.sub_network).collect();
+            assert_eq!(sub_networks, vec![0, 0, 1, 1]);
+            // Slack `a` supplies the load at `b` plus the export through the link,
+            // and slack `d`, overriding its own load, absorbs the import.
+            assert!((p[0] - 1.0).abs() < 1e-8);
+            assert!((p[1] + 0.5).abs() < 1e-8);
+            assert!(p[2].abs() < 1e-8);
+            assert!((p[3] + 0.5).abs() < 1e-8);
+            assert_eq!(result.branches[2].p0, 0.5);
+        }
+
+        data.branches.remove(&name("transformer"));
+        assert_eq!(
+            analysis().dc_power_flow(&model, &data),
+            Err(PowerFlowError::ZeroImpedance(name("transformer")))
+        );
+    }
+}
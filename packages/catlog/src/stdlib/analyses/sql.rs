@@ -4,7 +4,7 @@ use crate::{
     one::{
         Path,
         graph::FinGraph,
-        graph_algorithms::{ToposortData, toposort_lenient},
+        graph_algorithms::{Condensation, ToposortData, condensation, toposort_lenient},
     },
     zero::{QualifiedLabel, QualifiedName, name},
 };
@@ -68,7 +68,7 @@ pub enum ColumnType {
 impl ColumnType {
     fn build(
         model: &DiscreteDblModel,
-        cycles: &IndexMap<QualifiedName, Vec<QualifiedName>>,
+        condensation: &Condensation<QualifiedName, QualifiedName>,
         src: &QualifiedName,
         mor: QualifiedName,
     ) -> Self {
@@ -78,7 +78,9 @@ impl ColumnType {
                 ColumnType::Attribute { mor, tgt: tgt.clone() }
             }
             _ => {
-                if cycles.contains_key(src) || cycles.contains_key(&tgt.clone()) {
+                // A foreign key lies on a cycle just when its source and target
+                // tables are in the same strongly connected component.
+                if condensation.same_component(src, tgt) {
                     ColumnType::Deferrable { mor, tgt: tgt.clone() }
                 } else {
                     ColumnType::Ordinary { mor, tgt: tgt.clone() }
@@ -145,12 +147,12 @@ impl ForeignKeyConstraints {
     fn new(model: &DiscreteDblModel) -> Self {
         let g = model.generating_graph();
         let toposort: ToposortData<QualifiedName> = toposort_lenient(g);
-        let cycles = toposort.cycles;
+        let condensation = condensation(g);
         let fks = IndexMap::from_iter(toposort.stack.into_iter().rev().filter_map(|v| {
             (name("Entity") == model.ob_generator_type(&v)).then_some((
                 v.clone(),
                 g.out_edges(&v)
-                    .map(|e| ColumnType::build(model, &cycles, &v, e))
+                    .map(|e| ColumnType::build(model, &condensation, &v, e))
                     .collect::<Vec<ColumnType>>(),
             ))
         }));
@@ -268,7 +270,7 @@ impl SQLAnalysis {
         &self,
         model: &DiscreteDblModel,
     ) -> Result<ForeignKeyConstraints, SQLAnalysisError> {
-        // if a morphism is a key in toposort.cycles, then its source and targets are deferrable.
+        // if a morphism lies on a cycle of foreign keys, then it is deferrable.
         let constraints = ForeignKeyConstraints::new(model);
         self.validate_toposort(constraints)
     }
@@ -568,4 +570,59 @@ ADD
             }
         );
     }
+
+    #[test]
+    fn sql_mysql_cycles_only_on_cycle() {
+        let th = Rc::new(th_schema());
+        let source = "[
+                Refs : Entity,
+                Snapshots : Entity,
+                Users : Entity,
+                head : (Hom Entity)[Refs, Snapshots],
+                for_ref: (Hom Entity)[Snapshots, Refs],
+                owner: (Hom Entity)[Refs, Users],
+                favorite: (Hom Entity)[Users, Snapshots],
+            ]";
+        let model = tt::modelgen::Model::from_text(&th.into(), source)
+            .ok()
+            .and_then(|m| m.as_discrete())
+            .unwrap();
+
+        let constraints = ForeignKeyConstraints::new(&model);
+        let deferrable: Vec<_> = constraints
+            .fks
+            .values()
+            .flatten()
+            .filter(|col| matches!(col, ColumnType::Deferrable { .. }))
+            .map(|col| col.mor().clone())
+            .sorted()
+            .collect();
+        assert_eq!(
+            deferrable,
+            vec![name("favorite"), name("for_ref"), name("head"), name("owner")]
+        );
+
+        let source = "[
+                Refs : Entity,
+                Snapshots : Entity,
+                Users : Entity,
+                head : (Hom Entity)[Refs, Snapshots],
+                for_ref: (Hom Entity)[Snapshots, Refs],
+                favorite: (Hom Entity)[Users, Snapshots],
+            ]";
+        let th = Rc::new(th_schema());
+        let model = tt::modelgen::Model::from_text(&th.into(), source)
+            .ok()
+            .and_then(|m| m.as_discrete())
+            .unwrap();
+        let constraints = ForeignKeyConstraints::new(&model);
+        let favorite = constraints.fks[&name("Users")].first().unwrap();
+        assert_eq!(
+            favorite,
+            &ColumnType::Ordinary {
+                mor: name("favorite"),
+                tgt: name("Snapshots")
+            }
+        );
+    }
 }
@@ -0,0 +1,739 @@
+//! Interchange with Catlab via attributed C-sets in JSON.
+//!
+//! [Catlab.jl](https://github.com/AlgebraicJulia/Catlab.jl) represents
+//! combinatorial structures as attributed C-sets (ACSets), serialized in JSON by
+//! [ACSets.jl](https://github.com/AlgebraicJulia/ACSets.jl) as an object mapping
+//! each object of the schema to the list of its parts. Each part is an object
+//! with a 1-based `_id` and the values of the morphisms and attributes out of it,
+//! morphisms being given by the `_id` of the target part.
+//!
+//! This module translates models into ACSets on standard schemas:
+//!
+//! - models of discrete theories, e.g., schemas, as named graphs on `SchGraph`
+//!   extended by vertex and edge names, as in Catlab's `SchNamedGraph`
+//! - Petri nets as labelled Petri nets on `SchLabelledPetriNet`
+//! - stock-flow diagrams as ACSets on `SchStockFlow` from StockFlow.jl
+//!
+//! It also translates instances of schemas, given as tables of an instance
+//! document, into ACSets on the schema itself. Imported models are named by fresh
+//! UUIDs and labelled by the names in the ACSet.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::rc::Rc;
+
+use indexmap::IndexMap;
+use serde_json::{Map, Value};
+use thiserror::Error;
+use uuid::Uuid;
+
+use catcolab_document_types::current::instance::{FieldValue, Table, TableRow};
+
+use crate::dbl::modal::List;
+use crate::dbl::model::*;
+use crate::dbl::theory::{
+    DblTheory, DiscreteDblTheory, DiscreteTabTheory, ModalDblTheory, ModalMorType, ModalObType,
+    TabMorType, TabObType, Unital,
+};
+use crate::one::{FgCategory, Path};
+use crate::stdlib::analyses::petri::transition_boundary;
+use crate::zero::{NameSegment, Namespace, QualifiedName, label_seg, name};
+
+/// An attributed C-set (ACSet) in the JSON format of ACSets.jl.
+///
+/// The parts of each object or attribute type are stored in order, so that the
+/// part with `_id` equal to `n` is the `n`-th part.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ACSet {
+    tables: IndexMap<String, Vec<Map<String, Value>>>,
+}
+
+/// An error in converting to or from an ACSet.
+#[derive(Debug, Error)]
+pub enum ACSetError {
+    /// The JSON is not an ACSet.
+    #[error("Invalid ACSet JSON: {0}")]
+    Json(String),
+
+    /// A table required by the schema is missing.
+    #[error("ACSet has no table `{0}`")]
+    MissingTable(String),
+
+    /// A part lacks a required morphism or refers to a nonexistent part.
+    #[error("Part {1} of table `{0}` has invalid value for `{2}`")]
+    InvalidSubpart(String, usize, String),
+
+    /// A structure in the model or ACSet has no counterpart in the other.
+    #[error("Unsupported: {0}")]
+    Unsupported(String),
+
+    /// A row of an instance refers to a row not in the instance.
+    #[error("Instance refers to unknown row {0}")]
+    UnknownRow(Uuid),
+}
+
+impl ACSet {
+    /// Constructs an ACSet with the given tables, all empty.
+    pub fn with_tables<'a>(obs: impl IntoIterator<Item = &'a str>) -> Self {
+        Self {
+            tables: obs.into_iter().map(|ob| (ob.to_string(), Vec::new())).collect(),
+        }
+    }
+
+    /// Adds a part to a table, returning its 1-based ID.
+    pub fn add_part<'a>(
+        &mut self,
+        ob: &str,
+        subparts: impl IntoIterator<Item = (&'a str, Value)>,
+    ) -> usize {
+        let table = self.tables.entry(ob.to_string()).or_default();
+        let id = table.len() + 1;
+        let mut part = Map::new();
+        part.insert("_id".into(), id.into());
+        part.extend(subparts.into_iter().map(|(key, value)| (key.to_string(), value)));
+        table.push(part);
+        id
+    }
+
+    /// Gets the parts of a table.
+    pub fn parts(&self, ob: &str) -> Result<&[Map<String, Value>], ACSetError> {
+        self.tables
+            .get(ob)
+            .map(|table| table.as_slice())
+            .ok_or_else(|| ACSetError::MissingTable(ob.into()))
+    }
+
+    /// Gets the number of parts in a table, which is zero if there is no table.
+    pub fn nparts(&self, ob: &str) -> usize {
+        self.tables.get(ob).map_or(0, |table| table.len())
+    }
+
+    /// Iterates over the names of the tables.
+    pub fn table_names(&self) -> impl Iterator<Item = &str> {
+        self.tables.keys().map(|key| key.as_str())
+    }
+
+    /// Gets the 1-based ID of the part that a part maps to under a morphism.
+    pub fn subpart(&self, ob: &str, id: usize, hom: &str, cod: &str) -> Result<usize, ACSetError> {
+        let invalid = || ACSetError::InvalidSubpart(ob.into(), id, hom.into());
+        let part = self.parts(ob)?.get(id - 1).ok_or_else(invalid)?;
+        let target = part.get(hom).and_then(|v| v.as_u64()).ok_or_else(invalid)? as usize;
+        if target == 0 || target > self.nparts(cod) {
+            return Err(invalid());
+        }
+        Ok(target)
+    }
+
+    /// Gets the string value of an attribute of a part, if it is set.
+    pub fn attr_str(&self, ob: &str, id: usize, attr: &str) -> Option<&str> {
+        let part = self.tables.get(ob)?.get(id - 1)?;
+        part.get(attr).and_then(|v| v.as_str())
+    }
+
+    /// Iterates over the IDs of parts that map to a given part under a morphism.
+    pub fn incident<'a>(
+        &'a self,
+        ob: &'a str,
+        hom: &'a str,
+        target: usize,
+    ) -> impl Iterator<Item = usize> + 'a {
+        let parts = self.tables.get(ob).map(|table| table.as_slice()).unwrap_or_default();
+        parts.iter().enumerate().filter_map(move |(i, part)| {
+            (part.get(hom).and_then(|v| v.as_u64()) == Some(target as u64)).then_some(i + 1)
+        })
+    }
+
+    /// Converts to a JSON value in the format of ACSets.jl.
+    pub fn to_json(&self) -> Value {
+        let tables = self.tables.iter().map(|(ob, parts)| {
+            let parts = parts.iter().cloned().map(Value::Object).collect();
+            (ob.clone(), Value::Array(parts))
+        });
+        Value::Object(tables.collect())
+    }
+
+    /// Converts from a JSON value in the format of ACSets.jl.
+    ///
+    /// Parts of each table are sorted by their `_id`, if present.
+    pub fn from_json(value: &Value) -> Result<Self, ACSetError> {
+        let invalid = |msg: &str| ACSetError::Json(msg.into());
+        let object = value.as_object().ok_or_else(|| invalid("expected an object"))?;
+        let mut tables = IndexMap::new();
+        for (ob, parts) in object {
+            let parts = parts.as_array().ok_or_else(|| invalid("expected an array of parts"))?;
+            let mut parts: Vec<_> = parts
+                .iter()
+                .map(|part| part.as_object().cloned().ok_or_else(|| invalid("expected a part")))
+                .collect::<Result<_, _>>()?;
+            parts.sort_by_key(|part| part.get("_id").and_then(|id| id.as_u64()));
+            tables.insert(ob.clone(), parts);
+        }
+        Ok(Self { tables })
+    }
+
+    /// Parses an ACSet from a JSON string.
+    pub fn parse(input: &str) -> Result<Self, ACSetError> {
+        let value = serde_json::from_str(input).map_err(|err| ACSetError::Json(err.to_string()))?;
+        Self::from_json(&value)
+    }
+}
+
+impl fmt::Display for ACSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_json())
+    }
+}
+
+/// A model imported from an ACSet.
+pub struct ACSetModel<Model> {
+    /// The imported model.
+    pub model: Model,
+
+    /// Namespace labelling the generators of the model by their names in the ACSet.
+    pub namespace: Namespace,
+}
+
+/// Generates fresh UUID names with the given labels.
+fn fresh_name(namespace: &mut Namespace, label: &str) -> QualifiedName {
+    let uuid = Uuid::now_v7();
+    namespace.set_label(uuid, label_seg(label));
+    uuid.into()
+}
+
+/// Label of a part, falling back to its table and ID when unnamed.
+fn part_label(acset: &ACSet, ob: &str, id: usize, attr: &str) -> String {
+    acset
+        .attr_str(ob, id, attr)
+        .map_or_else(|| format!("{ob}{id}"), |s| s.to_string())
+}
+
+/// Exports a model of a discrete theory as a named graph.
+///
+/// Object generators become vertices and morphism generators become edges, named
+/// by their labels. The types of the generators are not recorded.
+pub fn graph_to_acset(model: &DiscreteDblModel, namespace: &Namespace) -> ACSet {
+    let mut acset = ACSet::with_tables(["V", "E", "VName", "EName"]);
+    let mut vertices = HashMap::new();
+    for x in model.ob_generators() {
+        let v = acset.add_part("V", [("vname", namespace.label_string(&x).into())]);
+        vertices.insert(x, v);
+    }
+    for f in model.mor_generators() {
+        let (src, tgt) = (model.mor_generator_dom(&f), model.mor_generator_cod(&f));
+        acset.add_part(
+            "E",
+            [
+                ("src", vertices[&src].into()),
+                ("tgt", vertices[&tgt].into()),
+                ("ename", namespace.label_string(&f).into()),
+            ],
+        );
+    }
+    acset
+}
+
+/// Imports a graph, possibly named, as a model of a discrete theory.
+///
+/// Every vertex is given the same object type and every edge the hom type on it.
+pub fn acset_to_graph(
+    acset: &ACSet,
+    theory: Rc<DiscreteDblTheory>,
+    ob_type: QualifiedName,
+) -> Result<ACSetModel<DiscreteDblModel>, ACSetError> {
+    let mut model = DiscreteDblModel::new(theory);
+    let mut namespace = Namespace::new_for_uuid();
+    let vertices: Vec<_> = (1..=acset.parts("V")?.len())
+        .map(|v| {
+            let x = fresh_name(&mut namespace, &part_label(acset, "V", v, "vname"));
+            model.add_ob(x.clone(), ob_type.clone());
+            x
+        })
+        .collect();
+    for e in 1..=acset.parts("E")?.len() {
+        let src = acset.subpart("E", e, "src", "V")?;
+        let tgt = acset.subpart("E", e, "tgt", "V")?;
+        let f = fresh_name(&mut namespace, &part_label(acset, "E", e, "ename"));
+        let (dom, cod) = (vertices[src - 1].clone(), vertices[tgt - 1].clone());
+        model.add_mor(f, dom, cod, Path::Id(ob_type.clone()));
+    }
+    Ok(ACSetModel { model, namespace })
+}
+
+/// Exports a Petri net as a labelled Petri net.
+///
+/// Places become species and transitions become transitions, with an input or
+/// output arc for each occurrence of a place in the inputs or outputs of a
+/// transition. The schema has no counterpart to object or morphism operations,
+/// so models using them, other than the tensor products of places in transitions,
+/// are rejected.
+pub fn petri_net_to_acset(
+    model: &ModalDblModel<Unital>,
+    namespace: &Namespace,
+) -> Result<ACSet, ACSetError> {
+    let ob_type = ModalObType::new(name("Object"));
+    let mut acset = ACSet::with_tables(["T", "S", "I", "O", "Name"]);
+    let mut species = HashMap::new();
+    for x in model.ob_generators() {
+        let label = namespace.label_string(&x);
+        if model.ob_generator_type(&x) != ob_type {
+            return Err(ACSetError::Unsupported(format!("object `{label}` is not a place")));
+        }
+        let s = acset.add_part("S", [("sname", label.into())]);
+        species.insert(x, s);
+    }
+    for f in model.mor_generators() {
+        let label = namespace.label_string(&f);
+        let unsupported = || {
+            ACSetError::Unsupported(format!(
+                "morphism `{label}` is not a transition between tensor products of places"
+            ))
+        };
+        if model.mor_generator_type(&f) != ModalMorType::Zero(ob_type.clone()) {
+            return Err(unsupported());
+        }
+        let (Some(inputs), Some(outputs)) =
+            (transition_places(model.get_dom(&f)), transition_places(model.get_cod(&f)))
+        else {
+            return Err(unsupported());
+        };
+
+        let t = acset.add_part("T", [("tname", label.into())]);
+        for x in inputs {
+            acset.add_part("I", [("it", t.into()), ("is", species[&x].into())]);
+        }
+        for x in outputs {
+            acset.add_part("O", [("ot", t.into()), ("os", species[&x].into())]);
+        }
+    }
+    Ok(acset)
+}
+
+/// Gets the places in the input or output of a transition, if it is a place or a
+/// tensor product of places.
+fn transition_places(ob: Option<&ModalOb>) -> Option<Vec<QualifiedName>> {
+    match ob? {
+        ModalOb::Generator(x) => Some(vec![x.clone()]),
+        ModalOb::App(list, op) if *op == name("tensor") => match &**list {
+            ModalOb::List(List::Symmetric, obs) => {
+                obs.iter().map(|ob| ob.clone().generator()).collect()
+            }
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Imports a Petri net, possibly labelled.
+pub fn acset_to_petri_net(
+    acset: &ACSet,
+    theory: Rc<ModalDblTheory<Unital>>,
+) -> Result<ACSetModel<ModalDblModel<Unital>>, ACSetError> {
+    let ob_type = ModalObType::new(name("Object"));
+    let mut model = ModalDblModel::new(theory);
+    let mut namespace = Namespace::new_for_uuid();
+    let species: Vec<_> = (1..=acset.parts("S")?.len())
+        .map(|s| {
+            let x = fresh_name(&mut namespace, &part_label(acset, "S", s, "sname"));
+            model.add_ob(x.clone(), ob_type.clone());
+            x
+        })
+        .collect();
+    let arcs = |ob: &str, hom: &str, t: usize| -> Result<Vec<QualifiedName>, ACSetError> {
+        acset
+            .incident(ob, &format!("{hom}t"), t)
+            .map(|arc| Ok(species[acset.subpart(ob, arc, &format!("{hom}s"), "S")? - 1].clone()))
+            .collect()
+    };
+    for t in 1..=acset.parts("T")?.len() {
+        let (inputs, outputs) = (arcs("I", "i", t)?, arcs("O", "o", t)?);
+        let f = fresh_name(&mut namespace, &part_label(acset, "T", t, "tname"));
+        model.add_mor(
+            f,
+            transition_boundary(inputs),
+            transition_boundary(outputs),
+            ModalMorType::Zero(ob_type.clone()),
+        );
+    }
+    Ok(ACSetModel { model, namespace })
+}
+
+/// Exports a stock-flow diagram on the schema of StockFlow.jl.
+///
+/// Stocks become stocks, each flow becomes a flow together with an outflow, an
+/// inflow, and a variable for its rate, and each link from a stock to a flow
+/// becomes a link from the stock to the flow's variable. The types of links, such
+/// as their signs, are not recorded.
+pub fn stock_flow_to_acset(
+    model: &DiscreteTabModel,
+    namespace: &Namespace,
+) -> Result<ACSet, ACSetError> {
+    let mut acset =
+        ACSet::with_tables(["S", "SV", "LS", "F", "I", "O", "V", "LV", "LSV", "Name", "Op"]);
+    let mut stocks = HashMap::new();
+    for x in model.ob_generators() {
+        let s = acset.add_part("S", [("sname", namespace.label_string(&x).into())]);
+        stocks.insert(x, s);
+    }
+
+    let (flows, links): (Vec<_>, Vec<_>) = model
+        .mor_generators()
+        .partition(|f| matches!(model.mor_generator_type(f), TabMorType::Hom(_)));
+    let mut variables = HashMap::new();
+    for f in flows {
+        let label = namespace.label_string(&f);
+        let stock = |ob: TabOb| {
+            ob.basic().map(|x| stocks[&x]).ok_or_else(|| {
+                ACSetError::Unsupported(format!("flow `{label}` is not between stocks"))
+            })
+        };
+        let (src, tgt) = (stock(model.mor_generator_dom(&f))?, stock(model.mor_generator_cod(&f))?);
+        let v = acset.add_part("V", [("vname", format!("v_{label}").into())]);
+        let flow = acset.add_part("F", [("fv", v.into()), ("fname", label.clone().into())]);
+        acset.add_part("O", [("os", src.into()), ("ofn", flow.into())]);
+        acset.add_part("I", [("is", tgt.into()), ("ifn", flow.into())]);
+        variables.insert(f, v);
+    }
+    for f in links {
+        let (dom, cod) = (model.mor_generator_dom(&f), model.mor_generator_cod(&f));
+        let stock = dom.basic().and_then(|x| stocks.get(&x).copied());
+        let variable = cod.tabulated().and_then(|path| match path.only()? {
+            TabEdge::Basic(flow) => variables.get(&flow).copied(),
+            TabEdge::Square { .. } => None,
+        });
+        let (Some(s), Some(v)) = (stock, variable) else {
+            let label = namespace.label_string(&f);
+            return Err(ACSetError::Unsupported(format!(
+                "link `{label}` is not from a stock to a flow"
+            )));
+        };
+        acset.add_part("LV", [("lvs", s.into()), ("lvv", v.into())]);
+    }
+    Ok(acset)
+}
+
+/// Imports a stock-flow diagram on the schema of StockFlow.jl.
+///
+/// Every flow must have exactly one outflow and one inflow, and links must go
+/// from stocks to the variables of flows. Sum variables are not supported.
+pub fn acset_to_stock_flow(
+    acset: &ACSet,
+    theory: Rc<DiscreteTabTheory>,
+) -> Result<ACSetModel<DiscreteTabModel>, ACSetError> {
+    if acset.nparts("SV") > 0 {
+        return Err(ACSetError::Unsupported("sum variables".into()));
+    }
+    let ob_type = TabObType::Basic(name("Object"));
+    let mut model = DiscreteTabModel::new(theory.clone());
+    let mut namespace = Namespace::new_for_uuid();
+    let stocks: Vec<_> = (1..=acset.parts("S")?.len())
+        .map(|s| {
+            let x = fresh_name(&mut namespace, &part_label(acset, "S", s, "sname"));
+            model.add_ob(x.clone(), ob_type.clone());
+            x
+        })
+        .collect();
+
+    let mut flows = HashMap::new();
+    for flow in 1..=acset.parts("F")?.len() {
+        let stock = |ob: &str, prefix: &str| -> Result<QualifiedName, ACSetError> {
+            let mut arcs = acset.incident(ob, if prefix == "o" { "ofn" } else { "ifn" }, flow);
+            let (Some(arc), None) = (arcs.next(), arcs.next()) else {
+                let label = part_label(acset, "F", flow, "fname");
+                return Err(ACSetError::Unsupported(format!(
+                    "flow `{label}` without exactly one {ob}"
+                )));
+            };
+            Ok(stocks[acset.subpart(ob, arc, &format!("{prefix}s"), "S")? - 1].clone())
+        };
+        let (dom, cod) = (stock("O", "o")?, stock("I", "i")?);
+        let f = fresh_name(&mut namespace, &part_label(acset, "F", flow, "fname"));
+        model.add_mor(f.clone(), dom.into(), cod.into(), theory.hom_type(ob_type.clone()));
+        flows.insert(acset.subpart("F", flow, "fv", "V")?, f);
+    }
+
+    for link in 1..=acset.nparts("LV") {
+        let stock = stocks[acset.subpart("LV", link, "lvs", "S")? - 1].clone();
+        let v = acset.subpart("LV", link, "lvv", "V")?;
+        let flow = flows.get(&v).ok_or_else(|| {
+            ACSetError::Unsupported(format!("link to variable {v} that is not a flow rate"))
+        })?;
+        let f = fresh_name(&mut namespace, "");
+        let cod = model.tabulated_gen(flow.clone());
+        model.add_mor(f, stock.into(), cod, TabMorType::Basic(name("Link")));
+    }
+    Ok(ACSetModel { model, namespace })
+}
+
+/// Gets the UUID of a generator in a model made in CatColab.
+fn generator_uuid(x: &QualifiedName) -> Option<Uuid> {
+    match x.only()? {
+        NameSegment::Uuid(uuid) => Some(uuid),
+        NameSegment::Text(_) => None,
+    }
+}
+
+/// Tables and columns of an instance of a schema, as used in ACSets.
+struct InstanceSchema {
+    /// Entities, with their UUIDs and table names.
+    entities: Vec<(Uuid, String)>,
+
+    /// Attribute types, by table name.
+    attr_types: Vec<String>,
+
+    /// Columns of entities, with their UUIDs, names, codomain, and whether they
+    /// are attributes.
+    columns: HashMap<Uuid, Vec<(Uuid, String, QualifiedName, bool)>>,
+}
+
+impl InstanceSchema {
+    fn new(schema: &DiscreteDblModel, namespace: &Namespace) -> Result<Self, ACSetError> {
+        let uuid = |x: &QualifiedName| {
+            generator_uuid(x).ok_or_else(|| {
+                ACSetError::Unsupported(format!("schema generator `{x}` without UUID"))
+            })
+        };
+        let mut entities = Vec::new();
+        for x in schema.ob_generators_with_type(&name("Entity")) {
+            entities.push((uuid(&x)?, namespace.label_string(&x)));
+        }
+        let attr_types = schema
+            .ob_generators_with_type(&name("AttrType"))
+            .map(|x| namespace.label_string(&x))
+            .collect();
+        let mut columns: HashMap<_, Vec<_>> = HashMap::new();
+        for f in schema.mor_generators() {
+            let (dom, cod) = (schema.mor_generator_dom(&f), schema.mor_generator_cod(&f));
+            let is_attr = schema.mor_generator_type(&f) == Path::single(name("Attr"));
+            let column = (uuid(&f)?, namespace.label_string(&f), cod, is_attr);
+            columns.entry(uuid(&dom)?).or_default().push(column);
+        }
+        Ok(Self { entities, attr_types, columns })
+    }
+
+    fn table_name(&self, x: &QualifiedName) -> Option<&str> {
+        let uuid = generator_uuid(x)?;
+        self.entities.iter().find(|(id, _)| *id == uuid).map(|(_, name)| name.as_str())
+    }
+}
+
+/// Exports an instance of a schema as an ACSet on that schema.
+///
+/// The instance is given by the tables of an instance document. Each entity of
+/// the schema becomes a table whose parts are the rows in order, and each
+/// morphism out of the entity becomes a column, holding the part that a row maps
+/// to or the value of an attribute. Missing values are exported as `null`.
+pub fn instance_to_acset(
+    schema: &DiscreteDblModel,
+    namespace: &Namespace,
+    tables: &HashMap<Uuid, Table>,
+) -> Result<ACSet, ACSetError> {
+    let schema_info = InstanceSchema::new(schema, namespace)?;
+    let empty = Vec::new();
+    let rows = |entity: &Uuid| tables.get(entity).map_or(&empty, |table| &table.row_order);
+    let mut part_ids = HashMap::new();
+    for (entity, _) in schema_info.entities.iter() {
+        for (i, row) in rows(entity).iter().enumerate() {
+            part_ids.insert(*row, i + 1);
+        }
+    }
+
+    let mut acset = ACSet::with_tables(
+        schema_info
+            .entities
+            .iter()
+            .map(|(_, name)| name.as_str())
+            .chain(schema_info.attr_types.iter().map(|name| name.as_str())),
+    );
+    for (entity, table_name) in schema_info.entities.iter() {
+        let columns = schema_info.columns.get(entity).unwrap_or(&Vec::new()).clone();
+        for row_id in rows(entity) {
+            let row = tables[entity].rows.get(row_id).ok_or(ACSetError::UnknownRow(*row_id))?;
+            let mut subparts = Vec::new();
+            for (column, column_name, _, _) in columns.iter() {
+                let value = match row.fields.get(column) {
+                    None | Some(FieldValue::Null) => Value::Null,
+                    Some(FieldValue::Bool(b)) => (*b).into(),
+                    Some(FieldValue::Int(n)) => (*n).into(),
+                    Some(FieldValue::Float(x)) => (*x as f64).into(),
+                    Some(FieldValue::String(s)) => s.clone().into(),
+                    Some(FieldValue::RowRef(other)) => {
+                        part_ids.get(other).ok_or(ACSetError::UnknownRow(*other))?.to_owned().into()
+                    }
+                };
+                subparts.push((column_name.as_str(), value));
+            }
+            acset.add_part(table_name, subparts);
+        }
+    }
+    Ok(acset)
+}
+
+/// Imports an ACSet on a schema as the tables of an instance document.
+///
+/// Rows are given fresh UUIDs. Integer values of attributes become integers,
+/// unless they do not fit, and other numbers become floats.
+pub fn acset_to_instance(
+    schema: &DiscreteDblModel,
+    namespace: &Namespace,
+    acset: &ACSet,
+) -> Result<HashMap<Uuid, Table>, ACSetError> {
+    let schema_info = InstanceSchema::new(schema, namespace)?;
+    let row_ids: HashMap<_, Vec<_>> = schema_info
+        .entities
+        .iter()
+        .map(|(entity, name)| (*entity, (0..acset.nparts(name)).map(|_| Uuid::now_v7()).collect()))
+        .collect();
+
+    let mut tables = HashMap::new();
+    for (entity, table_name) in schema_info.entities.iter() {
+        let columns = schema_info.columns.get(entity).cloned().unwrap_or_default();
+        let mut rows = HashMap::new();
+        for (i, id) in row_ids[entity].iter().enumerate() {
+            let part = acset.parts(table_name)?;
+            let mut fields = HashMap::new();
+            for (column, column_name, cod, is_attr) in columns.iter() {
+                let value = match part[i].get(column_name) {
+                    None | Some(Value::Null) => FieldValue::Null,
+                    Some(_) if !is_attr => {
+                        let cod_name = schema_info.table_name(cod).ok_or_else(|| {
+                            ACSetError::Unsupported(format!("column `{column_name}`"))
+                        })?;
+                        let target = acset.subpart(table_name, i + 1, column_name, cod_name)?;
+                        FieldValue::RowRef(row_ids[&generator_uuid(cod).unwrap()][target - 1])
+                    }
+                    Some(Value::Bool(b)) => FieldValue::Bool(*b),
+                    Some(Value::Number(n)) => {
+                        match n.as_i64().and_then(|n| i32::try_from(n).ok()) {
+                            Some(n) => FieldValue::Int(n),
+                            None => FieldValue::Float(n.as_f64().unwrap_or_default() as f32),
+                        }
+                    }
+                    Some(Value::String(s)) => FieldValue::String(s.clone()),
+                    Some(_) => {
+                        return Err(ACSetError::InvalidSubpart(
+                            table_name.clone(),
+                            i + 1,
+                            column_name.clone(),
+                        ));
+                    }
+                };
+                fields.insert(*column, value);
+            }
+            rows.insert(*id, TableRow { id: *id, fields });
+        }
+        let row_order = row_ids[entity].clone();
+        tables.insert(*entity, Table { id: *entity, rows, row_order });
+    }
+    Ok(tables)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stdlib::analyses::petri::transition_interface;
+    use crate::stdlib::{models::*, theories::*};
+    use crate::zero::label_seg;
+
+    #[test]
+    fn graphs() {
+        let th = Rc::new(th_signed_category());
+        let model = positive_feedback(th.clone());
+        let acset = graph_to_acset(&model, &Namespace::new_for_text());
+        assert_eq!((acset.nparts("V"), acset.nparts("E")), (2, 2));
+
+        let acset = ACSet::parse(&acset.to_string()).unwrap();
+        let imported = acset_to_graph(&acset, th, name("Object")).unwrap();
+        assert_eq!(imported.model.ob_generators().count(), 2);
+        let f = imported.model.mor_generators().next().unwrap();
+        assert_eq!(imported.namespace.label_string(&f), "positive1");
+        let dom = imported.model.mor_generator_dom(&f);
+        assert_eq!(imported.namespace.label_string(&dom), "x");
+
+        let bad = ACSet::parse(r#"{"V": [{"_id": 1}], "E": [{"_id": 1, "src": 1, "tgt": 2}]}"#);
+        let result = acset_to_graph(&bad.unwrap(), Rc::new(th_category()), name("Object"));
+        assert!(matches!(result, Err(ACSetError::InvalidSubpart(_, 1, _))));
+    }
+
+    #[test]
+    fn petri_nets() {
+        let th = Rc::new(th_sym_monoidal_category());
+        let model = sir_petri(th.clone());
+        let acset = petri_net_to_acset(&model, &Namespace::new_for_text()).unwrap();
+        assert_eq!((acset.nparts("I"), acset.nparts("O")), (3, 3));
+
+        let imported = acset_to_petri_net(&acset, th).unwrap();
+        let t = imported.model.mor_generators().next().unwrap();
+        assert_eq!(imported.namespace.label_string(&t), "infect");
+        let (inputs, outputs) = transition_interface(&imported.model, &t);
+        let labels = |obs: Vec<ModalOb>| -> Vec<_> {
+            obs.into_iter()
+                .map(|ob| imported.namespace.label_string(&ob.unwrap_generator()))
+                .collect()
+        };
+        assert_eq!(labels(inputs), vec!["S", "I"]);
+        assert_eq!(labels(outputs), vec!["I", "I"]);
+
+        // Object operations other than tensor products have no counterpart.
+        let mut model = model;
+        let (s, i) = (ModalOb::from(name("S")), ModalOb::from(name("I")));
+        model.add_mor(
+            name("pair"),
+            ModalOb::App(ModalOb::List(List::Plain, vec![s, i]).into(), name("tensor")),
+            transition_boundary([name("I")]),
+            ModalMorType::Zero(ModalObType::new(name("Object"))),
+        );
+        let result = petri_net_to_acset(&model, &Namespace::new_for_text());
+        assert!(matches!(result, Err(ACSetError::Unsupported(_))));
+    }
+
+    #[test]
+    fn stock_flows() {
+        let th = Rc::new(th_category_links());
+        let model = backward_link(th.clone());
+        let acset = stock_flow_to_acset(&model, &Namespace::new_for_text()).unwrap();
+        assert_eq!((acset.nparts("F"), acset.nparts("LV")), (1, 1));
+        assert_eq!(acset.attr_str("V", 1, "vname"), Some("v_f"));
+
+        let imported = acset_to_stock_flow(&acset, th).unwrap();
+        let mors: Vec<_> = imported.model.mor_generators().collect();
+        assert_eq!(mors.len(), 2);
+        let link_cod = imported.model.mor_generator_cod(&mors[1]);
+        assert_eq!(link_cod, imported.model.tabulated_gen(mors[0].clone()));
+    }
+
+    #[test]
+    fn instances() {
+        let [person, string, manager, name_attr] = [(); 4].map(|_| Uuid::now_v7());
+        let mut namespace = Namespace::new_for_uuid();
+        namespace.set_label(person, label_seg("Person"));
+        namespace.set_label(string, label_seg("String"));
+        namespace.set_label(manager, label_seg("manager"));
+        namespace.set_label(name_attr, label_seg("name"));
+        let mut schema = DiscreteDblModel::new(Rc::new(th_schema()));
+        schema.add_ob(person.into(), name("Entity"));
+        schema.add_ob(string.into(), name("AttrType"));
+        schema.add_mor(manager.into(), person.into(), person.into(), Path::Id(name("Entity")));
+        schema.add_mor(name_attr.into(), person.into(), string.into(), name("Attr").into());
+
+        let acset = ACSet::parse(
+            r#"{
+                "Person": [
+                    {"_id": 2, "manager": 2, "name": "Bob"},
+                    {"_id": 1, "manager": 2, "name": "Alice"}
+                ],
+                "String": []
+            }"#,
+        )
+        .unwrap();
+        let tables = acset_to_instance(&schema, &namespace, &acset).unwrap();
+        let table = &tables[&person];
+        let alice = &table.rows[&table.row_order[0]];
+        assert_eq!(alice.fields[&name_attr], FieldValue::String("Alice".into()));
+        assert_eq!(alice.fields[&manager], FieldValue::RowRef(table.row_order[1]));
+
+        let exported = instance_to_acset(&schema, &namespace, &tables).unwrap();
+        assert_eq!(exported.attr_str("Person", 2, "name"), Some("Bob"));
+        assert_eq!(exported.subpart("Person", 1, "manager", "Person").unwrap(), 2);
+        assert_eq!(exported.nparts("String"), 0);
+    }
+}
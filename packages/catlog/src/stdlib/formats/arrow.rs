@@ -0,0 +1,347 @@
+//! Export of data frames in the Arrow IPC format.
+//!
+//! This module writes [data frames](DataFrame) in the [Arrow IPC streaming
+//! format](https://arrow.apache.org/docs/format/Columnar.html#ipc-streaming-format),
+//! readable by PyArrow, Polars, Arrow.jl, and other Arrow implementations. It
+//! supports only what is needed for data frames: a schema with non-nullable
+//! integer, floating point, and string columns, followed by a single record batch.
+//!
+//! Arrow metadata is serialized with
+//! [FlatBuffers](https://flatbuffers.dev/), for which a minimal builder is
+//! included here.
+
+use super::table::{Column, DataFrame};
+
+/// Version of the Arrow metadata format, namely V5.
+const METADATA_VERSION: i16 = 4;
+
+/// Marker preceding each message in the IPC format.
+const CONTINUATION: [u8; 4] = [0xFF; 4];
+
+/// Value of a field in a FlatBuffers table.
+enum Slot {
+    U8(u8),
+    I16(i16),
+    I32(i32),
+    I64(i64),
+    /// Offset to an object previously written to the buffer.
+    Offset(usize),
+}
+
+/// Minimal builder of FlatBuffers.
+///
+/// Like the official builders, it builds the buffer back to front, so that every
+/// object is written before the objects referring to it. Positions of objects are
+/// measured from the end of the buffer.
+#[derive(Default)]
+struct FlatBufferBuilder {
+    /// Bytes written so far, in reverse order.
+    rev: Vec<u8>,
+}
+
+impl FlatBufferBuilder {
+    fn len(&self) -> usize {
+        self.rev.len()
+    }
+
+    fn prepend(&mut self, bytes: &[u8]) {
+        self.rev.extend(bytes.iter().rev());
+    }
+
+    /// Pads so that the given number of bytes can then be written aligned.
+    fn align(&mut self, alignment: usize, additional: usize) {
+        while !(self.len() + additional).is_multiple_of(alignment) {
+            self.rev.push(0);
+        }
+    }
+
+    fn push_u32(&mut self, x: u32) {
+        self.align(4, 4);
+        self.prepend(&x.to_le_bytes());
+    }
+
+    fn push_offset(&mut self, target: usize) {
+        self.align(4, 4);
+        let offset = self.len() + 4 - target;
+        self.prepend(&(offset as u32).to_le_bytes());
+    }
+
+    fn create_string(&mut self, s: &str) -> usize {
+        self.align(4, s.len() + 1);
+        self.prepend(&[0]);
+        self.prepend(s.as_bytes());
+        self.push_u32(s.len() as u32);
+        self.len()
+    }
+
+    fn create_offset_vector(&mut self, targets: &[usize]) -> usize {
+        self.align(4, 4 * targets.len());
+        for target in targets.iter().rev() {
+            self.push_offset(*target);
+        }
+        self.push_u32(targets.len() as u32);
+        self.len()
+    }
+
+    /// Creates a vector of structs, each consisting of two 64-bit integers.
+    fn create_pair_vector(&mut self, pairs: &[(i64, i64)]) -> usize {
+        let bytes: Vec<u8> = pairs
+            .iter()
+            .flat_map(|(x, y)| x.to_le_bytes().into_iter().chain(y.to_le_bytes()))
+            .collect();
+        self.align(8, bytes.len());
+        self.prepend(&bytes);
+        self.push_u32(pairs.len() as u32);
+        self.len()
+    }
+
+    /// Creates a table with the given fields, indexed by their IDs in the schema.
+    fn create_table(&mut self, fields: &[(usize, Slot)]) -> usize {
+        let start = self.len();
+        let mut positions = Vec::new();
+        for (id, slot) in fields {
+            match slot {
+                Slot::U8(x) => self.prepend(&x.to_le_bytes()),
+                Slot::I16(x) => {
+                    self.align(2, 2);
+                    self.prepend(&x.to_le_bytes())
+                }
+                Slot::I32(x) => {
+                    self.align(4, 4);
+                    self.prepend(&x.to_le_bytes())
+                }
+                Slot::I64(x) => {
+                    self.align(8, 8);
+                    self.prepend(&x.to_le_bytes())
+                }
+                Slot::Offset(target) => self.push_offset(*target),
+            }
+            positions.push((*id, self.len()));
+        }
+
+        // The vtable is written immediately before the table.
+        let nslots = fields.iter().map(|(id, _)| id + 1).max().unwrap_or(0);
+        let vtable_len = 4 + 2 * nslots;
+        self.align(4, 4);
+        self.prepend(&(vtable_len as i32).to_le_bytes());
+        let table = self.len();
+
+        let mut vtable = vec![0u16; 2 + nslots];
+        vtable[0] = vtable_len as u16;
+        vtable[1] = (table - start) as u16;
+        for (id, position) in positions {
+            vtable[2 + id] = (table - position) as u16;
+        }
+        let bytes: Vec<u8> = vtable.into_iter().flat_map(|x| x.to_le_bytes()).collect();
+        self.prepend(&bytes);
+        table
+    }
+
+    /// Finishes the buffer with the given root table.
+    fn finish(mut self, root: usize) -> Vec<u8> {
+        self.align(8, 4);
+        self.push_offset(root);
+        self.rev.reverse();
+        self.rev
+    }
+}
+
+/// Creates an Arrow schema message for the data frame.
+fn schema_message(df: &DataFrame) -> Vec<u8> {
+    let mut fbb = FlatBufferBuilder::default();
+    let fields: Vec<_> = df
+        .columns()
+        .map(|(name, column)| {
+            let name = fbb.create_string(name);
+            // Members of the `Type` union: `Int`, `FloatingPoint`, and `Utf8`.
+            let (type_id, type_table) = match column {
+                Column::Int(_) => (2, fbb.create_table(&[(0, Slot::I32(32)), (1, Slot::U8(1))])),
+                Column::Float(_) => (3, fbb.create_table(&[(0, Slot::I16(1))])),
+                Column::Text(_) => (5, fbb.create_table(&[])),
+            };
+            let children = fbb.create_offset_vector(&[]);
+            fbb.create_table(&[
+                (0, Slot::Offset(name)),
+                (1, Slot::U8(0)),
+                (2, Slot::U8(type_id)),
+                (3, Slot::Offset(type_table)),
+                (5, Slot::Offset(children)),
+            ])
+        })
+        .collect();
+    let fields = fbb.create_offset_vector(&fields);
+    let schema = fbb.create_table(&[(0, Slot::I16(0)), (1, Slot::Offset(fields))]);
+    let message = fbb.create_table(&[
+        (0, Slot::I16(METADATA_VERSION)),
+        (1, Slot::U8(1)),
+        (2, Slot::Offset(schema)),
+        (3, Slot::I64(0)),
+    ]);
+    fbb.finish(message)
+}
+
+/// Creates an Arrow record batch message for the data frame, plus its body.
+fn record_batch_message(df: &DataFrame) -> (Vec<u8>, Vec<u8>) {
+    let mut body = Vec::new();
+    let mut buffers = Vec::new();
+    let mut add_buffer = |bytes: &[u8]| {
+        let offset = body.len();
+        body.extend_from_slice(bytes);
+        while !body.len().is_multiple_of(8) {
+            body.push(0);
+        }
+        buffers.push((offset as i64, bytes.len() as i64));
+    };
+    for (_, column) in df.columns() {
+        // Columns have no nulls, so their validity bitmaps can be omitted.
+        add_buffer(&[]);
+        match column {
+            Column::Int(values) => {
+                let bytes: Vec<_> = values.iter().flat_map(|x| x.to_le_bytes()).collect();
+                add_buffer(&bytes);
+            }
+            Column::Float(values) => {
+                let bytes: Vec<_> = values.iter().flat_map(|x| x.to_le_bytes()).collect();
+                add_buffer(&bytes);
+            }
+            Column::Text(values) => {
+                let mut offsets = vec![0i32];
+                let mut data = Vec::new();
+                for value in values {
+                    data.extend_from_slice(value.as_bytes());
+                    offsets.push(data.len() as i32);
+                }
+                let offsets: Vec<_> = offsets.iter().flat_map(|x| x.to_le_bytes()).collect();
+                add_buffer(&offsets);
+                add_buffer(&data);
+            }
+        }
+    }
+
+    let nrows = df.nrows() as i64;
+    let nodes: Vec<_> = df.columns().map(|_| (nrows, 0)).collect();
+    let mut fbb = FlatBufferBuilder::default();
+    let nodes = fbb.create_pair_vector(&nodes);
+    let buffers = fbb.create_pair_vector(&buffers);
+    let batch = fbb.create_table(&[
+        (0, Slot::I64(nrows)),
+        (1, Slot::Offset(nodes)),
+        (2, Slot::Offset(buffers)),
+    ]);
+    let message = fbb.create_table(&[
+        (0, Slot::I16(METADATA_VERSION)),
+        (1, Slot::U8(3)),
+        (2, Slot::Offset(batch)),
+        (3, Slot::I64(body.len() as i64)),
+    ]);
+    (fbb.finish(message), body)
+}
+
+/// Writes an encapsulated message of the IPC format.
+fn write_message(out: &mut Vec<u8>, metadata: &[u8], body: &[u8]) {
+    out.extend_from_slice(&CONTINUATION);
+    out.extend_from_slice(&(metadata.len() as i32).to_le_bytes());
+    out.extend_from_slice(metadata);
+    out.extend_from_slice(body);
+}
+
+impl DataFrame {
+    /// Writes the data frame in the Arrow IPC streaming format.
+    pub fn to_arrow_ipc(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        write_message(&mut out, &schema_message(self), &[]);
+        let (metadata, body) = record_batch_message(self);
+        write_message(&mut out, &metadata, &body);
+        // End-of-stream marker.
+        out.extend_from_slice(&CONTINUATION);
+        out.extend_from_slice(&[0; 4]);
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Minimal reader of FlatBuffers, following the format specification.
+    struct Reader<'a>(&'a [u8]);
+
+    impl Reader<'_> {
+        fn u32(&self, pos: usize) -> usize {
+            u32::from_le_bytes(self.0[pos..pos + 4].try_into().unwrap()) as usize
+        }
+        fn i64(&self, pos: usize) -> i64 {
+            i64::from_le_bytes(self.0[pos..pos + 8].try_into().unwrap())
+        }
+        fn deref(&self, pos: usize) -> usize {
+            pos + self.u32(pos)
+        }
+        fn field(&self, table: usize, id: usize) -> Option<usize> {
+            let vtable =
+                table - i32::from_le_bytes(self.0[table..table + 4].try_into().unwrap()) as usize;
+            let vtable_len = u16::from_le_bytes([self.0[vtable], self.0[vtable + 1]]) as usize;
+            if 4 + 2 * id >= vtable_len {
+                return None;
+            }
+            let pos = vtable + 4 + 2 * id;
+            let offset = u16::from_le_bytes([self.0[pos], self.0[pos + 1]]) as usize;
+            (offset > 0).then_some(table + offset)
+        }
+        fn string(&self, pos: usize) -> &str {
+            let len = self.u32(pos);
+            std::str::from_utf8(&self.0[pos + 4..pos + 4 + len]).unwrap()
+        }
+    }
+
+    #[test]
+    fn ipc_stream() {
+        let mut df = DataFrame::new();
+        df.add_column("time", Column::Float(vec![0.0, 0.5, 1.0]));
+        df.add_column("run", Column::Int(vec![1, 2, 3]));
+        df.add_column("label", Column::Text(vec!["S".into(), "".into(), "IR".into()]));
+        let bytes = df.to_arrow_ipc();
+        assert_eq!(bytes.len() % 8, 0);
+
+        // Schema message.
+        assert_eq!(bytes[0..4], CONTINUATION);
+        let len = u32::from_le_bytes(bytes[4..8].try_into().unwrap()) as usize;
+        let meta = Reader(&bytes[8..8 + len]);
+        let message = meta.deref(0);
+        assert_eq!(meta.0[meta.field(message, 1).unwrap()], 1);
+        let schema = meta.deref(meta.field(message, 2).unwrap());
+        let fields = meta.deref(meta.field(schema, 1).unwrap());
+        let names: Vec<_> = (0..meta.u32(fields))
+            .map(|i| {
+                let field = meta.deref(fields + 4 + 4 * i);
+                meta.string(meta.deref(meta.field(field, 0).unwrap()))
+            })
+            .collect();
+        assert_eq!(names, vec!["time", "run", "label"]);
+
+        // Record batch message.
+        let rest = &bytes[8 + len..];
+        assert_eq!(rest[0..4], CONTINUATION);
+        let len = u32::from_le_bytes(rest[4..8].try_into().unwrap()) as usize;
+        let meta = Reader(&rest[8..8 + len]);
+        let message = meta.deref(0);
+        assert_eq!(meta.0[meta.field(message, 1).unwrap()], 3);
+        let body_len = meta.i64(meta.field(message, 3).unwrap()) as usize;
+        let batch = meta.deref(meta.field(message, 2).unwrap());
+        assert_eq!(meta.i64(meta.field(batch, 0).unwrap()), 3);
+        let buffers = meta.deref(meta.field(batch, 2).unwrap());
+        assert_eq!(meta.u32(buffers), 7);
+        let body = &rest[8 + len..8 + len + body_len];
+        let buffer = |i: usize| {
+            let offset = meta.i64(buffers + 4 + 16 * i) as usize;
+            let len = meta.i64(buffers + 4 + 16 * i + 8) as usize;
+            &body[offset..offset + len]
+        };
+        assert_eq!(buffer(1), [0.0f32, 0.5, 1.0].map(f32::to_le_bytes).concat());
+        assert_eq!(buffer(3), [1i32, 2, 3].map(i32::to_le_bytes).concat());
+        assert_eq!(buffer(5), [0i32, 1, 1, 3].map(i32::to_le_bytes).concat());
+        assert_eq!(buffer(6), b"SIR");
+
+        // End-of-stream marker.
+        assert_eq!(rest[8 + len + body_len..], [0xFF, 0xFF, 0xFF, 0xFF, 0, 0, 0, 0]);
+    }
+}
@@ -0,0 +1,521 @@
+//! Drawings of models and wiring diagrams in DOT and Mermaid.
+//!
+//! Models of double theories and undirected wiring diagrams are first converted
+//! into a [`GraphDrawing`], a graph with labelled and styled nodes and edges,
+//! which can then be rendered in the [DOT](https://graphviz.org/doc/info/lang.html)
+//! language of Graphviz or as a [Mermaid](https://mermaid.js.org) flowchart.
+//!
+//! Object generators of a model are drawn as nodes and morphism generators
+//! between them as edges. Morphisms whose domain or codomain is a list of
+//! objects, such as transitions in a Petri net, are drawn as hyperedges, i.e., as
+//! box nodes connected to each object in the lists. Morphisms that are the
+//! targets of other morphisms, such as flows in a stock-flow diagram, are drawn in
+//! the same way, so that links can point at them.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt::{Display, Write};
+use std::hash::Hash;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "serde-wasm")]
+use tsify::Tsify;
+
+use crate::dbl::model::*;
+use crate::dbl::theory::{DblTheoryKind, ModalMorType, TabMorType};
+use crate::one::{FgCategory, Path, QualifiedPath, ShortPath};
+use crate::wd::UWD;
+use crate::zero::{Namespace, QualifiedName};
+
+/// Format in which to render a graph drawing.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-wasm", derive(Tsify))]
+#[cfg_attr(feature = "serde-wasm", tsify(into_wasm_abi, from_wasm_abi))]
+pub enum GraphFormat {
+    /// The DOT language of Graphviz.
+    Dot,
+
+    /// A Mermaid flowchart.
+    Mermaid,
+}
+
+/// Shape of a node in a graph drawing.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NodeShape {
+    /// An ellipse, used for objects.
+    Ellipse,
+
+    /// A rectangle, used for hyperedges and boxes.
+    Box,
+
+    /// A small circle, used for junctions.
+    Point,
+
+    /// Text without a border, used for outer ports.
+    Plain,
+}
+
+/// Style of an edge in a graph drawing.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EdgeStyle {
+    /// A solid line.
+    Solid,
+
+    /// A dashed line.
+    Dashed,
+}
+
+/// A node in a graph drawing.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DrawingNode {
+    /// Identifier of the node, unique within the drawing.
+    pub id: String,
+
+    /// Label displayed on the node.
+    pub label: String,
+
+    /// Shape of the node.
+    pub shape: NodeShape,
+}
+
+/// An edge in a graph drawing.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DrawingEdge {
+    /// Identifier of the source node.
+    pub source: String,
+
+    /// Identifier of the target node.
+    pub target: String,
+
+    /// Label displayed on the edge, if any.
+    pub label: Option<String>,
+
+    /// Style of the edge.
+    pub style: EdgeStyle,
+
+    /// Whether the edge is drawn with an arrowhead.
+    pub directed: bool,
+}
+
+/// A graph with labelled and styled nodes and edges, ready to be rendered.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct GraphDrawing {
+    /// Nodes of the graph, in order of drawing.
+    pub nodes: Vec<DrawingNode>,
+
+    /// Edges of the graph, in order of drawing.
+    pub edges: Vec<DrawingEdge>,
+}
+
+impl GraphDrawing {
+    /// Adds a node with a fresh identifier, which is returned.
+    pub fn add_node(&mut self, label: impl Into<String>, shape: NodeShape) -> String {
+        let id = format!("n{}", self.nodes.len());
+        self.nodes.push(DrawingNode {
+            id: id.clone(),
+            label: label.into(),
+            shape,
+        });
+        id
+    }
+
+    /// Adds a directed edge between two nodes.
+    pub fn add_edge(&mut self, source: String, target: String, label: Option<String>) {
+        self.edges.push(DrawingEdge {
+            source,
+            target,
+            label,
+            style: EdgeStyle::Solid,
+            directed: true,
+        });
+    }
+
+    /// Renders the drawing in the given format.
+    pub fn render(&self, format: GraphFormat) -> String {
+        match format {
+            GraphFormat::Dot => self.to_dot(),
+            GraphFormat::Mermaid => self.to_mermaid(),
+        }
+    }
+
+    /// Renders the drawing in the DOT language.
+    pub fn to_dot(&self) -> String {
+        let quote = |s: &str| format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""));
+        let mut out = String::from("digraph {\n  rankdir=LR;\n");
+        for node in &self.nodes {
+            let label = quote(&node.label);
+            let attrs = match node.shape {
+                NodeShape::Ellipse => format!("label={label}, shape=ellipse"),
+                NodeShape::Box => format!("label={label}, shape=box"),
+                NodeShape::Point => format!("label=\"\", xlabel={label}, shape=point"),
+                NodeShape::Plain => format!("label={label}, shape=plaintext"),
+            };
+            writeln!(out, "  {} [{attrs}];", node.id).unwrap();
+        }
+        for edge in &self.edges {
+            let mut attrs = Vec::new();
+            if let Some(label) = &edge.label {
+                attrs.push(format!("label={}", quote(label)));
+            }
+            if edge.style == EdgeStyle::Dashed {
+                attrs.push("style=dashed".into());
+            }
+            if !edge.directed {
+                attrs.push("dir=none".into());
+            }
+            write!(out, "  {} -> {}", edge.source, edge.target).unwrap();
+            if !attrs.is_empty() {
+                write!(out, " [{}]", attrs.join(", ")).unwrap();
+            }
+            out.push_str(";\n");
+        }
+        out.push('}');
+        out
+    }
+
+    /// Renders the drawing as a Mermaid flowchart.
+    pub fn to_mermaid(&self) -> String {
+        let quote = |s: &str| format!("\"{}\"", s.replace('"', "#quot;"));
+        let mut out = String::from("flowchart LR");
+        for node in &self.nodes {
+            let label = quote(&node.label);
+            let shape = match node.shape {
+                NodeShape::Ellipse => format!("([{label}])"),
+                NodeShape::Box => format!("[{label}]"),
+                NodeShape::Point => format!("(({label}))"),
+                NodeShape::Plain => format!(">{label}]"),
+            };
+            write!(out, "\n  {}{shape}", node.id).unwrap();
+        }
+        for edge in &self.edges {
+            let arrow = match (edge.style, edge.directed) {
+                (EdgeStyle::Solid, true) => "-->",
+                (EdgeStyle::Solid, false) => "---",
+                (EdgeStyle::Dashed, true) => "-.->",
+                (EdgeStyle::Dashed, false) => "-.-",
+            };
+            let label = edge.label.as_ref().map(|s| format!("|{}|", quote(s))).unwrap_or_default();
+            write!(out, "\n  {} {arrow}{label} {}", edge.source, edge.target).unwrap();
+        }
+        out
+    }
+}
+
+/// A model of a double theory that can be drawn as a graph.
+pub trait DrawableDblModel: PrintableDblModel {
+    /// Draws the model, labelling generators using the given namespaces.
+    fn to_drawing(&self, ob_ns: &Namespace, mor_ns: &Namespace) -> GraphDrawing;
+}
+
+/// Key identifying a node drawn for part of a model.
+#[derive(PartialEq, Eq, Hash)]
+enum NodeKey {
+    Ob(QualifiedName),
+    Mor(QualifiedName),
+    Other(String),
+}
+
+/// Helper to draw a model, keeping track of the nodes already drawn.
+struct ModelDrawer<'a, Model: PrintableDblModel> {
+    model: &'a Model,
+    ob_ns: &'a Namespace,
+    mor_ns: &'a Namespace,
+    is_hom_type: fn(&Model::MorType) -> bool,
+    drawing: GraphDrawing,
+    nodes: HashMap<NodeKey, String>,
+}
+
+impl<'a, Model: PrintableDblModel> ModelDrawer<'a, Model> {
+    /// Starts a drawing with a node for each object generator.
+    ///
+    /// Morphisms whose types are hom types, as determined by the given
+    /// predicate, are labelled without their types.
+    fn new(
+        model: &'a Model,
+        ob_ns: &'a Namespace,
+        mor_ns: &'a Namespace,
+        is_hom_type: fn(&Model::MorType) -> bool,
+    ) -> Self {
+        let mut drawer = Self {
+            model,
+            ob_ns,
+            mor_ns,
+            is_hom_type,
+            drawing: GraphDrawing::default(),
+            nodes: HashMap::new(),
+        };
+        for x in model.ob_generators() {
+            drawer.ob_gen_node(&x);
+        }
+        drawer
+    }
+
+    fn node(&mut self, key: NodeKey, label: impl FnOnce() -> String, shape: NodeShape) -> String {
+        let drawing = &mut self.drawing;
+        self.nodes
+            .entry(key)
+            .or_insert_with(|| drawing.add_node(label(), shape))
+            .clone()
+    }
+
+    /// Gets the node for an object generator.
+    fn ob_gen_node(&mut self, x: &QualifiedName) -> String {
+        let ob_ns = self.ob_ns;
+        self.node(NodeKey::Ob(x.clone()), || ob_ns.label_string(x), NodeShape::Ellipse)
+    }
+
+    /// Gets the node for a morphism generator drawn as a hyperedge.
+    fn mor_gen_node(&mut self, f: &QualifiedName) -> String {
+        let label = self.mor_label(f).unwrap_or_default();
+        self.node(NodeKey::Mor(f.clone()), || label, NodeShape::Box)
+    }
+
+    /// Gets the node for an object that is not a generator.
+    fn other_ob_node(&mut self, ob: &Model::Ob) -> String {
+        let label = self.model.ob_to_doc(ob, self.ob_ns, self.mor_ns).0.pretty(80).to_string();
+        self.node(NodeKey::Other(label.clone()), || label, NodeShape::Ellipse)
+    }
+
+    /// Label of a morphism generator, including its type unless it is a hom type.
+    fn mor_label(&self, f: &QualifiedName) -> Option<String> {
+        let label = self.mor_ns.label(f).map(|label| label.to_string());
+        let mor_type = self.model.mor_generator_type(f);
+        if (self.is_hom_type)(&mor_type) {
+            return label;
+        }
+        let mor_type = Model::mor_type_to_doc(&mor_type).0.pretty(80).to_string();
+        Some(match label {
+            Some(label) => format!("{label} : {mor_type}"),
+            None => mor_type,
+        })
+    }
+
+    /// Draws a morphism generator as an edge between two nodes.
+    fn edge(&mut self, f: &QualifiedName, source: String, target: String, style: EdgeStyle) {
+        let label = self.mor_label(f);
+        self.drawing.edges.push(DrawingEdge {
+            source,
+            target,
+            label,
+            style,
+            directed: true,
+        });
+    }
+
+    /// Draws a morphism generator as a hyperedge between lists of nodes.
+    fn hyperedge(&mut self, f: &QualifiedName, sources: Vec<String>, targets: Vec<String>) {
+        let node = self.mor_gen_node(f);
+        for source in sources {
+            self.drawing.add_edge(source, node.clone(), None);
+        }
+        for target in targets {
+            self.drawing.add_edge(node.clone(), target, None);
+        }
+    }
+}
+
+impl DrawableDblModel for DiscreteDblModel {
+    fn to_drawing(&self, ob_ns: &Namespace, mor_ns: &Namespace) -> GraphDrawing {
+        let is_hom_type = |mor_type: &QualifiedPath| matches!(mor_type, Path::Id(_));
+        let mut drawer = ModelDrawer::new(self, ob_ns, mor_ns, is_hom_type);
+        for f in self.mor_generators() {
+            let source = drawer.ob_gen_node(&self.mor_generator_dom(&f));
+            let target = drawer.ob_gen_node(&self.mor_generator_cod(&f));
+            drawer.edge(&f, source, target, EdgeStyle::Solid);
+        }
+        drawer.drawing
+    }
+}
+
+impl DrawableDblModel for DiscreteTabModel {
+    fn to_drawing(&self, ob_ns: &Namespace, mor_ns: &Namespace) -> GraphDrawing {
+        // A tabulated morphism generator, such as a flow targeted by a link.
+        let tabulated_gen = |ob: &TabOb| match ob.clone().tabulated()?.only()? {
+            TabEdge::Basic(f) => Some(f),
+            TabEdge::Square { .. } => None,
+        };
+        let tabulated: HashSet<_> = self
+            .mor_generators()
+            .flat_map(|f| [self.mor_generator_dom(&f), self.mor_generator_cod(&f)])
+            .filter_map(|ob| tabulated_gen(&ob))
+            .collect();
+
+        let is_hom_type = |mor_type: &TabMorType| matches!(mor_type, TabMorType::Hom(_));
+        let mut drawer = ModelDrawer::new(self, ob_ns, mor_ns, is_hom_type);
+        let node = |drawer: &mut ModelDrawer<_>, ob: &TabOb| match ob {
+            TabOb::Basic(x) => drawer.ob_gen_node(x),
+            _ => match tabulated_gen(ob) {
+                Some(f) => drawer.mor_gen_node(&f),
+                None => drawer.other_ob_node(ob),
+            },
+        };
+        for f in self.mor_generators() {
+            let (dom, cod) = (self.mor_generator_dom(&f), self.mor_generator_cod(&f));
+            let (source, target) = (node(&mut drawer, &dom), node(&mut drawer, &cod));
+            if tabulated.contains(&f) {
+                drawer.hyperedge(&f, vec![source], vec![target]);
+            } else if matches!(dom, TabOb::Tabulated(_)) || matches!(cod, TabOb::Tabulated(_)) {
+                drawer.edge(&f, source, target, EdgeStyle::Dashed);
+            } else {
+                drawer.edge(&f, source, target, EdgeStyle::Solid);
+            }
+        }
+        drawer.drawing
+    }
+}
+
+impl<Kind: DblTheoryKind> DrawableDblModel for ModalDblModel<Kind> {
+    fn to_drawing(&self, ob_ns: &Namespace, mor_ns: &Namespace) -> GraphDrawing {
+        let is_hom_type = |mor_type: &ModalMorType| matches!(mor_type, ShortPath::Zero(_));
+        let mut drawer = ModelDrawer::new(self, ob_ns, mor_ns, is_hom_type);
+        let nodes = |drawer: &mut ModelDrawer<_>, ob: ModalOb| -> Vec<String> {
+            let obs = match ob {
+                ModalOb::App(ob, op) => match *ob {
+                    ModalOb::List(_, obs) => obs,
+                    ob => vec![ModalOb::App(ob.into(), op)],
+                },
+                ModalOb::List(_, obs) => obs,
+                ob => vec![ob],
+            };
+            obs.iter()
+                .map(|ob| match ob {
+                    ModalOb::Generator(x) => drawer.ob_gen_node(x),
+                    _ => drawer.other_ob_node(ob),
+                })
+                .collect()
+        };
+        for f in self.mor_generators() {
+            let (dom, cod) = (self.mor_generator_dom(&f), self.mor_generator_cod(&f));
+            match (dom, cod) {
+                (ModalOb::Generator(x), ModalOb::Generator(y)) => {
+                    let (source, target) = (drawer.ob_gen_node(&x), drawer.ob_gen_node(&y));
+                    drawer.edge(&f, source, target, EdgeStyle::Solid);
+                }
+                (dom, cod) => {
+                    let (sources, targets) = (nodes(&mut drawer, dom), nodes(&mut drawer, cod));
+                    drawer.hyperedge(&f, sources, targets);
+                }
+            }
+        }
+        drawer.drawing
+    }
+}
+
+/// Draws an undirected wiring diagram.
+///
+/// Boxes are drawn as box nodes and junctions as points, with an undirected edge,
+/// labelled by the port, for each port of a box assigned to a junction. Outer
+/// ports are drawn as plain text connected to their junctions by unlabelled
+/// dashed edges.
+pub fn uwd_drawing<T: Clone + Eq, J: Display + Clone + Eq + Hash>(uwd: &UWD<T, J>) -> GraphDrawing {
+    let mut drawing = GraphDrawing::default();
+    let mut junctions: HashMap<J, String> = HashMap::new();
+    let mut junction_node = |drawing: &mut GraphDrawing, j: &J| {
+        junctions
+            .entry(j.clone())
+            .or_insert_with(|| drawing.add_node(j.to_string(), NodeShape::Point))
+            .clone()
+    };
+    let undirected = |source, target, label: Option<String>, style| DrawingEdge {
+        source,
+        target,
+        label,
+        style,
+        directed: false,
+    };
+
+    for (&name, (label, _)) in uwd.outer_ports().iter() {
+        let port = drawing.add_node(label.to_string(), NodeShape::Plain);
+        if let Some(j) = uwd.get_outer(name) {
+            let junction = junction_node(&mut drawing, j);
+            let edge = undirected(port, junction, None, EdgeStyle::Dashed);
+            drawing.edges.push(edge);
+        }
+    }
+    for (&box_name, box_label, ports) in uwd.boxes() {
+        let node = drawing.add_node(box_label.to_string(), NodeShape::Box);
+        for (&port_name, (port_label, _)) in ports.iter() {
+            if let Some(j) = uwd.get(box_name, port_name) {
+                let junction = junction_node(&mut drawing, j);
+                let edge = undirected(
+                    node.clone(),
+                    junction,
+                    Some(port_label.to_string()),
+                    EdgeStyle::Solid,
+                );
+                drawing.edges.push(edge);
+            }
+        }
+    }
+    drawing
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use super::*;
+    use crate::stdlib::{models::*, theories::*};
+    use crate::zero::{label_seg, name, name_seg};
+
+    #[test]
+    fn draw_discrete_model() {
+        let model = negative_loop(Rc::new(th_signed_category()));
+        let ns = Namespace::new_for_text();
+        let drawing = model.to_drawing(&ns, &ns);
+        assert_eq!(drawing.nodes.len(), 1);
+        assert_eq!(drawing.edges[0].label.as_deref(), Some("loop : Negative"));
+        assert_eq!(
+            drawing.to_dot(),
+            "digraph {\n  rankdir=LR;\n  n0 [label=\"x\", shape=ellipse];\n  \
+             n0 -> n0 [label=\"loop : Negative\"];\n}"
+        );
+        assert_eq!(
+            drawing.to_mermaid(),
+            "flowchart LR\n  n0([\"x\"])\n  n0 -->|\"loop : Negative\"| n0"
+        );
+    }
+
+    #[test]
+    fn draw_petri_net() {
+        let model = sir_petri(Rc::new(th_sym_monoidal_category()));
+        let ns = Namespace::new_for_text();
+        let drawing = model.to_drawing(&ns, &ns);
+        let labels: Vec<_> = drawing.nodes.iter().map(|node| node.label.as_str()).collect();
+        assert_eq!(labels, vec!["S", "I", "R", "infect"]);
+        assert_eq!(drawing.nodes[3].shape, NodeShape::Box);
+        // Two input arcs and two output arcs for infection, one edge for recovery.
+        assert_eq!(drawing.edges.len(), 5);
+        assert!(drawing.render(GraphFormat::Mermaid).contains("n1 -->|\"recover\"| n2"));
+    }
+
+    #[test]
+    fn draw_stock_flow() {
+        let model = backward_link(Rc::new(th_category_links()));
+        let ns = Namespace::new_for_text();
+        let drawing = model.to_drawing(&ns, &ns);
+        assert_eq!(drawing.nodes.len(), 3);
+        assert_eq!(drawing.nodes[2].label, "f");
+        let link = drawing.edges.iter().find(|edge| edge.style == EdgeStyle::Dashed).unwrap();
+        assert_eq!((link.source.as_str(), link.target.as_str()), ("n1", "n2"));
+        assert_eq!(link.label.as_deref(), Some("link : Link"));
+    }
+
+    #[test]
+    fn draw_uwd() {
+        let mut uwd: UWD<_, QualifiedName> = UWD::empty();
+        let (x, y) = (name("x"), name("y"));
+        uwd.add_outer_port(name_seg("a"), label_seg("a"), "Object");
+        uwd.set_outer(name_seg("a"), x.clone());
+        uwd.add_box(name_seg("f"), label_seg("f"));
+        uwd.add_port(name_seg("f"), name_seg("in"), label_seg("in"), "Object");
+        uwd.add_port(name_seg("f"), name_seg("out"), label_seg("out"), "Object");
+        uwd.set(name_seg("f"), name_seg("in"), x);
+        uwd.set(name_seg("f"), name_seg("out"), y);
+        let drawing = uwd_drawing(&uwd);
+        assert_eq!(drawing.nodes.len(), 4);
+        assert_eq!(drawing.edges.len(), 3);
+        assert!(drawing.to_dot().contains("n2 -> n1 [label=\"in\", dir=none];"));
+    }
+}
@@ -0,0 +1,60 @@
+//! Identifiers for interchange formats and generated code.
+//!
+//! Exported documents and programs refer to objects and morphisms by identifiers
+//! derived from their labels, which must be valid in the target format and
+//! distinct from each other and from any names that the format reserves.
+
+use std::collections::HashSet;
+
+/// Generator of unique identifiers from arbitrary labels.
+pub(crate) struct IdGenerator {
+    /// Canonical forms of the identifiers used so far.
+    used: HashSet<String>,
+
+    /// Canonical form of an identifier, identifying those that the target
+    /// format regards as the same.
+    canonical: fn(&str) -> String,
+}
+
+impl Default for IdGenerator {
+    fn default() -> Self {
+        Self::with_canonical(str::to_string)
+    }
+}
+
+impl IdGenerator {
+    /// Creates a generator that never produces the given reserved words.
+    pub(crate) fn with_reserved<'a>(words: impl IntoIterator<Item = &'a str>) -> Self {
+        let mut ids = Self::default();
+        ids.used.extend(words.into_iter().map(String::from));
+        ids
+    }
+
+    /// Creates a generator for a format in which identifiers with the same
+    /// canonical form refer to the same thing.
+    pub(crate) fn with_canonical(canonical: fn(&str) -> String) -> Self {
+        Self { used: HashSet::new(), canonical }
+    }
+
+    /// Makes a valid identifier from the label, distinct from all previous ones
+    /// up to canonical form.
+    ///
+    /// Identifiers consist of ASCII letters, digits, and underscores and do not
+    /// start with a digit, making them valid as XML names, as SBML identifiers,
+    /// and as variable names in common programming languages.
+    pub(crate) fn fresh(&mut self, label: &str) -> String {
+        let mut base: String =
+            label.chars().map(|c| if c.is_ascii_alphanumeric() { c } else { '_' }).collect();
+        if !base.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_') {
+            base.insert(0, '_');
+        }
+        let mut id = base.clone();
+        let mut i = 1;
+        while self.used.contains(&(self.canonical)(&id)) {
+            i += 1;
+            id = format!("{base}_{i}");
+        }
+        self.used.insert((self.canonical)(&id));
+        id
+    }
+}
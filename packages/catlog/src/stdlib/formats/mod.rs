@@ -0,0 +1,14 @@
+//! Import and export of models and data in external interchange formats.
+
+pub mod acset;
+pub mod arrow;
+pub mod drawing;
+pub(crate) mod ids;
+pub mod pnml;
+pub mod table;
+pub mod xml;
+
+#[cfg(feature = "ode")]
+pub mod sbml;
+#[cfg(feature = "ode")]
+pub mod xmile;
@@ -0,0 +1,399 @@
+//! PNML import and export for Petri nets.
+//!
+//! The [Petri Net Markup Language](https://www.pnml.org) (PNML) is the ISO/IEC
+//! 15909-2 interchange format for Petri nets, read by model checkers and other
+//! verification tools. This module translates between place/transition nets in
+//! PNML and Petri nets as models of the [theory of symmetric monoidal
+//! categories](crate::stdlib::theories::th_sym_monoidal_category), together with
+//! the initial markings of a [reachability problem](ReachabilityProblemData).
+//! Arc weights correspond to the multiplicities of places in the inputs and
+//! outputs of transitions.
+
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use indexmap::IndexMap;
+use thiserror::Error;
+use uuid::Uuid;
+
+use super::ids::IdGenerator;
+use super::xml::{self, Element, XmlError};
+use crate::dbl::model::{ModalDblModel, ModalOb, MutDblModel};
+use crate::dbl::theory::{ModalDblTheory, ModalMorType, ModalObType, Unital};
+use crate::one::FgCategory;
+use crate::stdlib::analyses::petri::{transition_boundary, transition_interface};
+use crate::stdlib::analyses::reachability::ReachabilityProblemData;
+use crate::zero::{Namespace, QualifiedName, label_seg, name};
+
+const PNML_NAMESPACE: &str = "http://www.pnml.org/version-2009/grammar/pnml";
+const PT_NET_TYPE: &str = "http://www.pnml.org/version-2009/grammar/ptnet";
+
+/// Largest arc weight accepted on import.
+///
+/// Places are repeated according to arc weights, so unreasonably large weights
+/// are rejected rather than exhausting memory.
+pub const MAX_PNML_ARC_WEIGHT: i32 = 1000;
+
+/// A Petri net imported from PNML.
+pub struct PnmlPetriNet {
+    /// Petri net with the places and transitions of the PNML net.
+    pub model: ModalDblModel<Unital>,
+
+    /// Namespace labelling the places and transitions by their PNML names.
+    pub namespace: Namespace,
+
+    /// Initial marking of the net, with no forbidden tokens.
+    pub data: ReachabilityProblemData,
+}
+
+/// An error in importing PNML.
+#[derive(Debug, Error)]
+pub enum PnmlError {
+    /// The document is not well-formed XML.
+    #[error("Invalid XML: {0}")]
+    Xml(#[from] XmlError),
+
+    /// The document does not contain a PNML net.
+    #[error("Document is not a PNML net")]
+    NotPnml,
+
+    /// The net is not a place/transition net.
+    #[error("Net type `{0}` is not supported, only place/transition nets")]
+    UnsupportedNetType(String),
+
+    /// A marking or arc weight is not a nonnegative integer.
+    #[error("Invalid number of tokens `{0}`")]
+    InvalidTokens(String),
+
+    /// An arc refers to a node not declared in the net.
+    #[error("Arc `{0}` refers to unknown node `{1}`")]
+    UnknownNode(String, String),
+
+    /// An arc has a weight larger than [`MAX_PNML_ARC_WEIGHT`].
+    #[error("Arc `{0}` has weight {1}, more than the maximum of {max}", max = MAX_PNML_ARC_WEIGHT)]
+    ArcWeightTooLarge(String, i32),
+
+    /// An arc does not connect a place with a transition.
+    #[error("Arc `{0}` does not connect a place with a transition")]
+    InvalidArc(String),
+}
+
+/// Exports a Petri net with an initial marking as a PNML place/transition net.
+///
+/// Places and transitions are given PNML identifiers derived from their labels in
+/// the namespace, made unique if necessary, and the labels themselves are kept as
+/// PNML names. Repeated places in the inputs or outputs of a transition become a
+/// single arc whose weight is the multiplicity. Forbidden tokens are not part of
+/// the net and are ignored.
+pub fn write_pnml(
+    model: &ModalDblModel<Unital>,
+    data: &ReachabilityProblemData,
+    namespace: &Namespace,
+) -> String {
+    let mut ids = IdGenerator::default();
+    let (net_id, page_id) = (ids.fresh("net"), ids.fresh("page"));
+    let named = |tag: &str, id: &str, label: &str| {
+        Element::new(tag)
+            .attr("id", id)
+            .child(Element::new("name").child(Element::new("text").text(label)))
+    };
+
+    let mut nodes = Vec::new();
+    let mut place_ids = HashMap::new();
+    for ob in model.ob_generators() {
+        let label = namespace.label_string(&ob);
+        let id = ids.fresh(&label);
+        let mut place = named("place", &id, &label);
+        let tokens = data.tokens.get(&ob).copied().unwrap_or_default();
+        if tokens != 0 {
+            place = place.child(
+                Element::new("initialMarking").child(Element::new("text").text(tokens.to_string())),
+            );
+        }
+        nodes.push(place);
+        place_ids.insert(ob, id);
+    }
+
+    let mut arcs = Vec::new();
+    for mor in model.mor_generators() {
+        let label = namespace.label_string(&mor);
+        let id = ids.fresh(&label);
+        nodes.push(named("transition", &id, &label));
+
+        let (inputs, outputs) = transition_interface(model, &mor);
+        let weights = |obs: Vec<ModalOb>| {
+            let mut counts: IndexMap<&str, usize> = IndexMap::new();
+            for ob in obs {
+                *counts.entry(place_ids[&ob.unwrap_generator()].as_str()).or_default() += 1;
+            }
+            counts
+        };
+        let inputs = weights(inputs).into_iter().map(|(place, n)| (place, id.as_str(), n));
+        let outputs = weights(outputs).into_iter().map(|(place, n)| (id.as_str(), place, n));
+        for (source, target, n) in inputs.chain(outputs) {
+            let mut arc = Element::new("arc")
+                .attr("id", ids.fresh(&format!("{source}_{target}")))
+                .attr("source", source)
+                .attr("target", target);
+            if n != 1 {
+                arc = arc.child(
+                    Element::new("inscription").child(Element::new("text").text(n.to_string())),
+                );
+            }
+            arcs.push(arc);
+        }
+    }
+
+    let net = Element::new("net")
+        .attr("id", net_id)
+        .attr("type", PT_NET_TYPE)
+        .child(Element::new("page").attr("id", page_id).children(nodes).children(arcs));
+    Element::new("pnml").attr("xmlns", PNML_NAMESPACE).child(net).to_document()
+}
+
+/// Imports the first net of a PNML document as a Petri net.
+///
+/// Places and transitions are given fresh UUIDs as names, labelled in the
+/// returned namespace by their PNML names, or by their identifiers when they have
+/// no names. Nodes and arcs may be spread across nested pages. Arcs between the
+/// same place and transition are combined by adding their weights.
+pub fn read_pnml(
+    input: &str,
+    theory: Rc<ModalDblTheory<Unital>>,
+) -> Result<PnmlPetriNet, PnmlError> {
+    let root = xml::parse(input)?;
+    if root.local_name() != "pnml" {
+        return Err(PnmlError::NotPnml);
+    }
+    let net = root.find("net").ok_or(PnmlError::NotPnml)?;
+    match net.get_attr("type") {
+        Some(net_type) if net_type != PT_NET_TYPE => {
+            return Err(PnmlError::UnsupportedNetType(net_type.into()));
+        }
+        _ => {}
+    }
+    let mut elements = Vec::new();
+    collect_page_elements(net, &mut elements);
+
+    let ob_type = ModalObType::new(name("Object"));
+    let mor_type = ModalMorType::Zero(ob_type.clone());
+    let mut model = ModalDblModel::new(theory);
+    let mut namespace = Namespace::new_for_uuid();
+    let mut tokens = HashMap::new();
+    let mut fresh_name = |elem: &Element, id: &str| {
+        let uuid = Uuid::now_v7();
+        let label = elem
+            .find("name")
+            .map(|name| name.find("text").map(|text| text.text_content()).unwrap_or_default())
+            .filter(|s| !s.trim().is_empty());
+        namespace.set_label(uuid, label_seg(label.as_deref().map(str::trim).unwrap_or(id)));
+        QualifiedName::from(uuid)
+    };
+
+    let mut places = HashMap::new();
+    let mut transitions = IndexMap::new();
+    for elem in elements.iter() {
+        let Some(id) = elem.get_attr("id") else {
+            continue;
+        };
+        match elem.local_name() {
+            "place" => {
+                let ob = fresh_name(elem, id);
+                if let Some(marking) = elem.find("initialMarking") {
+                    tokens.insert(ob.clone(), parse_tokens(marking)?);
+                }
+                model.add_ob(ob.clone(), ob_type.clone());
+                places.insert(id, ob);
+            }
+            "transition" => {
+                let mor = fresh_name(elem, id);
+                transitions.insert(id, (mor, Vec::new(), Vec::new()));
+            }
+            _ => {}
+        }
+    }
+
+    for arc in elements.iter().filter(|elem| elem.local_name() == "arc") {
+        let id = arc.get_attr("id").unwrap_or_default();
+        let node = |attr: &str| -> Result<&str, PnmlError> {
+            let node = arc.get_attr(attr).unwrap_or_default();
+            if places.contains_key(node) || transitions.contains_key(node) {
+                Ok(node)
+            } else {
+                Err(PnmlError::UnknownNode(id.into(), node.into()))
+            }
+        };
+        let (source, target) = (node("source")?, node("target")?);
+        let weight = match arc.find("inscription") {
+            Some(inscription) => parse_tokens(inscription)?,
+            None => 1,
+        };
+        if weight > MAX_PNML_ARC_WEIGHT {
+            return Err(PnmlError::ArcWeightTooLarge(id.into(), weight));
+        }
+        let (place, (_, inputs, outputs)) = if let Some(place) = places.get(source) {
+            (
+                place,
+                transitions.get_mut(target).ok_or_else(|| PnmlError::InvalidArc(id.into()))?,
+            )
+        } else if let Some(place) = places.get(target) {
+            (
+                place,
+                transitions.get_mut(source).ok_or_else(|| PnmlError::InvalidArc(id.into()))?,
+            )
+        } else {
+            return Err(PnmlError::InvalidArc(id.into()));
+        };
+        let boundary = if places.contains_key(source) {
+            inputs
+        } else {
+            outputs
+        };
+        boundary.extend(std::iter::repeat_n(place.clone(), weight as usize));
+    }
+
+    for (mor, inputs, outputs) in transitions.into_values() {
+        model.add_mor(
+            mor,
+            transition_boundary(inputs),
+            transition_boundary(outputs),
+            mor_type.clone(),
+        );
+    }
+
+    let data = ReachabilityProblemData { tokens, forbidden: HashMap::new() };
+    Ok(PnmlPetriNet { model, namespace, data })
+}
+
+/// Collects the places, transitions, and arcs on the pages of a net.
+fn collect_page_elements<'a>(elem: &'a Element, elements: &mut Vec<&'a Element>) {
+    for page in elem.find_all("page") {
+        for child in page.elements() {
+            match child.local_name() {
+                "place" | "transition" | "arc" => elements.push(child),
+                _ => {}
+            }
+        }
+        collect_page_elements(page, elements);
+    }
+}
+
+/// Parses an initial marking or arc inscription.
+fn parse_tokens(elem: &Element) -> Result<i32, PnmlError> {
+    let text = elem.find("text").map(|text| text.text_content()).unwrap_or_default();
+    match text.trim().parse() {
+        Ok(n) if n >= 0 => Ok(n),
+        _ => Err(PnmlError::InvalidTokens(text)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stdlib::{models::*, theories::th_sym_monoidal_category};
+    use crate::zero::{NameLookup, label};
+
+    #[test]
+    fn round_trip() {
+        let th = Rc::new(th_sym_monoidal_category());
+        let model = sir_petri(th.clone());
+        let data = ReachabilityProblemData {
+            tokens: [(name("S"), 2), (name("I"), 1)].into(),
+            forbidden: HashMap::new(),
+        };
+        let pnml = write_pnml(&model, &data, &Namespace::new_for_text());
+        assert!(pnml.contains(r#"<arc id="infect_I" source="infect" target="I">"#));
+
+        let imported = read_pnml(&pnml, th).unwrap();
+        let lookup = |s: &str| match imported.namespace.name_with_label(&label(s)) {
+            NameLookup::Unique(name) => name,
+            _ => panic!("Label should be unique: {s}"),
+        };
+        assert_eq!(imported.data.tokens.get(&lookup("S")), Some(&2));
+        assert_eq!(imported.data.tokens.get(&lookup("R")), None);
+
+        // The transitions have the same interfaces up to renaming.
+        let interface = |model, namespace: &Namespace, mor: QualifiedName| {
+            let (inputs, outputs) = transition_interface(model, &mor);
+            let labels = |obs: Vec<ModalOb>| -> Vec<_> {
+                obs.into_iter()
+                    .map(|ob| namespace.label_string(&ob.unwrap_generator()))
+                    .collect()
+            };
+            (labels(inputs), labels(outputs))
+        };
+        let namespace = Namespace::new_for_text();
+        for id in ["infect", "recover"] {
+            assert_eq!(
+                interface(&model, &namespace, name(id)),
+                interface(&imported.model, &imported.namespace, lookup(id))
+            );
+        }
+    }
+
+    #[test]
+    fn import_nested_pages() {
+        let pnml = r#"<?xml version="1.0" encoding="UTF-8"?>
+        <pnml xmlns="http://www.pnml.org/version-2009/grammar/pnml">
+          <net id="net" type="http://www.pnml.org/version-2009/grammar/ptnet">
+            <page id="top">
+              <place id="p1"><initialMarking><text> 3 </text></initialMarking></place>
+              <page id="nested">
+                <place id="p2"><name><text>product</text></name></place>
+                <transition id="t1"/>
+              </page>
+              <arc id="a1" source="p1" target="t1">
+                <inscription><text>2</text></inscription>
+              </arc>
+              <arc id="a2" source="t1" target="p2"/>
+            </page>
+          </net>
+        </pnml>"#;
+        let th = Rc::new(th_sym_monoidal_category());
+        let imported = read_pnml(pnml, th.clone()).unwrap();
+        let places: Vec<_> = imported.model.ob_generators().collect();
+        assert_eq!(places.len(), 2);
+        assert_eq!(imported.namespace.label_string(&places[1]), "product");
+        assert_eq!(imported.data.tokens[&places[0]], 3);
+        let t = imported.model.mor_generators().next().unwrap();
+        assert_eq!(imported.namespace.label_string(&t), "t1");
+        let (inputs, outputs) = transition_interface(&imported.model, &t);
+        assert_eq!((inputs.len(), outputs.len()), (2, 1));
+
+        let bad_arc = pnml.replace(r#"source="t1" target="p2""#, r#"source="p1" target="p2""#);
+        assert!(matches!(read_pnml(&bad_arc, th.clone()), Err(PnmlError::InvalidArc(_))));
+        let bad_weight = pnml.replace("<text>2</text>", "<text>2000000000</text>");
+        assert!(matches!(
+            read_pnml(&bad_weight, th.clone()),
+            Err(PnmlError::ArcWeightTooLarge(arc, _)) if arc == "a1"
+        ));
+        let bad_type = pnml.replace("ptnet", "hlpn");
+        assert!(matches!(read_pnml(&bad_type, th), Err(PnmlError::UnsupportedNetType(_))));
+    }
+
+    #[test]
+    fn reserved_ids() {
+        let th = Rc::new(th_sym_monoidal_category());
+        let ob_type = ModalObType::new(name("Object"));
+        let mut model = ModalDblModel::new(th.clone());
+        model.add_ob(name("net"), ob_type.clone());
+        model.add_ob(name("x"), ob_type.clone());
+        let (net, x) = (name("net").into(), name("x").into());
+        model.add_mor(name("page"), net, x, ModalMorType::Zero(ob_type));
+        let data = ReachabilityProblemData {
+            tokens: HashMap::new(),
+            forbidden: HashMap::new(),
+        };
+        let pnml = write_pnml(&model, &data, &Namespace::new_for_text());
+        assert!(pnml.contains(r#"<net id="net" "#));
+        assert!(pnml.contains(r#"<page id="page">"#));
+        assert!(pnml.contains(r#"<place id="net_2">"#));
+        assert!(pnml.contains(r#"<transition id="page_2">"#));
+
+        let imported = read_pnml(&pnml, th).unwrap();
+        let (ob, mor) =
+            (imported.model.ob_generators().next(), imported.model.mor_generators().next());
+        assert_eq!(imported.namespace.label_string(&ob.unwrap()), "net");
+        assert_eq!(imported.namespace.label_string(&mor.unwrap()), "page");
+    }
+}
@@ -0,0 +1,622 @@
+//! SBML import and export for reaction networks.
+//!
+//! The [Systems Biology Markup Language](https://sbml.org) (SBML) is the standard
+//! interchange format for models in systems biology. This module translates
+//! between SBML Level 3 documents with mass-action kinetics and Petri nets, i.e.,
+//! models of the [theory of symmetric monoidal
+//! categories](crate::stdlib::theories::th_sym_monoidal_category), together with
+//! the rate coefficients and initial values of a [mass-action
+//! analysis](PetriNetMassActionAnalysis). Species correspond to places and
+//! reactions to transitions. Only the balanced form of mass-action kinetics is
+//! supported, since that is the one that SBML tools understand.
+
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use indexmap::IndexMap;
+use thiserror::Error;
+use uuid::Uuid;
+
+use super::ids::IdGenerator;
+use super::xml::{self, Element, XmlError};
+use crate::dbl::model::{FpDblModel, ModalDblModel, ModalOb, MutDblModel};
+use crate::dbl::theory::{ModalDblTheory, Unital};
+use crate::stdlib::analyses::ode::{
+    MassActionProblemData, MassConservationType, PetriNetMassActionAnalysis,
+};
+use crate::stdlib::analyses::petri::{transition_boundary, transition_interface};
+use crate::zero::{Namespace, QualifiedName, label_seg};
+
+const SBML_NAMESPACE: &str = "http://www.sbml.org/sbml/level3/version2/core";
+const MATHML_NAMESPACE: &str = "http://www.w3.org/1998/Math/MathML";
+const MODEL_ID: &str = "model";
+const COMPARTMENT_ID: &str = "compartment";
+const RATE_ID: &str = "k";
+
+/// Largest stoichiometry or exponent accepted on import.
+///
+/// Places are repeated according to stoichiometries, so unreasonably large ones
+/// are rejected rather than exhausting memory.
+pub const MAX_SBML_STOICHIOMETRY: usize = 1000;
+
+/// Duration of simulation assigned to imported models.
+///
+/// SBML documents do not specify how long to simulate a model.
+pub const DEFAULT_SBML_DURATION: f32 = 10.0;
+
+/// A reaction network imported from SBML.
+pub struct SbmlReactionNetwork {
+    /// Petri net with a place for each species and a transition for each reaction.
+    pub model: ModalDblModel<Unital>,
+
+    /// Namespace labelling the places and transitions by their SBML names.
+    pub namespace: Namespace,
+
+    /// Rate coefficients and initial values for balanced mass-action kinetics.
+    pub data: MassActionProblemData,
+}
+
+/// An error in importing or exporting SBML.
+#[derive(Debug, Error)]
+pub enum SbmlError {
+    /// The document is not well-formed XML.
+    #[error("Invalid XML: {0}")]
+    Xml(#[from] XmlError),
+
+    /// The document is not an SBML model.
+    #[error("Document is not an SBML model")]
+    NotSbml,
+
+    /// Only balanced mass-action kinetics can be exported.
+    #[error("Only balanced mass-action kinetics can be exported to SBML")]
+    UnsupportedConservation,
+
+    /// A number in the document could not be parsed.
+    #[error("Invalid number `{0}`")]
+    InvalidNumber(String),
+
+    /// A reaction refers to a species not declared in the model.
+    #[error("Reaction `{0}` refers to unknown species `{1}`")]
+    UnknownSpecies(String, String),
+
+    /// A stoichiometry is not a nonnegative integer up to
+    /// [`MAX_SBML_STOICHIOMETRY`].
+    #[error(
+        "Reaction `{0}` has a stoichiometry that is not an integer between 0 and {max}",
+        max = MAX_SBML_STOICHIOMETRY
+    )]
+    NonIntegralStoichiometry(String),
+
+    /// A reaction is reversible.
+    #[error("Reaction `{0}` is reversible, which is not supported")]
+    Reversible(String),
+
+    /// The kinetic law of a reaction is not of mass-action form.
+    #[error("Kinetic law of reaction `{0}` is not mass action")]
+    NotMassAction(String),
+}
+
+/// Exports a Petri net with mass-action kinetics as an SBML document.
+///
+/// Species and reactions are given SBML identifiers derived from their labels in
+/// the namespace, made unique if necessary, and the labels themselves are kept as
+/// SBML names.
+pub fn write_sbml(
+    model: &ModalDblModel<Unital>,
+    data: &MassActionProblemData,
+    namespace: &Namespace,
+) -> Result<String, SbmlError> {
+    if data.mass_conservation_type != MassConservationType::Balanced {
+        return Err(SbmlError::UnsupportedConservation);
+    }
+    let analysis = PetriNetMassActionAnalysis::default();
+    let mut ids = IdGenerator::default();
+    let model_id = ids.fresh(MODEL_ID);
+    let compartment_id = ids.fresh(COMPARTMENT_ID);
+    let rate_id = ids.fresh(RATE_ID);
+
+    let mut species_ids = HashMap::new();
+    let mut species = Vec::new();
+    for ob in model.ob_generators_with_type(&analysis.place_ob_type) {
+        let label = namespace.label_string(&ob);
+        let id = ids.fresh(&label);
+        let initial = data.initial_values.get(&ob).copied().unwrap_or_default();
+        species.push(
+            Element::new("species")
+                .attr("id", &id)
+                .attr("name", &label)
+                .attr("compartment", &compartment_id)
+                .attr("initialAmount", initial)
+                .attr("hasOnlySubstanceUnits", true)
+                .attr("boundaryCondition", false)
+                .attr("constant", false),
+        );
+        species_ids.insert(ob, id);
+    }
+
+    let mut reactions = Vec::new();
+    for mor in model.mor_generators_with_type(&analysis.transition_mor_type) {
+        let label = namespace.label_string(&mor);
+        let (inputs, outputs) = transition_interface(model, &mor);
+        let stoichiometry = |obs: Vec<ModalOb>| {
+            let mut counts: IndexMap<&str, usize> = IndexMap::new();
+            for ob in obs {
+                let id = species_ids.get(&ob.unwrap_generator()).unwrap();
+                *counts.entry(id.as_str()).or_default() += 1;
+            }
+            counts
+        };
+        let (inputs, outputs) = (stoichiometry(inputs), stoichiometry(outputs));
+        let species_refs = |counts: &IndexMap<&str, usize>| -> Vec<Element> {
+            counts
+                .iter()
+                .map(|(id, n)| {
+                    Element::new("speciesReference")
+                        .attr("species", id)
+                        .attr("stoichiometry", n)
+                        .attr("constant", true)
+                })
+                .collect()
+        };
+
+        let mut factors = vec![Element::new("ci").text(&rate_id)];
+        for (id, n) in inputs.iter() {
+            let factor = Element::new("ci").text(*id);
+            factors.push(if *n == 1 {
+                factor
+            } else {
+                Element::new("apply")
+                    .child(Element::new("power"))
+                    .child(factor)
+                    .child(Element::new("cn").attr("type", "integer").text(n.to_string()))
+            });
+        }
+        let rate_law = if factors.len() == 1 {
+            factors.pop().unwrap()
+        } else {
+            Element::new("apply").child(Element::new("times")).children(factors)
+        };
+        let rate = data.transition_rates.get(&mor).copied().unwrap_or_default();
+
+        let mut reaction = Element::new("reaction")
+            .attr("id", ids.fresh(&label))
+            .attr("name", &label)
+            .attr("reversible", false);
+        if !inputs.is_empty() {
+            reaction =
+                reaction.child(Element::new("listOfReactants").children(species_refs(&inputs)));
+        }
+        if !outputs.is_empty() {
+            reaction =
+                reaction.child(Element::new("listOfProducts").children(species_refs(&outputs)));
+        }
+        reactions.push(
+            reaction.child(
+                Element::new("kineticLaw")
+                    .child(Element::new("math").attr("xmlns", MATHML_NAMESPACE).child(rate_law))
+                    .child(Element::new("listOfLocalParameters").child(
+                        Element::new("localParameter").attr("id", &rate_id).attr("value", rate),
+                    )),
+            ),
+        );
+    }
+
+    let compartment = Element::new("compartment")
+        .attr("id", &compartment_id)
+        .attr("spatialDimensions", 3)
+        .attr("size", 1)
+        .attr("constant", true);
+    let mut sbml_model = Element::new("model")
+        .attr("id", &model_id)
+        .child(Element::new("listOfCompartments").child(compartment));
+    if !species.is_empty() {
+        sbml_model = sbml_model.child(Element::new("listOfSpecies").children(species));
+    }
+    if !reactions.is_empty() {
+        sbml_model = sbml_model.child(Element::new("listOfReactions").children(reactions));
+    }
+    let sbml = Element::new("sbml")
+        .attr("xmlns", SBML_NAMESPACE)
+        .attr("level", 3)
+        .attr("version", 2)
+        .child(sbml_model);
+    Ok(sbml.to_document())
+}
+
+/// Imports an SBML document with mass-action kinetics as a Petri net.
+///
+/// Places and transitions are given fresh UUIDs as names, labelled in the
+/// returned namespace by the SBML names of the species and reactions, or by their
+/// identifiers when they have no names. Kinetic laws must be products of a rate
+/// constant and the reactant species raised to their stoichiometries, where the
+/// rate constant can be any product of numbers, parameters, and compartment sizes.
+pub fn read_sbml(
+    input: &str,
+    theory: Rc<ModalDblTheory<Unital>>,
+) -> Result<SbmlReactionNetwork, SbmlError> {
+    let root = xml::parse(input)?;
+    if root.local_name() != "sbml" {
+        return Err(SbmlError::NotSbml);
+    }
+    let sbml_model = root.find("model").ok_or(SbmlError::NotSbml)?;
+
+    // Values of symbols that may appear as factors of rate constants.
+    let mut values: HashMap<&str, f32> = HashMap::new();
+    for compartment in sbml_model.find_list("listOfCompartments", "compartment") {
+        if let Some(id) = compartment.get_attr("id") {
+            values.insert(id, parse_number(compartment.get_attr("size").unwrap_or("1"))?);
+        }
+    }
+    for param in sbml_model.find_list("listOfParameters", "parameter") {
+        if let (Some(id), Some(value)) = (param.get_attr("id"), param.get_attr("value")) {
+            values.insert(id, parse_number(value)?);
+        }
+    }
+
+    let analysis = PetriNetMassActionAnalysis::default();
+    let mut model = ModalDblModel::new(theory);
+    let mut namespace = Namespace::new_for_uuid();
+    let mut initial_values = HashMap::new();
+    let mut transition_rates = HashMap::new();
+
+    let mut fresh_name = |elem: &Element, id: &str| {
+        let uuid = Uuid::now_v7();
+        let label = elem.get_attr("name").filter(|s| !s.is_empty()).unwrap_or(id);
+        namespace.set_label(uuid, label_seg(label));
+        QualifiedName::from(uuid)
+    };
+
+    let mut species_names: HashMap<&str, QualifiedName> = HashMap::new();
+    for species in sbml_model.find_list("listOfSpecies", "species") {
+        let Some(id) = species.get_attr("id") else {
+            continue;
+        };
+        let ob = fresh_name(species, id);
+        let initial = if let Some(amount) = species.get_attr("initialAmount") {
+            Some(parse_number(amount)?)
+        } else if let Some(concentration) = species.get_attr("initialConcentration") {
+            let size = species.get_attr("compartment").and_then(|c| values.get(c));
+            Some(parse_number(concentration)? * size.copied().unwrap_or(1.0))
+        } else {
+            None
+        };
+        if let Some(initial) = initial {
+            initial_values.insert(ob.clone(), initial);
+        }
+        model.add_ob(ob.clone(), analysis.place_ob_type.clone());
+        species_names.insert(id, ob);
+    }
+
+    for reaction in sbml_model.find_list("listOfReactions", "reaction") {
+        let id = reaction.get_attr("id").unwrap_or_default();
+        if reaction.get_attr("reversible") == Some("true") {
+            return Err(SbmlError::Reversible(id.into()));
+        }
+        let stoichiometry = |list: &str| -> Result<IndexMap<&str, usize>, SbmlError> {
+            let mut counts = IndexMap::new();
+            for species_ref in reaction.find_list(list, "speciesReference") {
+                let species = species_ref.get_attr("species").unwrap_or_default();
+                if !species_names.contains_key(species) {
+                    return Err(SbmlError::UnknownSpecies(id.into(), species.into()));
+                }
+                let n = parse_number(species_ref.get_attr("stoichiometry").unwrap_or("1"))?;
+                if !(0.0..=MAX_SBML_STOICHIOMETRY as f32).contains(&n) || n.fract() != 0.0 {
+                    return Err(SbmlError::NonIntegralStoichiometry(id.into()));
+                }
+                *counts.entry(species).or_default() += n as usize;
+            }
+            Ok(counts)
+        };
+        let (inputs, outputs) =
+            (stoichiometry("listOfReactants")?, stoichiometry("listOfProducts")?);
+
+        let rate = if let Some(law) = reaction.find("kineticLaw") {
+            let mut local_values = values.clone();
+            for param in law
+                .find_list("listOfLocalParameters", "localParameter")
+                .chain(law.find_list("listOfParameters", "parameter"))
+            {
+                if let (Some(id), Some(value)) = (param.get_attr("id"), param.get_attr("value")) {
+                    local_values.insert(id, parse_number(value)?);
+                }
+            }
+            let math = law.find("math").and_then(|math| math.elements().next());
+            let not_mass_action = || SbmlError::NotMassAction(id.into());
+            let mut factors = MassActionFactors::new();
+            factors.collect(
+                id,
+                math.ok_or_else(not_mass_action)?,
+                &local_values,
+                &species_names,
+            )?;
+            if factors.species != inputs.iter().map(|(s, n)| (*s, *n)).collect() {
+                return Err(not_mass_action());
+            }
+            Some(factors.coefficient)
+        } else {
+            None
+        };
+
+        let tensor =
+            |counts: IndexMap<&str, usize>| {
+                transition_boundary(counts.into_iter().flat_map(|(species, n)| {
+                    std::iter::repeat_n(species_names[species].clone(), n)
+                }))
+            };
+        let mor = fresh_name(reaction, id);
+        if let Some(rate) = rate {
+            transition_rates.insert(mor.clone(), rate);
+        }
+        model.add_mor(mor, tensor(inputs), tensor(outputs), analysis.transition_mor_type.clone());
+    }
+
+    let data = MassActionProblemData {
+        mass_conservation_type: MassConservationType::Balanced,
+        transition_rates,
+        transition_consumption_rates: HashMap::new(),
+        transition_production_rates: HashMap::new(),
+        place_consumption_rates: HashMap::new(),
+        place_production_rates: HashMap::new(),
+        initial_values,
+        duration: DEFAULT_SBML_DURATION,
+    };
+    Ok(SbmlReactionNetwork { model, namespace, data })
+}
+
+fn parse_number(s: &str) -> Result<f32, SbmlError> {
+    s.trim().parse().map_err(|_| SbmlError::InvalidNumber(s.into()))
+}
+
+/// Factors of a kinetic law in mass-action form.
+struct MassActionFactors<'a> {
+    coefficient: f32,
+    species: HashMap<&'a str, usize>,
+}
+
+impl<'a> MassActionFactors<'a> {
+    /// Creates the factors of an empty product.
+    fn new() -> Self {
+        Self {
+            coefficient: 1.0,
+            species: HashMap::new(),
+        }
+    }
+
+    /// Collects the factors of a MathML expression in the kinetic law of a
+    /// reaction, failing if it is not a product of numbers, known symbols, and
+    /// powers of species.
+    ///
+    /// Symbols are resolved as parameters or compartments before species, so
+    /// that local parameters shadow species as in SBML's scoping rules. Global
+    /// identifiers are unique, so otherwise the order does not matter.
+    fn collect(
+        &mut self,
+        reaction: &str,
+        expr: &'a Element,
+        values: &HashMap<&str, f32>,
+        species: &HashMap<&'a str, QualifiedName>,
+    ) -> Result<(), SbmlError> {
+        let not_mass_action = || SbmlError::NotMassAction(reaction.into());
+        match expr.local_name() {
+            "cn" => self.coefficient *= parse_number(&expr.text_content())?,
+            "ci" => {
+                let text = expr.text_content();
+                let symbol = text.trim();
+                if let Some(value) = values.get(symbol) {
+                    self.coefficient *= value;
+                } else {
+                    let (key, _) = species.get_key_value(symbol).ok_or_else(not_mass_action)?;
+                    *self.species.entry(key).or_default() += 1;
+                }
+            }
+            "apply" => {
+                let mut args = expr.elements();
+                let op = args.next().ok_or_else(not_mass_action)?;
+                match op.local_name() {
+                    "times" => {
+                        for arg in args {
+                            self.collect(reaction, arg, values, species)?;
+                        }
+                    }
+                    "power" => {
+                        let (Some(base), Some(exp), None) = (args.next(), args.next(), args.next())
+                        else {
+                            return Err(not_mass_action());
+                        };
+                        let n = parse_number(&exp.text_content())?;
+                        if exp.local_name() != "cn"
+                            || !(0.0..=MAX_SBML_STOICHIOMETRY as f32).contains(&n)
+                            || n.fract() != 0.0
+                        {
+                            return Err(not_mass_action());
+                        }
+                        for _ in 0..(n as usize) {
+                            self.collect(reaction, base, values, species)?;
+                        }
+                    }
+                    _ => return Err(not_mass_action()),
+                }
+            }
+            _ => return Err(not_mass_action()),
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dbl::theory::{ModalMorType, ModalObType};
+    use crate::one::FgCategory;
+    use crate::stdlib::analyses::ode::FlowParameter;
+    use crate::stdlib::{models::sir_petri, theories::th_sym_monoidal_category};
+    use crate::zero::{NameLookup, label, name};
+
+    fn sir_data() -> MassActionProblemData {
+        MassActionProblemData {
+            mass_conservation_type: MassConservationType::Balanced,
+            transition_rates: [(name("infect"), 0.5), (name("recover"), 0.25)].into(),
+            transition_consumption_rates: HashMap::new(),
+            transition_production_rates: HashMap::new(),
+            place_consumption_rates: HashMap::new(),
+            place_production_rates: HashMap::new(),
+            initial_values: [(name("S"), 99.0), (name("I"), 1.0)].into(),
+            duration: 10.0,
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        let th = Rc::new(th_sym_monoidal_category());
+        let model = sir_petri(th.clone());
+        let sbml = write_sbml(&model, &sir_data(), &Namespace::new_for_text()).unwrap();
+        assert!(
+            sbml.contains(r#"<speciesReference species="I" stoichiometry="2" constant="true"/>"#)
+        );
+
+        let imported = read_sbml(&sbml, th).unwrap();
+        let lookup = |s: &str| match imported.namespace.name_with_label(&label(s)) {
+            NameLookup::Unique(name) => name,
+            _ => panic!("Label should be unique: {s}"),
+        };
+        assert_eq!(imported.data.initial_values[&lookup("S")], 99.0);
+        assert_eq!(imported.data.transition_rates[&lookup("infect")], 0.5);
+
+        // The mass-action systems agree up to renaming.
+        let system = |model, namespace: &Namespace| {
+            PetriNetMassActionAnalysis::default()
+                .build_system(model, MassConservationType::Balanced)
+                .map_variables(|id| namespace.label_string(id))
+                .extend_scalars(|param| {
+                    param.map_variables(|flow| match flow {
+                        FlowParameter::Balanced { transition } => {
+                            namespace.label_string(transition)
+                        }
+                        _ => panic!("Flow should be balanced"),
+                    })
+                })
+                .to_string()
+        };
+        assert_eq!(
+            system(&model, &Namespace::new_for_text()),
+            system(&imported.model, &imported.namespace)
+        );
+    }
+
+    #[test]
+    fn round_trip_zero_rate() {
+        let th = Rc::new(th_sym_monoidal_category());
+        let model = sir_petri(th.clone());
+        let mut data = sir_data();
+        data.transition_rates.insert(name("recover"), 0.0);
+        let sbml = write_sbml(&model, &data, &Namespace::new_for_text()).unwrap();
+
+        let imported = read_sbml(&sbml, th).unwrap();
+        let rates: Vec<_> = imported
+            .model
+            .mor_generators()
+            .map(|mor| {
+                (imported.namespace.label_string(&mor), imported.data.transition_rates[&mor])
+            })
+            .collect();
+        assert_eq!(rates, vec![("infect".into(), 0.5), ("recover".into(), 0.0)]);
+    }
+
+    #[test]
+    fn import_kinetic_laws() {
+        let sbml = r#"<?xml version="1.0" encoding="UTF-8"?>
+        <sbml xmlns="http://www.sbml.org/sbml/level3/version1/core" level="3" version="1">
+          <model id="dimerization">
+            <listOfCompartments>
+              <compartment id="cell" size="2" constant="true"/>
+            </listOfCompartments>
+            <listOfSpecies>
+              <species id="A" compartment="cell" initialConcentration="3"/>
+              <species id="A2" name="dimer" compartment="cell" initialAmount="0"/>
+            </listOfSpecies>
+            <listOfParameters>
+              <parameter id="kf" value="0.5"/>
+            </listOfParameters>
+            <listOfReactions>
+              <reaction id="bind" reversible="false">
+                <listOfReactants>
+                  <speciesReference species="A" stoichiometry="2"/>
+                </listOfReactants>
+                <listOfProducts>
+                  <speciesReference species="A2"/>
+                </listOfProducts>
+                <kineticLaw>
+                  <math xmlns="http://www.w3.org/1998/Math/MathML">
+                    <apply><times/><ci>cell</ci><ci> kf </ci><ci>A</ci><ci>A</ci></apply>
+                  </math>
+                </kineticLaw>
+              </reaction>
+              <reaction id="decay" reversible="false">
+                <listOfReactants>
+                  <speciesReference species="A2"/>
+                </listOfReactants>
+                <kineticLaw>
+                  <math xmlns="http://www.w3.org/1998/Math/MathML">
+                    <apply><times/><ci>A2</ci><ci>A2</ci></apply>
+                  </math>
+                </kineticLaw>
+              </reaction>
+            </listOfReactions>
+          </model>
+        </sbml>"#;
+        let th = Rc::new(th_sym_monoidal_category());
+        let result = read_sbml(sbml, th);
+        assert!(matches!(result, Err(SbmlError::NotMassAction(id)) if id == "decay"));
+
+        let nonlinear = sbml.replace("<times/><ci>A2</ci>", "<plus/><ci>A2</ci>");
+        let result = read_sbml(&nonlinear, Rc::new(th_sym_monoidal_category()));
+        assert!(matches!(result, Err(SbmlError::NotMassAction(id)) if id == "decay"));
+
+        let sbml = sbml.replace("<ci>A2</ci><ci>A2</ci>", "<cn>0.1</cn><ci>A2</ci>");
+        let imported = read_sbml(&sbml, Rc::new(th_sym_monoidal_category())).unwrap();
+        let names: Vec<_> = imported.model.ob_generators().collect();
+        assert_eq!(imported.namespace.label_string(&names[1]), "dimer");
+        assert_eq!(imported.data.initial_values[&names[0]], 6.0);
+        let mors: Vec<_> = imported.model.mor_generators().collect();
+        assert_eq!(imported.data.transition_rates[&mors[0]], 1.0);
+        assert!((imported.data.transition_rates[&mors[1]] - 0.1).abs() < 1e-6);
+        let (inputs, outputs) = transition_interface(&imported.model, &mors[0]);
+        assert_eq!((inputs.len(), outputs.len()), (2, 1));
+
+        let huge = sbml.replace(r#"stoichiometry="2""#, r#"stoichiometry="1e12""#);
+        let result = read_sbml(&huge, Rc::new(th_sym_monoidal_category()));
+        assert!(matches!(result, Err(SbmlError::NonIntegralStoichiometry(id)) if id == "bind"));
+
+        let power = "<apply><power/><ci>A2</ci><cn>1e12</cn></apply>";
+        let huge = sbml.replace("<ci>A2</ci></apply>", &format!("{power}</apply>"));
+        let result = read_sbml(&huge, Rc::new(th_sym_monoidal_category()));
+        assert!(matches!(result, Err(SbmlError::NotMassAction(id)) if id == "decay"));
+    }
+
+    #[test]
+    fn round_trip_reserved_ids() {
+        let th = Rc::new(th_sym_monoidal_category());
+        let ob_type = ModalObType::new(name("Object"));
+        let mut model = ModalDblModel::new(th.clone());
+        model.add_ob(name("k"), ob_type.clone());
+        model.add_ob(name("compartment"), ob_type.clone());
+        let (k, compartment) = (name("k").into(), name("compartment").into());
+        model.add_mor(name("model"), k, compartment, ModalMorType::Zero(ob_type));
+        let data = MassActionProblemData {
+            transition_rates: [(name("model"), 0.5)].into(),
+            initial_values: [(name("k"), 2.0)].into(),
+            ..sir_data()
+        };
+        let sbml = write_sbml(&model, &data, &Namespace::new_for_text()).unwrap();
+        assert!(sbml.contains(r#"<species id="k_2" name="k""#));
+        assert!(sbml.contains(r#"<species id="compartment_2" name="compartment""#));
+        assert!(sbml.contains(r#"<reaction id="model_2" name="model""#));
+
+        let imported = read_sbml(&sbml, th).unwrap();
+        let mors: Vec<_> = imported.model.mor_generators().collect();
+        assert_eq!(imported.data.transition_rates[&mors[0]], 0.5);
+        let obs: Vec<_> = imported.model.ob_generators().collect();
+        assert_eq!(imported.namespace.label_string(&obs[0]), "k");
+        assert_eq!(imported.data.initial_values[&obs[0]], 2.0);
+    }
+}
@@ -0,0 +1,133 @@
+//! Tabular data and its export as CSV.
+//!
+//! A [`DataFrame`] is a minimal columnar table, used to export numerical results
+//! such as simulations. Besides CSV, data frames can be written in the
+//! [Arrow](super::arrow) IPC format.
+
+use std::fmt::Write;
+
+/// A column of a data frame.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Column {
+    /// A column of 32-bit integers.
+    Int(Vec<i32>),
+
+    /// A column of 32-bit floating point numbers.
+    Float(Vec<f32>),
+
+    /// A column of strings.
+    Text(Vec<String>),
+}
+
+impl Column {
+    /// Number of entries in the column.
+    pub fn len(&self) -> usize {
+        match self {
+            Column::Int(values) => values.len(),
+            Column::Float(values) => values.len(),
+            Column::Text(values) => values.len(),
+        }
+    }
+
+    /// Is the column empty?
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Formats an entry of the column as a string.
+    fn format(&self, i: usize) -> String {
+        match self {
+            Column::Int(values) => values[i].to_string(),
+            Column::Float(values) => values[i].to_string(),
+            Column::Text(values) => values[i].clone(),
+        }
+    }
+}
+
+/// A table of data with named columns of equal length.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DataFrame {
+    columns: Vec<(String, Column)>,
+}
+
+impl DataFrame {
+    /// Constructs an empty data frame, with no columns.
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Adds a column to the data frame.
+    ///
+    /// Panics if the column has a different length than the existing columns or
+    /// if there is already a column with the same name.
+    pub fn add_column(&mut self, name: impl Into<String>, column: Column) {
+        let name = name.into();
+        if let Some((_, first)) = self.columns.first() {
+            assert_eq!(first.len(), column.len(), "Columns should have equal length");
+        }
+        assert!(self.column(&name).is_none(), "Column names should be distinct: {name}");
+        self.columns.push((name, column));
+    }
+
+    /// Iterates over the named columns of the data frame.
+    pub fn columns(&self) -> impl ExactSizeIterator<Item = (&str, &Column)> {
+        self.columns.iter().map(|(name, column)| (name.as_str(), column))
+    }
+
+    /// Gets a column by name.
+    pub fn column(&self, name: &str) -> Option<&Column> {
+        self.columns.iter().find(|(other, _)| other == name).map(|(_, column)| column)
+    }
+
+    /// Number of rows in the data frame.
+    pub fn nrows(&self) -> usize {
+        self.columns.first().map_or(0, |(_, column)| column.len())
+    }
+
+    /// Writes the data frame as CSV, with a header row.
+    ///
+    /// Fields are quoted as needed following RFC 4180.
+    pub fn to_csv(&self) -> String {
+        let mut out = String::new();
+        let header: Vec<_> = self.columns.iter().map(|(name, _)| csv_field(name)).collect();
+        writeln!(out, "{}", header.join(",")).unwrap();
+        for i in 0..self.nrows() {
+            let row: Vec<_> =
+                self.columns.iter().map(|(_, column)| csv_field(&column.format(i))).collect();
+            writeln!(out, "{}", row.join(",")).unwrap();
+        }
+        out
+    }
+}
+
+/// Quotes a field of a CSV file if needed.
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn csv() {
+        let mut df = DataFrame::new();
+        df.add_column("n", Column::Int(vec![1, 2]));
+        df.add_column("x", Column::Float(vec![0.5, -1.0]));
+        df.add_column("name, quoted", Column::Text(vec!["a".into(), "say \"b\"".into()]));
+        assert_eq!(df.nrows(), 2);
+        assert_eq!(df.to_csv(), "n,x,\"name, quoted\"\n1,0.5,a\n2,-1,\"say \"\"b\"\"\"\n");
+    }
+
+    #[test]
+    #[should_panic(expected = "Column names should be distinct")]
+    fn repeated_column() {
+        let mut df = DataFrame::new();
+        df.add_column("x", Column::Int(vec![1]));
+        df.add_column("x", Column::Int(vec![2]));
+    }
+}
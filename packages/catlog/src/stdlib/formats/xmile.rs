@@ -0,0 +1,665 @@
+//! XMILE import and export for stock-flow diagrams.
+//!
+//! [XMILE](https://docs.oasis-open.org/xmile/xmile/v1.0/xmile-v1.0.html) is the
+//! OASIS standard interchange format for system dynamics models, supported by
+//! tools such as Stella and Vensim. This module translates between XMILE models
+//! and stock-flow diagrams, i.e., models of the [theory of categories with
+//! links](crate::stdlib::theories::th_category_links) or [signed
+//! links](crate::stdlib::theories::th_category_signed_links), together with the
+//! rate coefficients and initial values of a [mass-action
+//! analysis](StockFlowMassActionAnalysis).
+//!
+//! Stocks correspond to stocks and flows to flows. Under mass-action kinetics,
+//! the rate of a flow is a coefficient times the stock it drains, times each
+//! stock linked to the flow, divided by each stock negatively linked to it. In
+//! XMILE, the coefficient becomes an auxiliary variable and the rate becomes the
+//! equation of the flow. Only the balanced form of mass-action kinetics is
+//! supported, since XMILE flows have a single rate.
+//!
+//! Stock-flow diagrams have no clouds: every flow drains one stock and fills
+//! another. XMILE models with flows from or to clouds, i.e., flows missing from
+//! the outflows or inflows of every stock, cannot be imported until each cloud
+//! is replaced by an explicit stock.
+
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use thiserror::Error;
+use uuid::Uuid;
+
+use super::ids::IdGenerator;
+use super::xml::{self, Element, XmlError};
+use crate::dbl::model::{DiscreteTabModel, FpDblModel, MutDblModel, TabEdge};
+use crate::dbl::theory::{DblTheory, DiscreteTabTheory};
+use crate::one::FgCategory;
+use crate::stdlib::analyses::ode::{
+    MassActionProblemData, MassConservationType, StockFlowMassActionAnalysis,
+};
+use crate::zero::{Namespace, QualifiedName, label_seg};
+
+const XMILE_NAMESPACE: &str = "http://docs.oasis-open.org/xmile/ns/XMILE/v1.0";
+
+/// Duration of simulation assigned to imported models without simulation specs.
+pub const DEFAULT_XMILE_DURATION: f32 = 10.0;
+
+/// A stock-flow diagram imported from XMILE.
+pub struct XmileStockFlow {
+    /// Stock-flow diagram with a stock for each XMILE stock and a flow for each
+    /// XMILE flow.
+    pub model: DiscreteTabModel,
+
+    /// Namespace labelling the stocks and flows by their XMILE names.
+    pub namespace: Namespace,
+
+    /// Rate coefficients and initial values for balanced mass-action kinetics.
+    pub data: MassActionProblemData,
+}
+
+/// An error in importing or exporting XMILE.
+#[derive(Debug, Error)]
+pub enum XmileError {
+    /// The document is not well-formed XML.
+    #[error("Invalid XML: {0}")]
+    Xml(#[from] XmlError),
+
+    /// The document is not an XMILE model.
+    #[error("Document is not an XMILE model")]
+    NotXmile,
+
+    /// Only balanced mass-action kinetics can be exported.
+    #[error("Only balanced mass-action kinetics can be exported to XMILE")]
+    UnsupportedConservation,
+
+    /// A number in the document could not be parsed.
+    #[error("Invalid number `{0}`")]
+    InvalidNumber(String),
+
+    /// A flow starts or ends at a cloud, which stock-flow diagrams cannot
+    /// represent.
+    #[error(
+        "Flow `{0}` starts or ends at a cloud, which is not supported: \
+         replace the cloud with a stock"
+    )]
+    UnsupportedFlow(String),
+
+    /// A link of a type not supported by the theory, such as a negative link.
+    #[error("Flow `{0}` depends on stock `{1}` in a way not supported by the theory")]
+    UnsupportedLink(String, String),
+}
+
+/// Exports a stock-flow diagram with mass-action kinetics as an XMILE document.
+///
+/// Stocks, flows, and rate coefficients are given XMILE names derived from their
+/// labels in the namespace, made unique if necessary. Since XMILE names are
+/// compared [canonically](canonical_name), ignoring case among other things,
+/// labels such as `S` and `s` get distinct names.
+pub fn write_xmile(
+    model: &DiscreteTabModel,
+    data: &MassActionProblemData,
+    namespace: &Namespace,
+) -> Result<String, XmileError> {
+    if data.mass_conservation_type != MassConservationType::Balanced {
+        return Err(XmileError::UnsupportedConservation);
+    }
+    let analysis = StockFlowMassActionAnalysis::default();
+    let mut ids = IdGenerator::with_canonical(canonical_name);
+
+    let stocks: Vec<_> = model.ob_generators_with_type(&analysis.stock_ob_type).collect();
+    let flows: Vec<_> = model.mor_generators_with_type(&analysis.flow_mor_type).collect();
+    let stock_ids: HashMap<_, _> = stocks
+        .iter()
+        .map(|ob| (ob.clone(), ids.fresh(&namespace.label_string(ob))))
+        .collect();
+    let flow_ids: HashMap<_, _> = flows
+        .iter()
+        .map(|mor| (mor.clone(), ids.fresh(&namespace.label_string(mor))))
+        .collect();
+
+    // Factors of the rate of each flow contributed by links.
+    let mut link_factors: HashMap<QualifiedName, Vec<(&str, bool)>> = HashMap::new();
+    let links = model
+        .mor_generators_with_type(&analysis.pos_link_mor_type)
+        .map(|link| (link, true))
+        .chain(model.mor_generators_with_type(&analysis.neg_link_mor_type).map(|l| (l, false)));
+    for (link, positive) in links {
+        let stock = model.mor_generator_dom(&link).unwrap_basic();
+        let path = model.mor_generator_cod(&link).unwrap_tabulated();
+        if let (Some(TabEdge::Basic(flow)), Some(id)) = (path.only(), stock_ids.get(&stock)) {
+            link_factors.entry(flow).or_default().push((id.as_str(), positive));
+        }
+    }
+
+    let mut variables = Vec::new();
+    for ob in stocks.iter() {
+        let initial = data.initial_values.get(ob).copied().unwrap_or_default();
+        let mut stock = Element::new("stock")
+            .attr("name", &stock_ids[ob])
+            .child(Element::new("eqn").text(initial.to_string()));
+        for mor in flows.iter() {
+            if model.mor_generator_cod(mor).basic().as_ref() == Some(ob) {
+                stock = stock.child(Element::new("inflow").text(&flow_ids[mor]));
+            }
+        }
+        for mor in flows.iter() {
+            if model.mor_generator_dom(mor).basic().as_ref() == Some(ob) {
+                stock = stock.child(Element::new("outflow").text(&flow_ids[mor]));
+            }
+        }
+        variables.push(stock);
+    }
+
+    let mut auxiliaries = Vec::new();
+    for mor in flows.iter() {
+        let flow_id = &flow_ids[mor];
+        let rate_id = ids.fresh(&format!("{flow_id}_rate"));
+        let rate = data.transition_rates.get(mor).copied().unwrap_or_default();
+        auxiliaries.push(
+            Element::new("aux")
+                .attr("name", &rate_id)
+                .child(Element::new("eqn").text(rate.to_string())),
+        );
+
+        let source = model.mor_generator_dom(mor).unwrap_basic();
+        let mut eqn = format!("{rate_id} * {}", stock_ids[&source]);
+        for (stock_id, positive) in link_factors.get(mor).into_iter().flatten() {
+            eqn.push_str(if *positive { " * " } else { " / " });
+            eqn.push_str(stock_id);
+        }
+        variables
+            .push(Element::new("flow").attr("name", flow_id).child(Element::new("eqn").text(eqn)));
+    }
+    variables.extend(auxiliaries);
+
+    let dt = data.duration / 100.0;
+    let xmile = Element::new("xmile")
+        .attr("version", "1.0")
+        .attr("xmlns", XMILE_NAMESPACE)
+        .child(
+            Element::new("header")
+                .child(Element::new("vendor").text("Topos Institute"))
+                .child(Element::new("product").text("CatColab")),
+        )
+        .child(
+            Element::new("sim_specs")
+                .attr("method", "Euler")
+                .child(Element::new("start").text("0"))
+                .child(Element::new("stop").text(data.duration.to_string()))
+                .child(Element::new("dt").text(dt.to_string())),
+        )
+        .child(Element::new("model").child(Element::new("variables").children(variables)));
+    Ok(xmile.to_document())
+}
+
+/// Imports an XMILE model as a stock-flow diagram.
+///
+/// Stocks and flows are given fresh UUIDs as names, labelled in the returned
+/// namespace by their XMILE names. Every flow must go from one stock to another:
+/// a flow from or to a cloud is rejected with [`XmileError::UnsupportedFlow`].
+/// A flow is linked to each stock that its equation refers to, or that is
+/// connected to it in a view, other than the stock that it drains, which the
+/// rate of a mass-action flow depends on implicitly.
+///
+/// When the equation of a flow is a product of the drained stock, linked stocks,
+/// and constants, possibly dividing by stocks, its rate coefficient is recovered.
+/// Dividing by a stock yields a negative link, which requires the theory to have
+/// them. Initial values of stocks are recovered when they are constants.
+pub fn read_xmile(
+    input: &str,
+    theory: Rc<DiscreteTabTheory>,
+) -> Result<XmileStockFlow, XmileError> {
+    let root = xml::parse(input)?;
+    if root.local_name() != "xmile" {
+        return Err(XmileError::NotXmile);
+    }
+    let xmile_model = root.find("model").ok_or(XmileError::NotXmile)?;
+    let variables = xmile_model.find("variables").ok_or(XmileError::NotXmile)?;
+    let equation = |elem: &Element| elem.find("eqn").map(|eqn| eqn.text_content());
+
+    // Values of auxiliaries that are constants.
+    let mut constants = HashMap::new();
+    for aux in variables.find_all("aux") {
+        if let (Some(name), Some(value)) = (aux.get_attr("name"), equation(aux))
+            && let Ok(value) = value.trim().parse::<f32>()
+        {
+            constants.insert(canonical_name(name), value);
+        }
+    }
+
+    let analysis = StockFlowMassActionAnalysis::default();
+    let mut model = DiscreteTabModel::new(theory.clone());
+    let mut namespace = Namespace::new_for_uuid();
+    let mut initial_values = HashMap::new();
+    let mut transition_rates = HashMap::new();
+    let mut fresh_name = |label: &str| {
+        let uuid = Uuid::now_v7();
+        namespace.set_label(uuid, label_seg(label));
+        QualifiedName::from(uuid)
+    };
+
+    let mut stocks: HashMap<String, QualifiedName> = HashMap::new();
+    let mut sources: HashMap<String, QualifiedName> = HashMap::new();
+    let mut targets: HashMap<String, QualifiedName> = HashMap::new();
+    for stock in variables.find_all("stock") {
+        let Some(label) = stock.get_attr("name") else {
+            continue;
+        };
+        let ob = fresh_name(label);
+        if let Some(value) = equation(stock).and_then(|eqn| eqn.trim().parse::<f32>().ok()) {
+            initial_values.insert(ob.clone(), value);
+        }
+        for outflow in stock.find_all("outflow") {
+            sources.insert(canonical_name(&outflow.text_content()), ob.clone());
+        }
+        for inflow in stock.find_all("inflow") {
+            targets.insert(canonical_name(&inflow.text_content()), ob.clone());
+        }
+        model.add_ob(ob.clone(), analysis.stock_ob_type.clone());
+        stocks.insert(canonical_name(label), ob);
+    }
+
+    // Connectors from stocks to flows in the views.
+    let connectors: Vec<(String, String)> = xmile_model
+        .find_all("views")
+        .flat_map(|views| views.find_all("view"))
+        .flat_map(|view| view.find_all("connector"))
+        .filter_map(|connector| {
+            let from = connector.find("from")?.text_content();
+            let to = connector.find("to")?.text_content();
+            Some((canonical_name(&from), canonical_name(&to)))
+        })
+        .collect();
+
+    for flow in variables.find_all("flow") {
+        let Some(label) = flow.get_attr("name") else {
+            continue;
+        };
+        let key = canonical_name(label);
+        let (Some(source), Some(target)) = (sources.get(&key), targets.get(&key)) else {
+            return Err(XmileError::UnsupportedFlow(label.into()));
+        };
+        let mor = fresh_name(label);
+        model.add_mor(
+            mor.clone(),
+            source.clone().into(),
+            target.clone().into(),
+            analysis.flow_mor_type.clone(),
+        );
+
+        // Links from the flow's equation, as exponents of stocks.
+        let eqn = equation(flow).unwrap_or_default();
+        let mut exponents: Vec<(String, i32)> = Vec::new();
+        let mut coefficient = Some(1.0);
+        if let Some(factors) = parse_product(&eqn) {
+            for (factor, exponent) in factors {
+                match factor {
+                    Factor::Number(x) => {
+                        coefficient = coefficient.map(|c| c * x.powi(exponent));
+                    }
+                    Factor::Name(name) if stocks.contains_key(&name) => {
+                        add_exponent(&mut exponents, name, exponent);
+                    }
+                    Factor::Name(name) => {
+                        let value = constants.get(&name);
+                        coefficient = coefficient.zip(value).map(|(c, x)| c * x.powi(exponent));
+                    }
+                }
+            }
+            let source_key = stocks.iter().find(|(_, ob)| *ob == source).map(|(k, _)| k.clone());
+            match source_key.and_then(|k| exponents.iter_mut().find(|(name, _)| *name == k)) {
+                Some((_, n)) => *n -= 1,
+                None => coefficient = None,
+            }
+        } else {
+            coefficient = None;
+            for name in referenced_names(&eqn) {
+                if stocks.contains_key(&name) && stocks[&name] != *source {
+                    add_exponent(&mut exponents, name, 1);
+                }
+            }
+        }
+        for (from, _) in connectors.iter().filter(|(_, to)| *to == key) {
+            if stocks.get(from).is_some_and(|ob| ob != source)
+                && !exponents.iter().any(|(name, _)| name == from)
+            {
+                add_exponent(&mut exponents, from.clone(), 1);
+            }
+        }
+        if let Some(coefficient) = coefficient {
+            transition_rates.insert(mor.clone(), coefficient);
+        }
+
+        for (name, exponent) in exponents {
+            let link_type = if exponent > 0 {
+                analysis.pos_link_mor_type.clone()
+            } else {
+                analysis.neg_link_mor_type.clone()
+            };
+            if exponent != 0 && !theory.has_mor_type(&link_type) {
+                return Err(XmileError::UnsupportedLink(label.into(), name));
+            }
+            for _ in 0..exponent.abs() {
+                let link = QualifiedName::from(Uuid::now_v7());
+                let dom = stocks[&name].clone().into();
+                let cod = model.tabulated_gen(mor.clone());
+                model.add_mor(link, dom, cod, link_type.clone());
+            }
+        }
+    }
+
+    let sim_specs = root.find("sim_specs");
+    let spec = |name: &str| -> Result<Option<f32>, XmileError> {
+        let Some(elem) = sim_specs.and_then(|specs| specs.find(name)) else {
+            return Ok(None);
+        };
+        let text = elem.text_content();
+        text.trim().parse().map(Some).map_err(|_| XmileError::InvalidNumber(text))
+    };
+    let duration = match (spec("start")?, spec("stop")?) {
+        (start, Some(stop)) => stop - start.unwrap_or_default(),
+        _ => DEFAULT_XMILE_DURATION,
+    };
+
+    let data = MassActionProblemData {
+        mass_conservation_type: MassConservationType::Balanced,
+        transition_rates,
+        transition_consumption_rates: HashMap::new(),
+        transition_production_rates: HashMap::new(),
+        place_consumption_rates: HashMap::new(),
+        place_production_rates: HashMap::new(),
+        initial_values,
+        duration,
+    };
+    Ok(XmileStockFlow { model, namespace, data })
+}
+
+/// Adds to the exponent of a variable in a list of variable-exponent pairs.
+fn add_exponent(exponents: &mut Vec<(String, i32)>, name: String, exponent: i32) {
+    match exponents.iter_mut().find(|(other, _)| *other == name) {
+        Some((_, n)) => *n += exponent,
+        None => exponents.push((name, exponent)),
+    }
+}
+
+/// Canonical form of an XMILE name, for comparison.
+///
+/// XMILE names are case insensitive and do not distinguish between spaces and
+/// underscores. They may also be quoted in equations.
+fn canonical_name(name: &str) -> String {
+    let name = name.trim().trim_matches('"');
+    let words: Vec<_> = name.split(|c: char| c.is_whitespace() || c == '_').collect();
+    words
+        .into_iter()
+        .filter(|w| !w.is_empty())
+        .collect::<Vec<_>>()
+        .join("_")
+        .to_lowercase()
+}
+
+/// A factor in a product of numbers and variables.
+enum Factor {
+    Number(f32),
+    Name(String),
+}
+
+/// A token in an XMILE equation.
+#[derive(PartialEq)]
+enum Token {
+    Number(f32),
+    Name(String),
+    Op(char),
+}
+
+/// Splits an XMILE equation into tokens.
+fn tokenize(eqn: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut chars = eqn.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c == '"' {
+            chars.next();
+            let name: String = chars.by_ref().take_while(|c| *c != '"').collect();
+            tokens.push(Token::Name(canonical_name(&name)));
+        } else if c.is_ascii_digit() || c == '.' {
+            let mut number = String::new();
+            while let Some(&c) = chars.peek() {
+                let exponent = number.ends_with(['e', 'E']) && (c == '-' || c == '+');
+                if c.is_ascii_digit() || c == '.' || c == 'e' || c == 'E' || exponent {
+                    number.push(c);
+                    chars.next();
+                } else {
+                    break;
+                }
+            }
+            tokens.push(number.parse().map_or(Token::Op('?'), Token::Number));
+        } else if c.is_alphanumeric() || c == '_' || c == '$' {
+            let mut name = String::new();
+            while let Some(&c) = chars.peek() {
+                if c.is_alphanumeric() || c == '_' || c == '$' {
+                    name.push(c);
+                    chars.next();
+                } else {
+                    break;
+                }
+            }
+            tokens.push(Token::Name(canonical_name(&name)));
+        } else {
+            tokens.push(Token::Op(c));
+            chars.next();
+        }
+    }
+    tokens
+}
+
+/// Parses an equation that is a product and quotient of numbers and variables.
+///
+/// Returns the factors with their exponents, which are 1 or -1, or nothing if the
+/// equation is not of this form.
+fn parse_product(eqn: &str) -> Option<Vec<(Factor, i32)>> {
+    let mut factors = Vec::new();
+    let mut exponent = 1;
+    let mut expect_factor = true;
+    for token in tokenize(eqn) {
+        match (token, expect_factor) {
+            (Token::Number(x), true) => factors.push((Factor::Number(x), exponent)),
+            (Token::Name(name), true) => factors.push((Factor::Name(name), exponent)),
+            (Token::Op('*'), false) => exponent = 1,
+            (Token::Op('/'), false) => exponent = -1,
+            _ => return None,
+        }
+        expect_factor = !expect_factor;
+    }
+    (!expect_factor).then_some(factors)
+}
+
+/// Names referred to by an equation, excluding function calls.
+fn referenced_names(eqn: &str) -> Vec<String> {
+    let tokens = tokenize(eqn);
+    let mut names = Vec::new();
+    for (i, token) in tokens.iter().enumerate() {
+        if let Token::Name(name) = token
+            && tokens.get(i + 1) != Some(&Token::Op('('))
+        {
+            names.push(name.clone());
+        }
+    }
+    names
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dbl::theory::{TabMorType, TabObType};
+    use crate::stdlib::analyses::ode::FlowParameter;
+    use crate::stdlib::theories::{th_category_links, th_category_signed_links};
+    use crate::zero::{NameLookup, label, name};
+
+    /// An SIR model with a negative link slowing recovery.
+    fn sir_stock_flow(th: Rc<DiscreteTabTheory>) -> DiscreteTabModel {
+        let mut model = DiscreteTabModel::new(th.clone());
+        let ob_type = TabObType::Basic(name("Object"));
+        for x in ["S", "I", "R"] {
+            model.add_ob(name(x), ob_type.clone());
+        }
+        let flow_type = th.hom_type(ob_type);
+        model.add_mor(name("infect"), name("S").into(), name("I").into(), flow_type.clone());
+        model.add_mor(name("recover"), name("I").into(), name("R").into(), flow_type);
+        let cod = model.tabulated_gen(name("infect"));
+        model.add_mor(name("link"), name("I").into(), cod, TabMorType::Basic(name("Link")));
+        let cod = model.tabulated_gen(name("recover"));
+        let link_type = TabMorType::Basic(name("NegativeLink"));
+        model.add_mor(name("neg_link"), name("S").into(), cod, link_type);
+        model
+    }
+
+    fn sir_data() -> MassActionProblemData {
+        MassActionProblemData {
+            mass_conservation_type: MassConservationType::Balanced,
+            transition_rates: [(name("infect"), 0.5), (name("recover"), 0.25)].into(),
+            transition_consumption_rates: HashMap::new(),
+            transition_production_rates: HashMap::new(),
+            place_consumption_rates: HashMap::new(),
+            place_production_rates: HashMap::new(),
+            initial_values: [(name("S"), 99.0), (name("I"), 1.0)].into(),
+            duration: 20.0,
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        let th = Rc::new(th_category_signed_links());
+        let model = sir_stock_flow(th.clone());
+        let xmile = write_xmile(&model, &sir_data(), &Namespace::new_for_text()).unwrap();
+        assert!(xmile.contains("<eqn>infect_rate * S * I</eqn>"));
+        assert!(xmile.contains("<eqn>recover_rate * I / S</eqn>"));
+        assert!(xmile.contains("<dt>0.2</dt>"));
+
+        let imported = read_xmile(&xmile, th).unwrap();
+        let lookup = |s: &str| match imported.namespace.name_with_label(&label(s)) {
+            NameLookup::Unique(name) => name,
+            _ => panic!("Label should be unique: {s}"),
+        };
+        assert_eq!(imported.data.initial_values[&lookup("S")], 99.0);
+        assert_eq!(imported.data.transition_rates[&lookup("recover")], 0.25);
+        assert_eq!(imported.data.duration, 20.0);
+
+        // The mass-action systems agree up to renaming.
+        let system = |model, namespace: &Namespace| {
+            StockFlowMassActionAnalysis::default()
+                .build_system(model, MassConservationType::Balanced)
+                .map_variables(|id| namespace.label_string(id))
+                .extend_scalars(|param| {
+                    param.map_variables(|flow| match flow {
+                        FlowParameter::Balanced { transition } => {
+                            namespace.label_string(transition)
+                        }
+                        _ => panic!("Flow should be balanced"),
+                    })
+                })
+                .to_string()
+        };
+        assert_eq!(
+            system(&model, &Namespace::new_for_text()),
+            system(&imported.model, &imported.namespace)
+        );
+
+        // Negative links are not supported without signs.
+        let result = read_xmile(&xmile, Rc::new(th_category_links()));
+        assert!(matches!(result, Err(XmileError::UnsupportedLink(flow, _)) if flow == "recover"));
+    }
+
+    #[test]
+    fn round_trip_case_insensitive_names() {
+        let th = Rc::new(th_category_links());
+        let mut model = DiscreteTabModel::new(th.clone());
+        let ob_type = TabObType::Basic(name("Object"));
+        for x in ["S", "s", "Infect rate"] {
+            model.add_ob(name(x), ob_type.clone());
+        }
+        let flow_type = th.hom_type(ob_type);
+        model.add_mor(name("infect"), name("S").into(), name("s").into(), flow_type.clone());
+        model.add_mor(name("leak"), name("s").into(), name("Infect rate").into(), flow_type);
+        let data = MassActionProblemData {
+            transition_rates: [(name("infect"), 0.5), (name("leak"), 0.25)].into(),
+            initial_values: [(name("S"), 1.0), (name("s"), 2.0), (name("Infect rate"), 3.0)].into(),
+            ..sir_data()
+        };
+        let xmile = write_xmile(&model, &data, &Namespace::new_for_text()).unwrap();
+        assert!(xmile.contains(r#"<stock name="s_2">"#));
+        assert!(xmile.contains(r#"<aux name="infect_rate_2">"#));
+
+        let imported = read_xmile(&xmile, th).unwrap();
+        let obs: Vec<_> = imported.model.ob_generators().collect();
+        assert_eq!(obs.len(), 3);
+        let initial: Vec<_> = obs.iter().map(|ob| imported.data.initial_values[ob]).collect();
+        assert_eq!(initial, vec![1.0, 2.0, 3.0]);
+        for mor in imported.model.mor_generators() {
+            let (dom, cod) = (
+                imported.model.mor_generator_dom(&mor).unwrap_basic(),
+                imported.model.mor_generator_cod(&mor).unwrap_basic(),
+            );
+            let (dom, cod) =
+                (imported.namespace.label_string(&dom), imported.namespace.label_string(&cod));
+            match imported.namespace.label_string(&mor).as_str() {
+                "infect" => assert_eq!((dom.as_str(), cod.as_str()), ("S", "s_2")),
+                "leak" => assert_eq!((dom.as_str(), cod.as_str()), ("s_2", "Infect_rate")),
+                label => panic!("Unexpected flow: {label}"),
+            }
+        }
+    }
+
+    #[test]
+    fn import_connectors() {
+        let xmile = r#"<?xml version="1.0" encoding="utf-8"?>
+        <xmile version="1.0" xmlns="http://docs.oasis-open.org/xmile/ns/XMILE/v1.0">
+          <header><vendor>Example</vendor><product>Example</product></header>
+          <sim_specs><start>1</start><stop>51</stop></sim_specs>
+          <model>
+            <variables>
+              <stock name="Prey Population">
+                <eqn>100</eqn>
+                <outflow>"Predation"</outflow>
+              </stock>
+              <stock name="Predators">
+                <eqn>initial_predators</eqn>
+                <inflow>predation</inflow>
+              </stock>
+              <flow name="Predation">
+                <eqn>MAX(0, predation_rate * Prey_Population * Predators)</eqn>
+              </flow>
+              <aux name="predation rate"><eqn>0.01</eqn></aux>
+              <aux name="initial predators"><eqn>5</eqn></aux>
+            </variables>
+            <views>
+              <view>
+                <connector uid="1"><from>Predators</from><to>Predation</to></connector>
+                <connector uid="2"><from>predation_rate</from><to>Predation</to></connector>
+              </view>
+            </views>
+          </model>
+        </xmile>"#;
+        let imported = read_xmile(xmile, Rc::new(th_category_links())).unwrap();
+        let obs: Vec<_> = imported.model.ob_generators().collect();
+        assert_eq!(imported.namespace.label_string(&obs[0]), "Prey Population");
+        assert_eq!(imported.data.initial_values.len(), 1);
+        assert_eq!(imported.data.duration, 50.0);
+
+        // The equation is not a product, so no rate is recovered, but the link to
+        // the predators is found from both the equation and the connector.
+        assert!(imported.data.transition_rates.is_empty());
+        let mors: Vec<_> = imported.model.mor_generators().collect();
+        assert_eq!(mors.len(), 2);
+        assert_eq!(imported.model.mor_generator_dom(&mors[1]), obs[1].clone().into());
+
+        let eqn = "predation_rate * Prey_Population * Predators";
+        let xmile = xmile.replace(&format!("MAX(0, {eqn})"), eqn);
+        let imported = read_xmile(&xmile, Rc::new(th_category_links())).unwrap();
+        assert_eq!(imported.data.transition_rates.values().collect::<Vec<_>>(), vec![&0.01]);
+        assert_eq!(imported.model.mor_generators().count(), 2);
+
+        // Flows from clouds are not supported.
+        let xmile = xmile.replace(r#"<outflow>"Predation"</outflow>"#, "");
+        let result = read_xmile(&xmile, Rc::new(th_category_links()));
+        assert!(matches!(result, Err(XmileError::UnsupportedFlow(flow)) if flow == "Predation"));
+    }
+}
@@ -0,0 +1,413 @@
+//! Minimal XML documents for interchange formats.
+//!
+//! The XML-based interchange formats supported by catlog, such as SBML and PNML,
+//! use only a small part of XML: elements, attributes, and character data. This
+//! module provides a simple element tree for such documents, along with a parser
+//! and a pretty-printer. Processing instructions, comments, and document type
+//! declarations are skipped when parsing, and namespaces are not resolved,
+//! although qualified names can be matched by their local part.
+
+use std::fmt;
+
+use thiserror::Error;
+
+/// An XML element.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Element {
+    /// Qualified name of the element, possibly including a namespace prefix.
+    pub name: String,
+
+    /// Attributes of the element, in order of appearance.
+    pub attributes: Vec<(String, String)>,
+
+    /// Child nodes of the element.
+    pub children: Vec<Node>,
+}
+
+/// A node in an XML element tree.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Node {
+    /// A child element.
+    Element(Element),
+
+    /// Character data, with entities decoded.
+    Text(String),
+}
+
+/// Strips the namespace prefix, if any, from a qualified name.
+fn local_part(name: &str) -> &str {
+    name.rsplit(':').next().unwrap_or(name)
+}
+
+impl Element {
+    /// Creates an element with no attributes or children.
+    pub fn new(name: impl Into<String>) -> Self {
+        Self { name: name.into(), ..Default::default() }
+    }
+
+    /// Adds an attribute to the element.
+    pub fn attr(mut self, name: impl Into<String>, value: impl ToString) -> Self {
+        self.attributes.push((name.into(), value.to_string()));
+        self
+    }
+
+    /// Adds a child element.
+    pub fn child(mut self, child: Element) -> Self {
+        self.children.push(Node::Element(child));
+        self
+    }
+
+    /// Adds child elements.
+    pub fn children(mut self, children: impl IntoIterator<Item = Element>) -> Self {
+        self.children.extend(children.into_iter().map(Node::Element));
+        self
+    }
+
+    /// Adds character data.
+    pub fn text(mut self, text: impl Into<String>) -> Self {
+        self.children.push(Node::Text(text.into()));
+        self
+    }
+
+    /// Name of the element without its namespace prefix.
+    pub fn local_name(&self) -> &str {
+        local_part(&self.name)
+    }
+
+    /// Gets the value of an attribute, matching by local name.
+    pub fn get_attr(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(key, _)| key == name || local_part(key) == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Iterates over the child elements.
+    pub fn elements(&self) -> impl Iterator<Item = &Element> {
+        self.children.iter().filter_map(|node| match node {
+            Node::Element(elem) => Some(elem),
+            Node::Text(_) => None,
+        })
+    }
+
+    /// Finds the first child element with the given local name.
+    pub fn find(&self, name: &str) -> Option<&Element> {
+        self.elements().find(|elem| elem.local_name() == name)
+    }
+
+    /// Iterates over the child elements with the given local name.
+    pub fn find_all<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Element> {
+        self.elements().filter(move |elem| elem.local_name() == name)
+    }
+
+    /// Iterates over the grandchildren with local name `item` inside the first
+    /// child with local name `list`.
+    ///
+    /// This is the common pattern `<listOfThings><thing/>...</listOfThings>`.
+    pub fn find_list<'a>(&'a self, list: &str, item: &'a str) -> impl Iterator<Item = &'a Element> {
+        self.find(list).into_iter().flat_map(move |elem| elem.find_all(item))
+    }
+
+    /// Concatenated character data of the element and its descendants.
+    pub fn text_content(&self) -> String {
+        let mut output = String::new();
+        self.write_text_content(&mut output);
+        output
+    }
+
+    fn write_text_content(&self, output: &mut String) {
+        for node in self.children.iter() {
+            match node {
+                Node::Element(elem) => elem.write_text_content(output),
+                Node::Text(text) => output.push_str(text),
+            }
+        }
+    }
+
+    /// Writes the element as an XML document, including the XML declaration.
+    pub fn to_document(&self) -> String {
+        format!("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n{self}")
+    }
+
+    fn write_indented(&self, f: &mut fmt::Formatter<'_>, depth: usize) -> fmt::Result {
+        let indent = "  ".repeat(depth);
+        write!(f, "{indent}<{}", self.name)?;
+        for (key, value) in self.attributes.iter() {
+            write!(f, " {key}=\"{}\"", escape(value))?;
+        }
+        if self.children.is_empty() {
+            return writeln!(f, "/>");
+        }
+        if let [Node::Text(text)] = self.children.as_slice() {
+            return writeln!(f, ">{}</{}>", escape(text), self.name);
+        }
+        writeln!(f, ">")?;
+        for node in self.children.iter() {
+            match node {
+                Node::Element(elem) => elem.write_indented(f, depth + 1)?,
+                Node::Text(text) => writeln!(f, "{indent}  {}", escape(text.trim()))?,
+            }
+        }
+        writeln!(f, "{indent}</{}>", self.name)
+    }
+}
+
+/// Pretty-prints the element with two-space indentation.
+impl fmt::Display for Element {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write_indented(f, 0)
+    }
+}
+
+/// Escapes the special characters in character data or an attribute value.
+fn escape(text: &str) -> String {
+    let mut output = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '<' => output.push_str("&lt;"),
+            '>' => output.push_str("&gt;"),
+            '&' => output.push_str("&amp;"),
+            '"' => output.push_str("&quot;"),
+            '\'' => output.push_str("&apos;"),
+            _ => output.push(c),
+        }
+    }
+    output
+}
+
+/// An error in parsing an XML document.
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum XmlError {
+    /// The document ended before the root element was closed.
+    #[error("Unexpected end of XML document")]
+    UnexpectedEnd,
+
+    /// The document is malformed at the given byte offset.
+    #[error("Malformed XML at byte {0}: {1}")]
+    Syntax(usize, String),
+
+    /// A closing tag does not match the open element.
+    #[error("Closing tag `{found}` does not match open element `{expected}`")]
+    MismatchedTag {
+        /// Name of the open element.
+        expected: String,
+        /// Name in the closing tag.
+        found: String,
+    },
+}
+
+/// Parses an XML document into its root element.
+///
+/// Whitespace-only character data between elements is discarded.
+pub fn parse(input: &str) -> Result<Element, XmlError> {
+    let mut parser = Parser { input, pos: 0 };
+    parser.skip_misc()?;
+    let root = parser.element()?;
+    parser.skip_misc()?;
+    if parser.pos < input.len() {
+        return Err(parser.error("content after root element"));
+    }
+    Ok(root)
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn rest(&self) -> &str {
+        &self.input[self.pos..]
+    }
+
+    fn error(&self, msg: &str) -> XmlError {
+        XmlError::Syntax(self.pos, msg.to_string())
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    /// Skips past the next occurrence of the delimiter.
+    fn skip_past(&mut self, delim: &str) -> Result<(), XmlError> {
+        let i = self.rest().find(delim).ok_or(XmlError::UnexpectedEnd)?;
+        self.pos += i + delim.len();
+        Ok(())
+    }
+
+    /// Skips whitespace, comments, processing instructions, and declarations.
+    fn skip_misc(&mut self) -> Result<(), XmlError> {
+        loop {
+            self.skip_whitespace();
+            if self.rest().starts_with("<?") {
+                self.skip_past("?>")?;
+            } else if self.rest().starts_with("<!--") {
+                self.skip_past("-->")?;
+            } else if self.rest().starts_with("<!") {
+                self.skip_past(">")?;
+            } else {
+                return Ok(());
+            }
+        }
+    }
+
+    fn name(&mut self) -> Result<String, XmlError> {
+        let rest = self.rest();
+        let len = rest
+            .find(|c: char| c.is_whitespace() || matches!(c, '/' | '>' | '=' | '<'))
+            .unwrap_or(rest.len());
+        if len == 0 {
+            return Err(self.error("expected a name"));
+        }
+        let name = rest[..len].to_string();
+        self.pos += len;
+        Ok(name)
+    }
+
+    fn expect(&mut self, token: &str) -> Result<(), XmlError> {
+        if self.rest().starts_with(token) {
+            self.pos += token.len();
+            Ok(())
+        } else if self.rest().is_empty() {
+            Err(XmlError::UnexpectedEnd)
+        } else {
+            Err(self.error(&format!("expected `{token}`")))
+        }
+    }
+
+    fn element(&mut self) -> Result<Element, XmlError> {
+        self.expect("<")?;
+        let mut elem = Element::new(self.name()?);
+        loop {
+            self.skip_whitespace();
+            if self.rest().starts_with("/>") {
+                self.pos += 2;
+                return Ok(elem);
+            } else if self.rest().starts_with(">") {
+                self.pos += 1;
+                break;
+            }
+            let key = self.name()?;
+            self.skip_whitespace();
+            self.expect("=")?;
+            self.skip_whitespace();
+            let quote = self.rest().chars().next().ok_or(XmlError::UnexpectedEnd)?;
+            if quote != '"' && quote != '\'' {
+                return Err(self.error("expected quoted attribute value"));
+            }
+            self.pos += 1;
+            let len = self.rest().find(quote).ok_or(XmlError::UnexpectedEnd)?;
+            let value = unescape(&self.rest()[..len]).map_err(|msg| self.error(&msg))?;
+            self.pos += len + 1;
+            elem.attributes.push((key, value));
+        }
+
+        loop {
+            let rest = self.rest();
+            if rest.is_empty() {
+                return Err(XmlError::UnexpectedEnd);
+            } else if rest.starts_with("</") {
+                self.pos += 2;
+                let found = self.name()?;
+                if found != elem.name {
+                    return Err(XmlError::MismatchedTag { expected: elem.name, found });
+                }
+                self.skip_whitespace();
+                self.expect(">")?;
+                return Ok(elem);
+            } else if rest.starts_with("<!--") {
+                self.skip_past("-->")?;
+            } else if let Some(cdata) = rest.strip_prefix("<![CDATA[") {
+                let len = cdata.find("]]>").ok_or(XmlError::UnexpectedEnd)?;
+                elem.children.push(Node::Text(cdata[..len].to_string()));
+                self.pos += "<![CDATA[".len() + len + "]]>".len();
+            } else if rest.starts_with("<?") {
+                self.skip_past("?>")?;
+            } else if rest.starts_with('<') {
+                let child = self.element()?;
+                elem.children.push(Node::Element(child));
+            } else {
+                let len = rest.find('<').unwrap_or(rest.len());
+                let text = &rest[..len];
+                if !text.trim().is_empty() {
+                    let text = unescape(text).map_err(|msg| self.error(&msg))?;
+                    elem.children.push(Node::Text(text));
+                }
+                self.pos += len;
+            }
+        }
+    }
+}
+
+/// Decodes the predefined entities and character references.
+fn unescape(text: &str) -> Result<String, String> {
+    let mut output = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(i) = rest.find('&') {
+        output.push_str(&rest[..i]);
+        rest = &rest[i + 1..];
+        let end = rest.find(';').ok_or("unterminated entity")?;
+        let entity = &rest[..end];
+        let c = match entity {
+            "lt" => '<',
+            "gt" => '>',
+            "amp" => '&',
+            "quot" => '"',
+            "apos" => '\'',
+            _ => {
+                let code = if let Some(hex) = entity.strip_prefix("#x") {
+                    u32::from_str_radix(hex, 16).ok()
+                } else if let Some(dec) = entity.strip_prefix('#') {
+                    dec.parse().ok()
+                } else {
+                    None
+                };
+                code.and_then(char::from_u32)
+                    .ok_or_else(|| format!("unknown entity `&{entity};`"))?
+            }
+        };
+        output.push(c);
+        rest = &rest[end + 1..];
+    }
+    output.push_str(rest);
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_and_print() {
+        let input = r#"<?xml version="1.0"?>
+            <!-- A comment -->
+            <root xmlns:a="urn:a" a:key='1 &amp; 2'>
+              <a:item id="x"/>
+              <item id="y">Text &lt;here&gt; &#65;</item>
+              <![CDATA[<raw>]]>
+            </root>"#;
+        let root = parse(input).unwrap();
+        assert_eq!(root.local_name(), "root");
+        assert_eq!(root.get_attr("key"), Some("1 & 2"));
+        let ids: Vec<_> = root.find_all("item").filter_map(|e| e.get_attr("id")).collect();
+        assert_eq!(ids, vec!["x", "y"]);
+        assert_eq!(root.find_all("item").nth(1).unwrap().text_content(), "Text <here> A");
+
+        let printed = root.to_document();
+        let reparsed = parse(&printed).unwrap();
+        assert_eq!(reparsed.find_all("item").count(), 2);
+        assert_eq!(reparsed.find_all("item").nth(1).unwrap().text_content(), "Text <here> A");
+        assert!(reparsed.text_content().contains("<raw>"));
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!(
+            parse("<a><b></a>").unwrap_err(),
+            XmlError::MismatchedTag { expected: "b".into(), found: "a".into() }
+        );
+        assert_eq!(parse("<a>").unwrap_err(), XmlError::UnexpectedEnd);
+        assert!(matches!(parse("<a x=1/>"), Err(XmlError::Syntax(_, _))));
+    }
+}
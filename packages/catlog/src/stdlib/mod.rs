@@ -9,3 +9,4 @@ pub use theories::*;
 pub use theory_morphisms::*;
 
 pub mod analyses;
+pub mod formats;
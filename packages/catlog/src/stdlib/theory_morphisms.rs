@@ -2,10 +2,26 @@
 //!
 //! These can be used to migrate models from one theory to another.
 
-use crate::one::{FpFunctorData, Path, QualifiedPath};
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+#[cfg(feature = "serde-wasm")]
+use tsify::Tsify;
+
+use super::analyses::feedback_loops::feedback_clusters;
+use crate::dbl::model::{DiscreteDblModel, DiscreteTabModel, FpDblModel, MutDblModel};
+use crate::dbl::theory::{
+    DblTheory, DiscreteTabTheory, DiscreteTabTheoryMap, ModalDblTheoryMap, ModeApp, TabMorType,
+    TabObType,
+};
+use crate::one::{FgCategory, FpFunctorData, Path, QualifiedPath, ShortPath};
 use crate::zero::{HashColumn, QualifiedName, name};
 
-type DiscreteDblTheoryMap = FpFunctorData<
+/// A map between discrete double theories, given by its action on generators.
+pub type DiscreteDblTheoryMap = FpFunctorData<
     HashColumn<QualifiedName, QualifiedName>,
     HashColumn<QualifiedName, QualifiedPath>,
 >;
@@ -13,7 +29,8 @@ type DiscreteDblTheoryMap = FpFunctorData<
 /// Map from theory of categories to the theories of schemas.
 ///
 /// Sigma migration along this map sends objects in a category to entity types in a
-/// schema, yielding a schema with no attributes or attribute types.
+/// schema, yielding a schema with no attributes or attribute types. Delta
+/// migration along it restricts a schema to its entity types.
 pub fn th_category_to_schema() -> DiscreteDblTheoryMap {
     FpFunctorData::new(
         HashColumn::from_iter([(name("Object"), name("Entity"))]),
@@ -51,10 +68,263 @@ pub fn th_delayable_signed_category_to_signed_category() -> DiscreteDblTheoryMap
     )
 }
 
+/// Inclusion of theory of signed categories into nullable signed categories.
+///
+/// Delta migration along this map restricts a nullable signed category to its
+/// morphisms with nonzero sign.
+pub fn th_signed_category_to_nullable_signed_category() -> DiscreteDblTheoryMap {
+    FpFunctorData::new(
+        HashColumn::from_iter([(name("Object"), name("Object"))]),
+        HashColumn::from_iter([(name("Negative"), name("Negative").into())]),
+    )
+}
+
+/// Projection from theory of categories with signed links to categories with links.
+///
+/// Sigma migration along this map forgets the signs of links.
+pub fn th_category_signed_links_to_category_links() -> DiscreteTabTheoryMap {
+    DiscreteTabTheoryMap::new(
+        HashColumn::from_iter([(name("Object"), name("Object").into())]),
+        HashColumn::from_iter([
+            (name("Link"), name("Link").into()),
+            (name("NegativeLink"), name("Link").into()),
+        ]),
+    )
+}
+
+/// Projection from theory of signed polynomial ODE systems to unsigned ones.
+///
+/// Sigma migration along this map forgets the signs of contributions.
+pub fn th_signed_polynomial_ode_system_to_polynomial_ode_system() -> ModalDblTheoryMap {
+    let contribution = ShortPath::One(ModeApp::new(name("Contribution")));
+    ModalDblTheoryMap::new(
+        HashColumn::from_iter([(name("State"), ModeApp::new(name("State")))]),
+        HashColumn::from_iter([
+            (name("Contribution"), contribution.clone()),
+            (name("NegativeContribution"), contribution),
+        ]),
+        HashColumn::default(),
+    )
+}
+
+/// Strictification map from theory of lax monoidal categories.
+///
+/// Sigma migration along this map regards a lax monoidal category as a strict one.
+pub fn th_lax_monoidal_category_to_monoidal_category() -> ModalDblTheoryMap {
+    ModalDblTheoryMap::new(
+        HashColumn::from_iter([(name("Object"), ModeApp::new(name("Object")))]),
+        HashColumn::default(),
+        HashColumn::from_iter([(name("tensor"), name("tensor"))]),
+    )
+}
+
+/// Rule for converting an edge of a causal loop diagram into a stock-flow diagram.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "tag", content = "content"))]
+#[cfg_attr(feature = "serde-wasm", derive(Tsify))]
+#[cfg_attr(feature = "serde-wasm", tsify(into_wasm_abi, from_wasm_abi))]
+pub enum CausalEdgeRule {
+    /// Edge becomes a flow between its source and target stocks.
+    Flow,
+
+    /// Edge becomes a link from its source stock to the given flow.
+    Link(QualifiedName),
+
+    /// Edge is dropped.
+    Omit,
+}
+
+/// Rules for scaffolding a stock-flow diagram from a causal loop diagram.
+///
+/// A causal loop diagram, a model of the theory of [signed
+/// categories](super::theories::th_signed_category), can be migrated to a
+/// stock-flow diagram, a model of the theory of [categories with signed
+/// links](super::theories::th_category_signed_links). Unlike the maps above,
+/// this migration is not a Sigma or Delta migration along a theory morphism.
+/// Such migrations act uniformly on all generators of a given type, whereas
+/// here a variable might become a stock or be dropped, and two edges of the same
+/// sign might become a flow and a link. Nor can any map from the theory of signed
+/// categories send an edge to a link, since links go from objects to tabulators
+/// rather than between objects. Instead, the choices are recorded as rules, which
+/// can be [proposed](Self::propose) and then adjusted by the user.
+///
+/// The sign of an edge is the parity of its number of `Negative` factors. Edges
+/// that become flows must be positive. Edges that become links keep their signs,
+/// positive edges becoming `Link` and negative edges becoming `NegativeLink`, so
+/// the target theory must have the link types that are used. Stocks, flows, and
+/// links keep the names of the objects and edges they come from, so that labels
+/// are preserved.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde-wasm", derive(Tsify))]
+#[cfg_attr(feature = "serde-wasm", tsify(into_wasm_abi, from_wasm_abi))]
+pub struct StockFlowScaffold {
+    /// Objects of the causal loop diagram that become stocks.
+    pub stocks: Vec<QualifiedName>,
+
+    /// Rules for edges of the causal loop diagram.
+    ///
+    /// Edges without a rule are omitted.
+    pub edges: HashMap<QualifiedName, CausalEdgeRule>,
+}
+
+/// Error in migrating a causal loop diagram to a stock-flow diagram.
+#[derive(Debug, Error)]
+pub enum StockFlowScaffoldError {
+    /// Edge becomes a flow or link but an endpoint is not a stock.
+    #[error("Edge `{0}` requires `{1}` to be a stock")]
+    NotStock(QualifiedName, QualifiedName),
+
+    /// Negative edge cannot become a flow.
+    #[error("Negative edge `{0}` cannot become a flow")]
+    NegativeFlow(QualifiedName),
+
+    /// Edge becomes a link to something that is not a flow.
+    #[error("Edge `{0}` is linked to `{1}`, which is not a flow")]
+    NotFlow(QualifiedName, QualifiedName),
+
+    /// Target theory has no object type for stocks.
+    #[error("Target theory has no object type `Object` for stocks")]
+    NoStockType,
+
+    /// Target theory has no link type from stocks to flows for an edge.
+    #[error("Edge `{0}` requires the target theory to have a link type `{1}`")]
+    NoLinkType(QualifiedName, QualifiedName),
+}
+
+impl StockFlowScaffold {
+    /// Proposes rules for scaffolding a causal loop diagram.
+    ///
+    /// Objects belonging to a [feedback cluster](feedback_clusters) are proposed
+    /// as stocks, since variables in a feedback loop tend to accumulate. Positive
+    /// edges between distinct stocks become flows. Any other edge between stocks
+    /// becomes a link to a flow into its target stock, or else out of its target
+    /// stock, if there is one. All remaining edges are omitted.
+    pub fn propose(model: &DiscreteDblModel) -> Self {
+        let clustered: HashSet<_> =
+            feedback_clusters(model).into_iter().flat_map(|c| c.ob_generators).collect();
+        let stocks: Vec<_> = model.ob_generators().filter(|x| clustered.contains(x)).collect();
+
+        let mut edges = HashMap::new();
+        let mut flows = Vec::new();
+        for f in model.mor_generators() {
+            let (x, y) = (model.mor_generator_dom(&f), model.mor_generator_cod(&f));
+            if x != y
+                && clustered.contains(&x)
+                && clustered.contains(&y)
+                && is_positive(&model.mor_generator_type(&f))
+            {
+                edges.insert(f.clone(), CausalEdgeRule::Flow);
+                flows.push((f, x, y));
+            }
+        }
+        for f in model.mor_generators() {
+            let (x, y) = (model.mor_generator_dom(&f), model.mor_generator_cod(&f));
+            if edges.contains_key(&f) {
+                continue;
+            }
+            let flow = (clustered.contains(&x) && clustered.contains(&y))
+                .then(|| {
+                    let inflow = flows.iter().find(|(_, _, tgt)| *tgt == y);
+                    inflow.or_else(|| flows.iter().find(|(_, src, _)| *src == y))
+                })
+                .flatten();
+            let rule = match flow {
+                Some((flow, _, _)) => CausalEdgeRule::Link(flow.clone()),
+                None => CausalEdgeRule::Omit,
+            };
+            edges.insert(f, rule);
+        }
+        Self { stocks, edges }
+    }
+
+    /// Migrates a causal loop diagram to a stock-flow diagram using the rules.
+    pub fn migrate(
+        &self,
+        model: &DiscreteDblModel,
+        theory: Rc<DiscreteTabTheory>,
+    ) -> Result<DiscreteTabModel, StockFlowScaffoldError> {
+        let stocks: HashSet<_> = self.stocks.iter().collect();
+        let ob_type = TabObType::Basic(name("Object"));
+        let flow_type = theory.hom_type(ob_type.clone());
+        if !theory.has_ob_type(&ob_type) {
+            return Err(StockFlowScaffoldError::NoStockType);
+        }
+        let is_link_type = |link_type: &TabMorType| {
+            theory.has_mor_type(link_type)
+                && theory.src_type(link_type) == ob_type
+                && theory.tgt_type(link_type) == theory.tabulator(flow_type.clone())
+        };
+        let mut result = DiscreteTabModel::new(theory.clone());
+        for x in model.ob_generators().filter(|x| stocks.contains(x)) {
+            result.add_ob(x, ob_type.clone());
+        }
+
+        let rule = |f: &QualifiedName| self.edges.get(f).unwrap_or(&CausalEdgeRule::Omit);
+        let require_stock = |f: &QualifiedName, x: QualifiedName| {
+            if stocks.contains(&x) {
+                Ok(x)
+            } else {
+                Err(StockFlowScaffoldError::NotStock(f.clone(), x))
+            }
+        };
+        let mut flows = HashSet::new();
+        for f in model.mor_generators() {
+            if *rule(&f) != CausalEdgeRule::Flow {
+                continue;
+            }
+            if !is_positive(&model.mor_generator_type(&f)) {
+                return Err(StockFlowScaffoldError::NegativeFlow(f));
+            }
+            let x = require_stock(&f, model.mor_generator_dom(&f))?;
+            let y = require_stock(&f, model.mor_generator_cod(&f))?;
+            result.add_mor(f.clone(), x.into(), y.into(), flow_type.clone());
+            flows.insert(f);
+        }
+        for f in model.mor_generators() {
+            let CausalEdgeRule::Link(flow) = rule(&f) else {
+                continue;
+            };
+            if !flows.contains(flow) {
+                return Err(StockFlowScaffoldError::NotFlow(f, flow.clone()));
+            }
+            let x = require_stock(&f, model.mor_generator_dom(&f))?;
+            let link = signed_link(&model.mor_generator_type(&f));
+            let link_type = TabMorType::Basic(link.clone());
+            if !is_link_type(&link_type) {
+                return Err(StockFlowScaffoldError::NoLinkType(f, link));
+            }
+            result.add_mor(f, x.into(), result.tabulated_gen(flow.clone()), link_type);
+        }
+        Ok(result)
+    }
+}
+
+/// Whether a morphism type in the theory of signed categories is positive.
+///
+/// Since `Negative` squares to the identity, the sign is the parity of the
+/// number of `Negative` generators in the path.
+fn is_positive(mor_type: &QualifiedPath) -> bool {
+    mor_type.len().is_multiple_of(2)
+}
+
+/// Link type in a category with signed links for a morphism type in the theory
+/// of signed categories.
+fn signed_link(mor_type: &QualifiedPath) -> QualifiedName {
+    if is_positive(mor_type) {
+        name("Link")
+    } else {
+        name("NegativeLink")
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::super::theories::*;
     use super::*;
+    use crate::dbl::model::TabEdge;
+    use crate::validate::Validate;
 
     #[test]
     fn discrete_theory_morphisms() {
@@ -68,5 +338,83 @@ mod tests {
                 .validate_on(&th_delayable_signed_category().0)
                 .is_ok()
         );
+        assert!(
+            th_signed_category_to_nullable_signed_category()
+                .functor_into(&th_nullable_signed_category().0)
+                .validate_on(&th_signed_category().0)
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn causal_loop_to_stock_flow() {
+        let mut model = DiscreteDblModel::new(Rc::new(th_signed_category()));
+        for x in ["x", "y", "z"] {
+            model.add_ob(name(x), name("Object"));
+        }
+        model.add_mor(name("f"), name("x"), name("y"), Path::Id(name("Object")));
+        model.add_mor(name("g"), name("y"), name("x"), name("Negative").into());
+        model.add_mor(name("h"), name("z"), name("x"), Path::Id(name("Object")));
+
+        let scaffold = StockFlowScaffold::propose(&model);
+        assert_eq!(scaffold.stocks, vec![name("x"), name("y")]);
+        assert_eq!(scaffold.edges[&name("f")], CausalEdgeRule::Flow);
+        assert_eq!(scaffold.edges[&name("g")], CausalEdgeRule::Link(name("f")));
+        assert_eq!(scaffold.edges[&name("h")], CausalEdgeRule::Omit);
+
+        let th = Rc::new(th_category_signed_links());
+        let sf = scaffold.migrate(&model, th.clone()).unwrap();
+        assert!(sf.validate().is_ok());
+        assert_eq!(sf.ob_generators().count(), 2);
+        assert_eq!(sf.mor_generators().count(), 2);
+        assert_eq!(sf.mor_generator_type(&name("g")), TabMorType::Basic(name("NegativeLink")));
+        assert_eq!(
+            sf.mor_generator_cod(&name("g")).unwrap_tabulated().only(),
+            Some(TabEdge::Basic(name("f")))
+        );
+
+        let mut scaffold = scaffold;
+        scaffold.edges.insert(name("g"), CausalEdgeRule::Flow);
+        assert!(matches!(
+            scaffold.migrate(&model, th.clone()),
+            Err(StockFlowScaffoldError::NegativeFlow(_))
+        ));
+        scaffold.edges.insert(name("h"), CausalEdgeRule::Link(name("f")));
+        scaffold.edges.insert(name("g"), CausalEdgeRule::Omit);
+        assert!(matches!(
+            scaffold.migrate(&model, th),
+            Err(StockFlowScaffoldError::NotStock(_, _))
+        ));
+    }
+
+    #[test]
+    fn causal_loop_to_stock_flow_signs() {
+        let mut model = DiscreteDblModel::new(Rc::new(th_signed_category()));
+        for x in ["x", "y"] {
+            model.add_ob(name(x), name("Object"));
+        }
+        let double_negative = Path::pair(name("Negative"), name("Negative"));
+        model.add_mor(name("f"), name("x"), name("y"), double_negative);
+        model.add_mor(name("g"), name("y"), name("x"), name("Negative").into());
+
+        // An edge with two negative factors is positive, so it becomes a flow.
+        let scaffold = StockFlowScaffold::propose(&model);
+        assert_eq!(scaffold.edges[&name("f")], CausalEdgeRule::Flow);
+        let sf = scaffold.migrate(&model, Rc::new(th_category_signed_links())).unwrap();
+        assert_eq!(sf.mor_generator_type(&name("g")), TabMorType::Basic(name("NegativeLink")));
+
+        // The target theory must have the link types that are used.
+        assert!(matches!(
+            scaffold.migrate(&model, Rc::new(th_category_links())),
+            Err(StockFlowScaffoldError::NoLinkType(f, link))
+                if f == name("g") && link == name("NegativeLink")
+        ));
+        let mut positive = scaffold.clone();
+        positive.edges.insert(name("g"), CausalEdgeRule::Omit);
+        assert!(positive.migrate(&model, Rc::new(th_category_links())).is_ok());
+        assert!(matches!(
+            positive.migrate(&model, Rc::new(DiscreteTabTheory::new())),
+            Err(StockFlowScaffoldError::NoStockType)
+        ));
     }
 }
@@ -11,7 +11,7 @@ use scopeguard::guard;
 use tattle::display::SourceInfo;
 use tattle::{Reporter, declare_error};
 
-use super::{text_elab::*, theory::std_theories, toplevel::*};
+use super::{notebook_gen::UuidSource, text_elab::*, theory::std_theories, toplevel::*};
 use crate::zero::NameSegment;
 
 declare_error!(TOP_ERROR, "top", "an error at the top-level");
@@ -109,6 +109,14 @@ impl BatchOutput {
         }
     }
 
+    fn uuids(&self) -> UuidSource {
+        match self {
+            // Snapshots must be reproducible.
+            BatchOutput::Snapshot(_) => UuidSource::Sequential(0),
+            BatchOutput::Interactive => UuidSource::Fresh,
+        }
+    }
+
     fn record_time(&self, path: &str, elapsed_t: Duration) {
         match self {
             BatchOutput::Snapshot(_) => {}
@@ -154,7 +162,7 @@ pub fn elaborate(src: &str, path: &str, output: &BatchOutput) -> io::Result<bool
     let mut succeeded = true;
     let _ = TT_PARSE_CONFIG.with_parsed_top(src, reporter.clone(), |topntns| {
         let mut toplevel = Toplevel::new(std_theories());
-        let mut topelab = TopElaborator::new(reporter.clone());
+        let mut topelab = TopElaborator::new(reporter.clone()).notebook_uuids(output.uuids());
         for topntn in topntns.iter() {
             output.log_input(src, topntn);
             let mut should_fail = false;
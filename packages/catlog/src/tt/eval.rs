@@ -9,6 +9,7 @@
 use derive_more::Constructor;
 
 use crate::{
+    one::{Category, FpCategory, Graph},
     tt::{prelude::*, stx::*, toplevel::*, val::*},
     zero::LabelSegment,
 };
@@ -50,6 +51,11 @@ impl<'a> Evaluator<'a> {
     pub fn eval_ty(&self, ty: &TyS) -> TyV {
         match &**ty {
             TyS_::TopVar(tv) => self.toplevel.declarations.get(tv).unwrap().clone().unwrap_ty().val,
+            TyS_::TopApp(tv, args_s) => {
+                let env = Env::nil().extend_by(args_s.iter().map(|arg_s| self.eval_tm(arg_s)));
+                let def = self.toplevel.declarations.get(tv).unwrap().clone().unwrap_type_def();
+                self.with_env(env).eval_ty(&def.body)
+            }
             TyS_::Object(ot) => TyV::object(ot.clone()),
             TyS_::Morphism(pt, dom, cod) => {
                 TyV::morphism(pt.clone(), self.eval_tm(dom), self.eval_tm(cod))
@@ -195,6 +201,36 @@ impl<'a> Evaluator<'a> {
         }
     }
 
+    /// Quotes the type of a record field, given its syntax and value.
+    ///
+    /// Quoting the type of a field declared with a top-level type, such as an
+    /// instantiation, would unfold it into a record type, so instead we keep
+    /// the reference to the top-level declaration and quote only the
+    /// specializations. This records the declared name for later use, e.g.,
+    /// when [generating notebooks](super::notebook_gen).
+    pub fn quote_field_ty(&self, ty_s: &TyS, ty_v: &TyV) -> TyS {
+        let base = match &**ty_s {
+            TyS_::Specialize(base, _) => base,
+            _ => ty_s,
+        };
+        match (&**base, &**ty_v) {
+            (TyS_::TopVar(_), TyV_::Record(r)) if !r.specializations.is_empty() => {
+                let specializations = r
+                    .specializations
+                    .flatten()
+                    .into_iter()
+                    .map(|(name, label, ty_v)| {
+                        let path = name.segments().copied().zip(label.segments().copied());
+                        (path.collect(), self.quote_ty(&ty_v))
+                    })
+                    .collect();
+                TyS::specialize(base.clone(), specializations)
+            }
+            (TyS_::TopVar(_), TyV_::Record(_)) => base.clone(),
+            _ => self.quote_ty(ty_v),
+        }
+    }
+
     /// Produce term syntax from a neutral term.
     ///
     /// The documentation for [Evaluator::quote_ty] is also applicable here.
@@ -318,7 +354,10 @@ impl<'a> Evaluator<'a> {
                 TmV::cons(fields)
             }
             TyV_::Sing(_, x) => x.clone(),
-            TyV_::Id(_, _, _) => TmV::tt(), // Extensional equality at a 100% discount!
+            // Proofs of equality are not expanded to `tt`, so that the equation
+            // remains available to `equal_mor`. Equality is still extensional,
+            // as `equal_tm` regards all proofs of an equation as equal.
+            TyV_::Id(_, _, _) => TmV::neu(n.clone(), ty.clone()),
             TyV_::Unit => TmV::tt(),
             TyV_::Meta(_) => TmV::neu(n.clone(), ty.clone()),
         }
@@ -359,15 +398,80 @@ impl<'a> Evaluator<'a> {
     ///
     /// Assumes that the type of tm1 is convertible with the type of tm2. First
     /// attempts to do conversion checking without eta-expansion (strict mode),
-    /// and if that fails, does conversion checking with eta-expansion.
+    /// and if that fails, does conversion checking with eta-expansion. If both
+    /// fail and the terms are morphisms, falls back to checking equality under
+    /// the equations in context with [`Evaluator::equal_mor`].
     pub fn equal_tm<'b>(&self, tm1: &TmV, tm2: &TmV) -> Result<(), D<'b>> {
         if self.equal_tm_helper(tm1, tm2, true, true).is_err() {
             self.equal_tm_helper(tm1, tm2, false, false)
+                .or_else(|err| self.equal_mor(tm1, tm2).unwrap_or(Err(err)))
         } else {
             Ok(())
         }
     }
 
+    /// Check if two morphisms are equal under the equations in context.
+    ///
+    /// The morphisms are flattened into paths of neutral morphisms, which are
+    /// compared in the category presented by the neutral morphisms and the
+    /// equations between them found in the environment. The word problem for
+    /// this category is referred to an e-graph ([`FpCategory`]). Only the axioms
+    /// of a category are used, so this is not complete for theories with more
+    /// structure on morphisms, such as modal theories.
+    ///
+    /// Returns `None` if either term is not a composite of neutral morphisms
+    /// and identities on neutral objects.
+    pub fn equal_mor<'b>(&self, tm1: &TmV, tm2: &TmV) -> Option<Result<(), D<'b>>> {
+        let mut cat = FpCategory::new();
+        let path1 = mor_path(&mut cat, tm1)?;
+        let path2 = mor_path(&mut cat, tm2)?;
+        let mut equations = Vec::new();
+        for v in self.env.to_vec() {
+            self.collect_equations(&v, &mut equations);
+        }
+        for (lhs, rhs) in equations {
+            if let (Some(lhs), Some(rhs)) = (mor_path(&mut cat, &lhs), mor_path(&mut cat, &rhs)) {
+                cat.equate(lhs, rhs);
+            }
+        }
+        if cat.morphisms_are_equal(path1.clone(), path2.clone()) {
+            Some(Ok(()))
+        } else {
+            Some(Err(t(format!(
+                "Paths {} and {} are not equal under the equations in context.",
+                self.path_to_string(&path1),
+                self.path_to_string(&path2),
+            ))))
+        }
+    }
+
+    /// Collects the equations that are proved by (the fields of) a term.
+    fn collect_equations(&self, tm: &TmV, out: &mut Vec<(TmV, TmV)>) {
+        match &**tm {
+            TmV_::Neu(n, ty) => match &**ty {
+                TyV_::Id(_, lhs, rhs) => out.push((lhs.clone(), rhs.clone())),
+                TyV_::Record(_) => self.collect_equations(&self.eta_neu(n, ty), out),
+                _ => {}
+            },
+            TmV_::Cons(fields) => {
+                for (_, (_, field)) in fields.iter() {
+                    self.collect_equations(field, out);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn path_to_string(&self, path: &Path<TmN, TmN>) -> String {
+        match path {
+            Path::Id(x) => format!("(@id {})", self.quote_neu(x)),
+            Path::Seq(fs) => {
+                let fs: Vec<_> = fs.iter().map(|f| self.quote_neu(f).to_string()).collect();
+                fs.join(" · ")
+            }
+        }
+    }
+
     fn equal_tm_helper<'b>(
         &self,
         tm1: &TmV,
@@ -382,6 +486,10 @@ impl<'a> Evaluator<'a> {
             (_, TmV_::Neu(n2, ty2)) if !strict2 => {
                 self.equal_tm_helper(tm1, &self.eta_neu(n2, ty2), strict1, true)
             }
+            // All proofs of an equation are equal.
+            (TmV_::Neu(_, ty), _) | (_, TmV_::Neu(_, ty)) if matches!(&**ty, TyV_::Id(..)) => {
+                Ok(())
+            }
             (TmV_::Neu(n1, _), TmV_::Neu(n2, _)) => {
                 if n1 == n2 {
                     Ok(())
@@ -473,3 +581,45 @@ impl<'a> Evaluator<'a> {
         Ok(TyV::record(r.add_specialization(path, field_ty)))
     }
 }
+
+/// Flattens a morphism into a path of neutral morphisms, adding the neutral
+/// morphisms and their (co)domains to the category as generators.
+fn mor_path(cat: &mut FpCategory<TmN, TmN>, tm: &TmV) -> Option<Path<TmN, TmN>> {
+    match &**tm {
+        TmV_::Neu(f, ty) => {
+            let TyV_::Morphism(_, dom, cod) = &**ty else {
+                return None;
+            };
+            let (TmV_::Neu(dom, _), TmV_::Neu(cod, _)) = (&**dom, &**cod) else {
+                return None;
+            };
+            if !cat.generators().has_edge(f) {
+                for x in [dom, cod] {
+                    if !cat.has_ob(x) {
+                        cat.add_ob_generator(x.clone());
+                    }
+                }
+                cat.add_mor_generator(f.clone(), dom.clone(), cod.clone());
+            }
+            Some(Path::single(f.clone()))
+        }
+        TmV_::Id(x) => {
+            let TmV_::Neu(x, _) = &**x else {
+                return None;
+            };
+            if !cat.has_ob(x) {
+                cat.add_ob_generator(x.clone());
+            }
+            Some(Path::empty(x.clone()))
+        }
+        TmV_::Compose(f, g) => match (mor_path(cat, f)?, mor_path(cat, g)?) {
+            (Path::Id(_), g) => Some(g),
+            (f, Path::Id(_)) => Some(f),
+            (Path::Seq(mut fs), Path::Seq(gs)) => {
+                fs.extend(gs);
+                Some(Path::Seq(fs))
+            }
+        },
+        _ => None,
+    }
+}
@@ -124,8 +124,10 @@
 //! The implementation of NbE for DoubleTT is simplified compared to a generic
 //! dependent type theory because we need only normalize types for objects---and
 //! type dependency appears only for morphism types (which depend on a pair of
-//! objects). Therefore, we don’t need to worry about equality checking
-//! with respect to any morphism equalities which we might want to impose.
+//! objects). Therefore, normalization need not take into account any morphism
+//! equalities which we might want to impose. Instead, when two morphisms fail
+//! to be convertible, [`eval::Evaluator::equal_tm`] falls back to deciding
+//! whether they are equal under the equations in context using an e-graph.
 //!
 //! # Specialization
 //!
@@ -191,15 +193,41 @@
 //! the first type is an element of the second type. This neatly resolves the
 //! difference between `[ x : @sing a ]` and `[ x : Entity ] & [ .x := a ]`,
 //! which are represented differently, but should be semantically the same type.
+//!
+//! # Parameterised types
+//!
+//! Type declarations may take term arguments, which allows families of models
+//! to be generated without copy-paste:
+//!
+//! ```text
+//! type Stage[input : Object] := [
+//!   output : Object,
+//!   flow : (Hom Object)[input, output]
+//! ]
+//!
+//! type Chain2 := [
+//!   x0 : Object,
+//!   s1 : Stage[x0],
+//!   s2 : Stage[s1.output]
+//! ]
+//! ```
+//!
+//! Such a declaration is stored as a [`toplevel::TypeDef`] and its applications
+//! as [`TyS_::TopApp`]. Since the body of the declaration depends on the
+//! arguments, it cannot be evaluated ahead of time; instead, an application is
+//! evaluated by evaluating the body in an environment containing the values of
+//! the arguments, just like applications of term definitions ([`TmS_::TopApp`]).
 
 pub mod batch;
 pub mod context;
 pub mod eval;
 pub mod modelgen;
 pub mod notebook_elab;
+pub mod notebook_gen;
 pub mod prelude;
 pub mod stx;
 pub mod text_elab;
+pub mod text_gen;
 pub mod theory;
 pub mod toplevel;
 pub mod util;
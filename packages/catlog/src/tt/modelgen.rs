@@ -14,6 +14,7 @@ use crate::one::{
     Category,
     path::{Path, PathEq},
 };
+use crate::stdlib::formats::drawing::{DrawableDblModel, GraphDrawing};
 use crate::zero::{Namespace, QualifiedName};
 
 /// A model generated by DoubleTT.
@@ -199,6 +200,13 @@ impl Model {
             Model::[Discrete, DiscreteTab, ModalUnital, ModalNonUnital](model) => printer.namespaced_doc(model.as_ref(), ns, ns)
         })
     }
+
+    /// Draws the model as a graph with labels from the given namespace.
+    pub fn to_drawing(&self, ns: &Namespace) -> GraphDrawing {
+        all_the_same!(match self {
+            Model::[Discrete, DiscreteTab, ModalUnital, ModalNonUnital](model) => model.to_drawing(ns, ns)
+        })
+    }
 }
 
 struct ModelGenerator<'a> {
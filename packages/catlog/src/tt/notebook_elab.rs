@@ -12,9 +12,10 @@ use uuid::Uuid;
 
 use super::{context::*, eval::*, prelude::*, stx::*, theory::*, toplevel::*, val::*};
 use crate::dbl::{
-    modal,
+    discrete_tabulator, modal,
     model::{Feature, InvalidDblModel, InvalidModelEqn},
 };
+use crate::one::ShortPath;
 use crate::zero::QualifiedName;
 
 /// The current state of a notebook elaboration session.
@@ -445,13 +446,13 @@ impl<'a> Elaborator<'a> {
         let c = self.checkpoint();
 
         for cell in cells {
-            let (name, label, _, ty_v) = match &cell {
+            let (name, label, ty_s, ty_v) = match &cell {
                 nb::ModelJudgment::Object(ob_decl) => self.object_cell(ob_decl),
                 nb::ModelJudgment::Morphism(mor_decl) => self.morphism_cell(mor_decl),
                 nb::ModelJudgment::Instantiation(i_decl) => self.instantiation_cell(i_decl),
                 nb::ModelJudgment::Equation(eqn_decl) => self.equation_cell(eqn_decl),
             };
-            field_ty_vs.push((name, (label, ty_v.clone())));
+            field_ty_vs.push((name, (label, ty_s, ty_v.clone())));
             self.ctx.scope.push(VarInContext::new(name, label, Some(ty_v.clone())));
             self.ctx.env =
                 self.ctx.env.snoc(TmV::neu(TmN::proj(self_var.clone(), name, label), ty_v));
@@ -460,7 +461,9 @@ impl<'a> Elaborator<'a> {
         self.reset_to(c);
         let field_tys: Row<_> = field_ty_vs
             .iter()
-            .map(|(name, (label, ty_v))| (*name, (*label, self.evaluator().quote_ty(ty_v))))
+            .map(|(name, (label, ty_s, ty_v))| {
+                (*name, (*label, self.evaluator().quote_field_ty(ty_s, ty_v)))
+            })
             .collect();
         let r_v = RecordV::new(self.ctx.env.clone(), field_tys.clone(), Dtry::empty());
         (TyS::record(field_tys), TyV::record(r_v))
@@ -495,6 +498,83 @@ pub fn demote_modality(modality: modal::Modality) -> nb::Modality {
     }
 }
 
+/// Demotes an object type to notebook type, if it has a notebook counterpart.
+pub fn demote_ob_type(ob_type: &ObType) -> Option<nb::ObType> {
+    match ob_type {
+        ObType::Discrete(name) => Some(nb::ObType::Basic(single_name(name)?)),
+        ObType::DiscreteTab(ob_type) => demote_tab_ob_type(ob_type),
+        ObType::Modal(app) => {
+            let mut demoted = nb::ObType::Basic(single_name(&app.arg)?);
+            for modality in &app.modalities {
+                demoted = nb::ObType::ModeApp {
+                    modality: demote_modality(*modality),
+                    ob_type: demoted.into(),
+                }
+            }
+            Some(demoted)
+        }
+    }
+}
+
+/// Demotes a morphism type to notebook type, if it has a notebook counterpart.
+pub fn demote_mor_type(mor_type: &MorType) -> Option<nb::MorType> {
+    match mor_type {
+        MorType::Discrete(Path::Id(name)) => {
+            Some(nb::MorType::Hom(nb::ObType::Basic(single_name(name)?).into()))
+        }
+        MorType::Discrete(Path::Seq(names)) => {
+            let demoted: Option<Vec<_>> =
+                names.iter().map(|name| Some(nb::MorType::Basic(single_name(name)?))).collect();
+            let mut demoted = demoted?;
+            if demoted.len() == 1 {
+                demoted.pop()
+            } else {
+                Some(nb::MorType::Composite(demoted))
+            }
+        }
+        MorType::DiscreteTab(mor_type) => demote_tab_mor_type(mor_type),
+        MorType::Modal(ShortPath::Zero(ob_type)) => {
+            Some(nb::MorType::Hom(demote_ob_type(&ob_type.clone().into())?.into()))
+        }
+        MorType::Modal(ShortPath::One(app)) => {
+            let mut demoted = nb::MorType::Basic(single_name(&app.arg)?);
+            for modality in &app.modalities {
+                demoted = nb::MorType::ModeApp {
+                    modality: demote_modality(*modality),
+                    mor_type: demoted.into(),
+                }
+            }
+            Some(demoted)
+        }
+    }
+}
+
+fn demote_tab_ob_type(ob_type: &discrete_tabulator::TabObType) -> Option<nb::ObType> {
+    match ob_type {
+        discrete_tabulator::TabObType::Basic(name) => Some(nb::ObType::Basic(single_name(name)?)),
+        discrete_tabulator::TabObType::Tabulator(mor_type) => {
+            Some(nb::ObType::Tabulator(demote_tab_mor_type(mor_type)?.into()))
+        }
+    }
+}
+
+fn demote_tab_mor_type(mor_type: &discrete_tabulator::TabMorType) -> Option<nb::MorType> {
+    match mor_type {
+        discrete_tabulator::TabMorType::Basic(name) => Some(nb::MorType::Basic(single_name(name)?)),
+        discrete_tabulator::TabMorType::Hom(ob_type) => {
+            Some(nb::MorType::Hom(demote_tab_ob_type(ob_type)?.into()))
+        }
+    }
+}
+
+/// Notebook types only support names with a single textual segment.
+fn single_name(name: &QualifiedName) -> Option<Ustr> {
+    match name.only() {
+        Some(NameSegment::Text(text)) => Some(text),
+        _ => None,
+    }
+}
+
 #[cfg(test)]
 mod test {
     use expect_test::{Expect, expect};
@@ -0,0 +1,615 @@
+//! Generate notebook documents from DoubleTT types.
+//!
+//! This is roughly the inverse of [notebook elaboration](super::notebook_elab):
+//! a record type is turned into a model document that can be opened in the
+//! CatColab editor. Fields of object, morphism, and identity type become object,
+//! morphism, and equation cells. A field whose type is a top-level record type,
+//! possibly with some of its objects specialized, becomes an instantiation of the
+//! document generated for that top-level type. All other fields of record type
+//! are flattened into the enclosing notebook, with dotted labels.
+//!
+//! Instantiations link to the documents of other top-level types through a
+//! [resolver](NotebookGenerator::with_resolver) supplied by the caller, which
+//! knows where those documents are stored. Since the generated documents refer
+//! to each other's UUIDs, the documents for all the top-level types should be
+//! produced by the same [`NotebookGenerator`].
+
+use catcolab_document_types::current as nb;
+use uuid::Uuid;
+
+use super::notebook_elab::{demote_modality, demote_mor_type, demote_ob_type};
+use super::{eval::*, prelude::*, stx::*, theory::*, toplevel::*, val::*};
+use crate::zero::QualifiedName;
+
+/// A source of UUIDs for generated notebooks.
+pub enum UuidSource {
+    /// Fresh, time-ordered UUIDs.
+    Fresh,
+    /// Sequential UUIDs starting after the given number, for reproducible output.
+    Sequential(u128),
+}
+
+impl UuidSource {
+    fn next(&mut self) -> Uuid {
+        match self {
+            UuidSource::Fresh => Uuid::now_v7(),
+            UuidSource::Sequential(n) => {
+                *n += 1;
+                Uuid::from_u128(*n)
+            }
+        }
+    }
+}
+
+/// Gets the notebook theory corresponding to a standard DoubleTT theory, if any.
+pub fn document_theory_id(theory: &Theory) -> Option<&'static str> {
+    match theory.name.to_string().as_str() {
+        "ThCategory" => Some("simple-olog"),
+        "ThSchema" => Some("simple-schema"),
+        "ThSignedCategory" => Some("causal-loop"),
+        "ThCategoryLinks" => Some("primitive-stock-flow"),
+        "ThSymMonoidalCategory" => Some("petri-net"),
+        _ => None,
+    }
+}
+
+/// Gets the standard DoubleTT theory corresponding to a notebook theory, if any.
+///
+/// This is the inverse of [`document_theory_id`].
+pub fn document_theory(theory_id: &str) -> Option<Theory> {
+    let name = match theory_id {
+        "simple-olog" => "ThCategory",
+        "simple-schema" => "ThSchema",
+        "causal-loop" => "ThSignedCategory",
+        "primitive-stock-flow" => "ThCategoryLinks",
+        "petri-net" => "ThSymMonoidalCategory",
+        _ => return None,
+    };
+    std_theories().remove(&QualifiedName::from(name))
+}
+
+/// Resolves the name of a top-level type to a reference to its document.
+pub type RefResolver = Box<dyn Fn(TopVarName) -> Option<nb::StableRef>>;
+
+/// Assignment of notebook names to the (possibly nested) fields of a record.
+type Layout = HashMap<QualifiedName, QualifiedName>;
+
+struct Generated {
+    layout: Layout,
+    content: nb::ModelDocumentContent,
+}
+
+/// Generator of notebook documents from DoubleTT types.
+pub struct NotebookGenerator {
+    uuids: UuidSource,
+    resolver: Option<RefResolver>,
+    documents: HashMap<TopVarName, Generated>,
+}
+
+impl NotebookGenerator {
+    /// Constructs a new notebook generator.
+    ///
+    /// Without a [resolver](Self::with_resolver), fields that would become
+    /// instantiations are rejected.
+    pub fn new(uuids: UuidSource) -> Self {
+        Self {
+            uuids,
+            resolver: None,
+            documents: HashMap::new(),
+        }
+    }
+
+    /// Sets the resolver used to link instantiations to documents.
+    ///
+    /// Instantiating a top-level type that the resolver cannot resolve is an
+    /// error.
+    pub fn with_resolver(
+        mut self,
+        resolver: impl Fn(TopVarName) -> Option<nb::StableRef> + 'static,
+    ) -> Self {
+        self.resolver = Some(Box::new(resolver));
+        self
+    }
+
+    /// Generates the document for a top-level type declaration.
+    ///
+    /// Documents are cached by name, so that instantiations in documents
+    /// generated later refer to the same UUIDs.
+    pub fn document(
+        &mut self,
+        toplevel: &Toplevel,
+        name: TopVarName,
+    ) -> Result<&nb::ModelDocumentContent, String> {
+        if !self.documents.contains_key(&name) {
+            let Some(TopDecl::Type(ty)) = toplevel.lookup(name) else {
+                return Err(format!("{name} is not a top-level type"));
+            };
+            let generated = self.generate(toplevel, &ty.theory, name.to_string(), &ty.val)?;
+            self.documents.insert(name, generated);
+        }
+        Ok(&self.documents[&name].content)
+    }
+
+    /// Generates a document with the given name from a record type.
+    ///
+    /// Precondition: `ty` must be valid in the empty context.
+    pub fn document_for_ty(
+        &mut self,
+        toplevel: &Toplevel,
+        theory: &Theory,
+        name: String,
+        ty: &TyV,
+    ) -> Result<nb::ModelDocumentContent, String> {
+        Ok(self.generate(toplevel, theory, name, ty)?.content)
+    }
+
+    fn generate(
+        &mut self,
+        toplevel: &Toplevel,
+        theory: &Theory,
+        name: String,
+        ty: &TyV,
+    ) -> Result<Generated, String> {
+        let Some(theory_id) = document_theory_id(theory) else {
+            return Err(format!("theory {theory} has no notebook counterpart"));
+        };
+        let TyV_::Record(_) = &**ty else {
+            return Err("expected a record type".to_string());
+        };
+        let (self_n, eval) = Evaluator::empty(toplevel).bind_self(ty.clone());
+        let self_v = eval.eta_neu(&self_n, ty);
+        let mut builder = NotebookBuilder {
+            toplevel,
+            eval,
+            theory: theory.clone(),
+            generator: self,
+            layout: Layout::new(),
+            cells: Vec::new(),
+        };
+        builder.fields(&QualifiedName::from(vec![]), "", &self_v, ty)?;
+
+        let mut notebook = nb::Notebook {
+            cell_contents: HashMap::new(),
+            cell_order: Vec::new(),
+        };
+        for content in builder.cells {
+            let id = builder.generator.uuids.next();
+            notebook.cell_order.push(id);
+            notebook.cell_contents.insert(id, nb::NotebookCell::Formal { id, content });
+        }
+        let content = nb::ModelDocumentContent {
+            name,
+            theory: theory_id.to_string(),
+            editor_variant: None,
+            notebook,
+            version: "2".to_string(),
+        };
+        Ok(Generated { layout: builder.layout, content })
+    }
+}
+
+/// State while generating a single notebook.
+struct NotebookBuilder<'a> {
+    toplevel: &'a Toplevel,
+    eval: Evaluator<'a>,
+    theory: Theory,
+    generator: &'a mut NotebookGenerator,
+    layout: Layout,
+    cells: Vec<nb::ModelJudgment>,
+}
+
+impl<'a> NotebookBuilder<'a> {
+    /// Allocates a fresh UUID for the declaration at the given field path.
+    fn declare(&mut self, path: QualifiedName) -> Uuid {
+        let id = self.generator.uuids.next();
+        self.layout.insert(path, QualifiedName::single(NameSegment::Uuid(id)));
+        id
+    }
+
+    fn fields(
+        &mut self,
+        prefix: &QualifiedName,
+        label_prefix: &str,
+        val: &TmV,
+        ty: &TyV,
+    ) -> Result<(), String> {
+        let TyV_::Record(r) = &**ty else {
+            unreachable!()
+        };
+        for (name, (label, field_ty_s)) in r.fields.iter() {
+            let path = prefix.snoc(*name);
+            let field_tm_v = self.eval.proj(val, *name, *label);
+            let field_ty_v = self.eval.field_ty(ty, val, *name);
+            let label = format!("{label_prefix}{label}");
+            self.field(path, label, &field_tm_v, field_ty_s, &field_ty_v)?;
+        }
+        Ok(())
+    }
+
+    fn field(
+        &mut self,
+        path: QualifiedName,
+        label: String,
+        val: &TmV,
+        ty_s: &TyS,
+        ty: &TyV,
+    ) -> Result<(), String> {
+        match &**ty {
+            TyV_::Object(ob_type) => {
+                let ob_type = demote_ob_type(ob_type)
+                    .ok_or_else(|| format!("object type of {label} is not supported"))?;
+                let id = self.declare(path);
+                self.cells
+                    .push(nb::ModelJudgment::Object(nb::ObDecl { name: label, id, ob_type }));
+            }
+            TyV_::Morphism(mor_type, dom, cod) => {
+                let dom = self.ob(dom, &self.theory.definition.src_type(mor_type))?;
+                let cod = self.ob(cod, &self.theory.definition.tgt_type(mor_type))?;
+                let mor_type = demote_mor_type(mor_type)
+                    .ok_or_else(|| format!("morphism type of {label} is not supported"))?;
+                let id = self.declare(path);
+                self.cells.push(nb::ModelJudgment::Morphism(nb::MorDecl {
+                    name: label,
+                    id,
+                    mor_type,
+                    dom: Some(dom),
+                    cod: Some(cod),
+                }));
+            }
+            TyV_::Id(mor_ty, lhs, rhs) => {
+                let TyV_::Morphism(mor_type, _, _) = &**mor_ty else {
+                    return Err(format!("equation {label} is not between morphisms"));
+                };
+                let lhs = self.mor(lhs, mor_type)?;
+                let rhs = self.mor(rhs, mor_type)?;
+                let id = self.declare(path);
+                self.cells.push(nb::ModelJudgment::Equation(nb::EqnDecl {
+                    name: label,
+                    id,
+                    lhs: Some(lhs),
+                    rhs: Some(rhs),
+                }));
+            }
+            TyV_::Record(r) => {
+                if let Some(instance) = self.instantiation(&path, &label, ty_s, r)? {
+                    self.cells.push(nb::ModelJudgment::Instantiation(instance));
+                } else {
+                    self.fields(&path, &format!("{label}."), val, ty)?;
+                }
+            }
+            // Fields with singleton or unit type are determined by the others.
+            TyV_::Sing(_, _) | TyV_::Unit => {}
+            TyV_::Meta(_) => return Err(format!("type of {label} is unknown")),
+        }
+        Ok(())
+    }
+
+    /// Tries to generate an instantiation for a field of record type.
+    ///
+    /// This succeeds when the field was declared with a top-level type of the
+    /// same theory, and all of the specializations are of objects in that type.
+    fn instantiation(
+        &mut self,
+        path: &QualifiedName,
+        label: &str,
+        ty_s: &TyS,
+        r: &RecordV,
+    ) -> Result<Option<nb::InstantiatedModel>, String> {
+        let Some(topname) = declared_top_type(ty_s) else {
+            return Ok(None);
+        };
+        match self.toplevel.lookup(topname) {
+            Some(TopDecl::Type(top_ty)) if top_ty.theory == self.theory => {}
+            _ => return Ok(None),
+        }
+        let stable_ref = self.generator.resolver.as_ref().and_then(|resolve| resolve(topname));
+        let Some(stable_ref) = stable_ref else {
+            return Err(format!("no document to link to for instantiation of {topname}"));
+        };
+        self.generator.document(self.toplevel, topname)?;
+        let inner_layout = &self.generator.documents[&topname].layout;
+
+        let mut specializations = Vec::new();
+        for (field_path, _, ty) in r.specializations.flatten() {
+            let TyV_::Sing(field_ty, ob) = &*ty else {
+                return Ok(None);
+            };
+            let TyV_::Object(ob_type) = &**field_ty else {
+                return Ok(None);
+            };
+            let Some(NameSegment::Uuid(field_id)) =
+                inner_layout.get(&field_path).and_then(|name| name.only())
+            else {
+                return Ok(None);
+            };
+            let Ok(ob) = self.ob(ob, ob_type) else {
+                return Ok(None);
+            };
+            specializations.push(nb::SpecializeModel {
+                id: Some(field_id.to_string()),
+                ob: Some(ob),
+            });
+        }
+
+        let id = self.generator.uuids.next();
+        let inner_layout = &self.generator.documents[&topname].layout;
+        for (inner_path, inner_name) in inner_layout {
+            let outer_path: Vec<_> =
+                path.segments().chain(inner_path.segments()).copied().collect();
+            let outer_name: Vec<_> = std::iter::once(NameSegment::Uuid(id))
+                .chain(inner_name.segments().copied())
+                .collect();
+            self.layout.insert(outer_path.into(), outer_name.into());
+        }
+        Ok(Some(nb::InstantiatedModel {
+            name: label.to_string(),
+            id,
+            model: Some(nb::Link {
+                stable_ref,
+                r#type: nb::LinkType::Instantiation,
+            }),
+            specializations,
+        }))
+    }
+
+    /// Looks up the notebook name of a neutral term.
+    fn name(&self, n: &TmN) -> Result<String, String> {
+        let path = n.to_qualified_name();
+        match self.layout.get(&path) {
+            Some(name) => Ok(name.serialize_string()),
+            None => Err(format!("{path} cannot be referenced from a notebook")),
+        }
+    }
+
+    /// Converts a term into a notebook object of the given object type.
+    fn ob(&self, val: &TmV, ob_type: &ObType) -> Result<nb::Ob, String> {
+        match &**val {
+            TmV_::Neu(n, _) => Ok(nb::Ob::Basic(self.name(n)?)),
+            TmV_::App(op, arg) => {
+                let NameSegment::Text(op_name) = op else {
+                    return Err(format!("operation {op} is not supported"));
+                };
+                let Some(ob_op) = self.theory.definition.basic_ob_op([*op].into()) else {
+                    return Err(format!("operation {op} is not in the theory"));
+                };
+                let arg = self.ob(arg, &self.theory.definition.ob_op_dom(&ob_op))?;
+                Ok(nb::Ob::App {
+                    op: nb::ObOp::Basic(*op_name),
+                    ob: Box::new(arg),
+                })
+            }
+            TmV_::List(elems) => {
+                let Some((modality, elem_type)) = ob_type.clone().mode_app() else {
+                    return Err(format!("expected an object of type {ob_type}"));
+                };
+                let objects: Result<Vec<_>, _> =
+                    elems.iter().map(|elem| self.ob(elem, &elem_type).map(Some)).collect();
+                Ok(nb::Ob::List {
+                    modality: demote_modality(modality),
+                    objects: objects?,
+                })
+            }
+            TmV_::Tab(mor) => {
+                let TmV_::Neu(n, _) = &**mor else {
+                    return Err("only generating morphisms can be tabulated".to_string());
+                };
+                Ok(nb::Ob::Tabulated(nb::Mor::Basic(self.name(n)?)))
+            }
+            _ => Err("term is not an object".to_string()),
+        }
+    }
+
+    /// Converts a term into a notebook morphism of the given morphism type.
+    fn mor(&self, val: &TmV, mor_type: &MorType) -> Result<nb::Mor, String> {
+        match &**val {
+            TmV_::Neu(n, _) => Ok(nb::Mor::Basic(self.name(n)?)),
+            TmV_::Id(ob) => {
+                let ob = self.ob(ob, &self.theory.definition.src_type(mor_type))?;
+                Ok(nb::Mor::Composite(Box::new(nb::path::Path::Id(ob))))
+            }
+            TmV_::Compose(_, _) => {
+                let mut mors = Vec::new();
+                self.composands(val, mor_type, &mut mors)?;
+                Ok(nb::Mor::Composite(Box::new(nb::path::Path::Seq(mors))))
+            }
+            _ => Err("term is not a morphism".to_string()),
+        }
+    }
+
+    fn composands(
+        &self,
+        val: &TmV,
+        mor_type: &MorType,
+        out: &mut Vec<nb::Mor>,
+    ) -> Result<(), String> {
+        match &**val {
+            TmV_::Compose(f, g) => {
+                self.composands(f, mor_type, out)?;
+                self.composands(g, mor_type, out)
+            }
+            _ => {
+                out.push(self.mor(val, mor_type)?);
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Gets the top-level type that a field was declared with, if any.
+fn declared_top_type(ty_s: &TyS) -> Option<TopVarName> {
+    match &**ty_s {
+        TyS_::TopVar(name) => Some(*name),
+        TyS_::Specialize(base, _) => declared_top_type(base),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use expect_test::{Expect, expect};
+    use ustr::ustr;
+
+    use super::*;
+    use crate::dbl::model::DblModelPrinter;
+    use crate::tt::{
+        modelgen::Model, notebook_elab::Elaborator, text_elab::*, theory::std_theories,
+    };
+
+    /// Links to the document for a top-level type by its name, which is how the
+    /// notebook elaborator resolves links in these tests.
+    fn link(name: TopVarName) -> Option<nb::StableRef> {
+        Some(nb::StableRef {
+            id: name.to_string(),
+            version: None,
+            server: "localhost".to_string(),
+        })
+    }
+
+    /// Elaborates the top-level declarations in the source.
+    fn elab_toplevel(src: &str) -> Toplevel {
+        let reporter = Reporter::new();
+        let toplevel = TT_PARSE_CONFIG
+            .with_parsed_top(src, reporter.clone(), |topntns| {
+                let mut toplevel = Toplevel::new(std_theories());
+                let mut topelab = TopElaborator::new(reporter.clone());
+                for topntn in topntns.iter() {
+                    if let Some(TopElabResult::Declaration(name, decl)) =
+                        topelab.elab(&toplevel, topntn)
+                    {
+                        toplevel.declarations.insert(name, decl);
+                    }
+                }
+                Some(toplevel)
+            })
+            .unwrap();
+        assert!(!reporter.errored());
+        toplevel
+    }
+
+    /// Generates notebooks for the named types, elaborates them back in order,
+    /// and prints the model generated by the last one.
+    fn round_trip(src: &str, names: &[&str], expected: Expect) {
+        let toplevel = elab_toplevel(src);
+        let mut generator = NotebookGenerator::new(UuidSource::Sequential(0)).with_resolver(link);
+        let mut nb_toplevel = Toplevel::new(std_theories());
+        let mut out = String::new();
+        for name in names {
+            let Some(TopDecl::Type(ty)) = toplevel.lookup(name_seg(*name)) else {
+                panic!("{name} should be a type");
+            };
+            let theory = ty.theory.clone();
+            let doc = generator.document(&toplevel, name_seg(*name)).unwrap();
+            let mut elab = Elaborator::new(theory.clone(), &nb_toplevel, ustr(name));
+            let (ty_s, ty_v) = elab.notebook(doc.notebook.formal_content());
+            assert!(elab.errors().is_empty());
+            let (model, ns) = Model::from_ty(&nb_toplevel, &theory.definition, &ty_v);
+            out = model.to_doc(&DblModelPrinter::new(), &ns).pretty().to_string();
+            nb_toplevel
+                .declarations
+                .insert(name_seg(*name), TopDecl::Type(Type::new(theory, ty_s, ty_v)));
+        }
+        expected.assert_eq(&out);
+    }
+
+    #[test]
+    fn instantiations() {
+        let src = "
+set_theory ThSchema
+type Arrow := [ src : Entity, tgt : Entity, f : (Hom Entity)[src, tgt] ]
+type Square := [
+    top : Arrow,
+    bottom : Arrow,
+    l : (Hom Entity)[top.src, bottom.src],
+    r : (Hom Entity)[top.tgt, bottom.tgt],
+    comm : (top.f * r == l * bottom.f)
+]
+type Composable := [
+    first : Arrow,
+    second : Arrow & [ .src : @sing first.tgt ],
+    g : (Hom Entity)[first.src, second.tgt]
+]
+";
+        round_trip(
+            src,
+            &["Arrow", "Square"],
+            expect![[r#"
+                model generated by 4 objects and 4 morphisms
+                top.src : Entity
+                top.tgt : Entity
+                bottom.src : Entity
+                bottom.tgt : Entity
+                top.f : top.src -> top.tgt : Hom Entity
+                bottom.f : bottom.src -> bottom.tgt : Hom Entity
+                l : top.src -> bottom.src : Hom Entity
+                r : top.tgt -> bottom.tgt : Hom Entity
+                top.f ⋅ r = l ⋅ bottom.f : (Hom Entity)[top.src, bottom.tgt]"#]],
+        );
+        round_trip(
+            src,
+            &["Arrow", "Composable"],
+            expect![[r#"
+                model generated by 3 objects and 3 morphisms
+                first.src : Entity
+                first.tgt : Entity
+                second.tgt : Entity
+                first.f : first.src -> first.tgt : Hom Entity
+                second.f : first.tgt -> second.tgt : Hom Entity
+                g : first.src -> second.tgt : Hom Entity"#]],
+        );
+    }
+
+    #[test]
+    fn instantiation_links() {
+        let src = "
+set_theory ThSchema
+type Arrow := [ src : Entity, tgt : Entity, f : (Hom Entity)[src, tgt] ]
+type Edge := [ src : Entity, tgt : Entity, f : (Hom Entity)[src, tgt] ]
+type Pair := [ e : Edge, a : Arrow ]
+";
+        let toplevel = elab_toplevel(src);
+
+        // Links go to the declared types, even when they are structurally equal.
+        let mut generator = NotebookGenerator::new(UuidSource::Sequential(0)).with_resolver(link);
+        let doc = generator.document(&toplevel, name_seg("Pair")).unwrap();
+        let mut linked: Vec<_> = doc
+            .notebook
+            .formal_content()
+            .filter_map(|judgment| match judgment {
+                nb::ModelJudgment::Instantiation(inst) => {
+                    Some((inst.name.clone(), inst.model.as_ref()?.stable_ref.id.clone()))
+                }
+                _ => None,
+            })
+            .collect();
+        linked.sort();
+        assert_eq!(linked, [("a".into(), "Arrow".into()), ("e".into(), "Edge".into())]);
+
+        // Without a document to link to, instantiations are rejected.
+        let mut generator = NotebookGenerator::new(UuidSource::Sequential(0));
+        let err = generator.document(&toplevel, name_seg("Pair")).map(|_| ()).unwrap_err();
+        expect!["no document to link to for instantiation of Edge"].assert_eq(&err);
+    }
+
+    #[test]
+    fn flattened_records() {
+        round_trip(
+            "
+set_theory ThSymMonoidalCategory
+type Reaction := [
+    a : Object,
+    b : Object,
+    inner : [ c : Object, r : (Hom Object)[@tensor [c], @tensor [c, c]] ],
+    s : (Hom Object)[@tensor [a, inner.c], @tensor [b]]
+]
+",
+            &["Reaction"],
+            expect![[r#"
+                model generated by 3 objects and 2 morphisms
+                a : Object
+                b : Object
+                inner.c : Object
+                inner.r : ⨂ [inner.c] -> ⨂ [inner.c, inner.c] : Hom Object
+                s : ⨂ [a, inner.c] -> ⨂ [b] : Hom Object"#]],
+        );
+    }
+}
@@ -31,6 +31,10 @@ impl fmt::Display for MetaVar {
 pub enum TyS_ {
     /// A reference to a top-level declaration.
     TopVar(TopVarName),
+    /// An application of a top-level parameterised type to arguments.
+    ///
+    /// Example syntax: `Stage[x]`.
+    TopApp(TopVarName, Vec<TmS>),
     /// Type constructor for object types.
     ///
     /// Example syntax: `Entity` (top-level constants are bound by the elaborator to
@@ -110,6 +114,11 @@ impl TyS {
         Self(Rc::new(TyS_::TopVar(name)))
     }
 
+    /// Smart constructor for [TyS], [TyS_::TopApp] case.
+    pub fn topapp(name: TopVarName, args: Vec<TmS>) -> Self {
+        Self(Rc::new(TyS_::TopApp(name, args)))
+    }
+
     /// Smart constructor for [TyS], [TyS_::Object] case.
     pub fn object(object_type: ObType) -> Self {
         Self(Rc::new(TyS_::Object(object_type)))
@@ -158,6 +167,9 @@ impl ToDoc for TyS {
     fn to_doc<'a>(&self) -> D<'a> {
         match &**self {
             TyS_::TopVar(name) => t(format!("{}", name)),
+            TyS_::TopApp(name, args) => {
+                t(format!("{}", name)) + tuple(args.iter().map(|arg| arg.to_doc()))
+            }
             TyS_::Object(ob_type) => t(format!("{}", ob_type)),
             TyS_::Morphism(mor_type, dom, cod) => {
                 mor_type.to_doc().parens() + tuple([dom.to_doc(), cod.to_doc()])
@@ -7,11 +7,13 @@ use fnotation::{ParseConfig, parser::Prec};
 use tattle::declare_error;
 
 use super::{
-    context::*, eval::*, modelgen::*, prelude::*, stx::*, theory::*, toplevel::*, val::*, wd::*,
+    context::*, eval::*, modelgen::*, notebook_gen::*, prelude::*, stx::*, theory::*, toplevel::*,
+    val::*, wd::*,
 };
 use crate::{
-    dbl::model::DblModelPrinter,
-    zero::{QualifiedName, name},
+    dbl::model::{DblModelPrinter, InvalidDblModel},
+    stdlib::formats::drawing::{GraphFormat, uwd_drawing},
+    zero::{Namespace, QualifiedName, name},
 };
 
 /// Parser config for DoubleTT.
@@ -24,7 +26,22 @@ pub const TT_PARSE_CONFIG: ParseConfig = ParseConfig::new(
         ("==", Prec::nonassoc(30)),
     ],
     &[":", ":=", "&", "Unit", "Hom", "*", "=="],
-    &["type", "def", "syn", "chk", "norm", "generate", "uwd", "set_theory"],
+    &[
+        "type",
+        "def",
+        "syn",
+        "chk",
+        "norm",
+        "generate",
+        "pullback",
+        "notebook",
+        "uwd",
+        "set_theory",
+        "dot",
+        "mermaid",
+        "uwd_dot",
+        "uwd_mermaid",
+    ],
 );
 
 /// The result of elaborating a top-level statement.
@@ -41,19 +58,37 @@ pub enum TopElabResult {
 pub struct TopElaborator {
     current_theory: Option<Theory>,
     reporter: Reporter,
+    notebooks: NotebookGenerator,
 }
 
 impl TopElaborator {
     /// Constructs a context for top-level elaboration.
     pub fn new(reporter: Reporter) -> Self {
-        Self { current_theory: None, reporter }
+        Self {
+            current_theory: None,
+            reporter,
+            notebooks: NotebookGenerator::new(UuidSource::Fresh),
+        }
     }
 
-    fn bare_def<'c>(&self, n: &FNtn<'c>) -> Option<(TopVarName, &'c FNtn<'c>)> {
+    /// Sets the source of UUIDs for notebooks generated by `notebook` commands.
+    pub fn notebook_uuids(mut self, uuids: UuidSource) -> Self {
+        self.notebooks = NotebookGenerator::new(uuids);
+        self
+    }
+
+    fn param_def<'c>(
+        &self,
+        n: &FNtn<'c>,
+    ) -> Option<(TopVarName, Option<&'c [&'c FNtn<'c>]>, &'c FNtn<'c>)> {
         match n.ast0() {
-            App2(L(_, Keyword(":=")), L(_, Var(name)), tn) => {
-                Some((NameSegment::Text(ustr(name)), tn))
-            }
+            App2(L(_, Keyword(":=")), head_n, tn) => match head_n.ast0() {
+                App1(L(_, Var(name)), L(_, Tuple(args))) => {
+                    Some((name_seg(*name), Some(args.as_slice()), tn))
+                }
+                Var(name) => Some((name_seg(*name), None, tn)),
+                _ => None,
+            },
             _ => None,
         }
     }
@@ -117,17 +152,30 @@ impl TopElaborator {
             },
             "type" => {
                 let theory = self.get_theory(tn.loc)?;
-                let (name, ty_n) = self.bare_def(tn.body).or_else(|| {
+                let (name, args_n, ty_n) = self.param_def(tn.body).or_else(|| {
                     self.error(
                         tn.loc,
                         "unknown syntax for type declaration, expected <name> := <type>",
                     )
                 })?;
-                let (ty_s, ty_v) = self.elaborator(&theory, toplevel).ty(ty_n);
-                Some(TopElabResult::Declaration(
-                    name,
-                    TopDecl::Type(Type::new(theory.clone(), ty_s, ty_v)),
-                ))
+                match args_n {
+                    Some(args_n) => {
+                        let mut elab = self.elaborator(&theory, toplevel);
+                        let args_stx = elab.bindings(args_n)?;
+                        let (body_s, _) = elab.ty(ty_n);
+                        Some(TopElabResult::Declaration(
+                            name,
+                            TopDecl::TypeDef(TypeDef::new(theory.clone(), args_stx, body_s)),
+                        ))
+                    }
+                    None => {
+                        let (ty_s, ty_v) = self.elaborator(&theory, toplevel).ty(ty_n);
+                        Some(TopElabResult::Declaration(
+                            name,
+                            TopDecl::Type(Type::new(theory.clone(), ty_s, ty_v)),
+                        ))
+                    }
+                }
             }
             "def" => {
                 let theory = self.get_theory(tn.loc)?;
@@ -140,22 +188,12 @@ impl TopElaborator {
                 match args_n {
                     Some(args_n) => {
                         let mut elab = self.elaborator(&theory, toplevel);
-                        let mut args_stx = IndexMap::new();
-                        for arg_n in args_n {
-                            let (name, label, ty_s, ty_v) = elab.binding(arg_n)?;
-                            args_stx.insert(name, (label, ty_s));
-                            elab.intro(name, label, Some(ty_v));
-                        }
+                        let args_stx = elab.bindings(args_n)?;
                         let (ret_ty_s, ret_ty_v) = elab.ty(ty_n);
                         let (body_s, _) = elab.chk(&ret_ty_v, tm_n);
                         Some(TopElabResult::Declaration(
                             name,
-                            TopDecl::Def(Def::new(
-                                theory.clone(),
-                                args_stx.into(),
-                                ret_ty_s,
-                                body_s,
-                            )),
+                            TopDecl::Def(Def::new(theory.clone(), args_stx, ret_ty_s, body_s)),
                         ))
                     }
                     None => {
@@ -216,12 +254,30 @@ impl TopElaborator {
                 let theory = self.get_theory(tn.loc)?;
                 let mut elab = self.elaborator(&theory, toplevel);
                 let (_, ty_v) = elab.ty(tn.body);
-                let Some(uwd) = record_to_uwd(&ty_v) else {
+                let Some(uwd) = record_to_uwd(toplevel, &ty_v) else {
                     return self.error(tn.loc, "expected a record type");
                 };
                 let out = uwd.to_doc().0.pretty(77).to_string().replace("\n", "\n#/ ");
                 Some(TopElabResult::Output(out))
             }
+            "uwd_dot" | "uwd_mermaid" => {
+                let theory = self.get_theory(tn.loc)?;
+                let mut elab = self.elaborator(&theory, toplevel);
+                let (_, ty_v) = elab.ty(tn.body);
+                let Some(uwd) = record_to_uwd(toplevel, &ty_v) else {
+                    return self.error(tn.loc, "expected a record type");
+                };
+                let out = uwd_drawing(&uwd).render(graph_format(tn.name));
+                Some(TopElabResult::Output(out.replace("\n", "\n#/ ")))
+            }
+            "dot" | "mermaid" => {
+                let theory = self.get_theory(tn.loc)?;
+                let mut elab = self.elaborator(&theory, toplevel);
+                let (_, ty_v) = elab.ty(tn.body);
+                let (model, ns) = Model::from_ty(toplevel, &theory.definition, &ty_v);
+                let out = model.to_drawing(&ns).render(graph_format(tn.name));
+                Some(TopElabResult::Output(out.replace("\n", "\n#/ ")))
+            }
             "generate" => {
                 let theory = self.get_theory(tn.loc)?;
                 let mut elab = self.elaborator(&theory, toplevel);
@@ -232,11 +288,88 @@ impl TopElaborator {
                 let out = out.trim().replace("\n", "\n#/ ");
                 Some(TopElabResult::Output(out))
             }
+            "pullback" => {
+                let theory = self.get_theory(tn.loc)?;
+                let App1(L(_, Var(dom_name)), ty_n) = tn.body.ast0() else {
+                    return self.error(tn.loc, "expected <THEORY_NAME> <type>");
+                };
+                let Some(dom) = toplevel.theory_library.get(&name(*dom_name)) else {
+                    return self.error(tn.loc, format!("{dom_name} not found"));
+                };
+                let Some(map) = std_theory_maps().remove(&(dom.name.clone(), theory.name.clone()))
+                else {
+                    return self.error(tn.loc, format!("no theory map from {dom} to {theory}"));
+                };
+                let (TheoryDef::Discrete(dom_th), TheoryDef::Discrete(cod_th)) =
+                    (&dom.definition, &theory.definition)
+                else {
+                    return self.error(tn.loc, "pullback is only supported for discrete theories");
+                };
+                let mut elab = self.elaborator(&theory, toplevel);
+                let (_, ty_v) = elab.ty(ty_n);
+                let (model, ns) = Model::from_ty(toplevel, &theory.definition, &ty_v);
+                let model = model.as_discrete()?;
+                match model.pull_back(&map.functor_into(&cod_th.0), dom_th.clone()) {
+                    Ok(model) => {
+                        let printer = DblModelPrinter::new().include_summary(true);
+                        let model = Model::Discrete(Box::new(model));
+                        let out = model.to_doc(&printer, &ns).0.pretty(77).to_string();
+                        Some(TopElabResult::Output(out.trim().replace("\n", "\n#/ ")))
+                    }
+                    Err(errors) => {
+                        let errors: Vec<_> =
+                            errors.iter().map(|err| pullback_error(err, &ns)).collect();
+                        self.error(tn.loc, format!("pullback failed: {}", errors.join(", ")))
+                    }
+                }
+            }
+            "notebook" => {
+                let Var(name) = tn.body.ast0() else {
+                    return self.error(tn.loc, "expected the name of a top-level type");
+                };
+                match self.notebooks.document(toplevel, name_seg(*name)) {
+                    Ok(doc) => match serde_json::to_value(doc)
+                        .and_then(|v| serde_json::to_string_pretty(&v))
+                    {
+                        Ok(json) => Some(TopElabResult::Output(json.replace("\n", "\n#/ "))),
+                        Err(err) => {
+                            self.error(tn.loc, format!("could not serialize notebook: {err}"))
+                        }
+                    },
+                    Err(msg) => self.error(tn.loc, msg),
+                }
+            }
             _ => self.error(tn.loc, "unknown toplevel declaration"),
         }
     }
 }
 
+/// Graph format for a drawing command.
+fn graph_format(command: &str) -> GraphFormat {
+    if command.ends_with("dot") {
+        GraphFormat::Dot
+    } else {
+        GraphFormat::Mermaid
+    }
+}
+
+/// Describes a failure of a model to pull back along a theory map.
+fn pullback_error(err: &InvalidDblModel, ns: &Namespace) -> String {
+    match err {
+        InvalidDblModel::ObType(x) => {
+            format!("type of {} has several preimages", ns.label_string(x))
+        }
+        InvalidDblModel::MorType(f) => {
+            format!("type of {} has several preimages", ns.label_string(f))
+        }
+        InvalidDblModel::Dom(f) => format!("domain of {} is not pulled back", ns.label_string(f)),
+        InvalidDblModel::Cod(f) => {
+            format!("codomain of {} is not pulled back", ns.label_string(f))
+        }
+        err => format!("{err:?}"),
+    }
+}
+
 /// Text-based elaborator of types.
 pub struct Elaborator<'a> {
     theory: Theory,
@@ -363,6 +496,45 @@ impl<'a> Elaborator<'a> {
         }
     }
 
+    /// Elaborates a list of bindings, introducing each into scope in turn.
+    fn bindings(&mut self, ns: &[&FNtn]) -> Option<Row<TyS>> {
+        let mut bindings = Row::empty();
+        for n in ns {
+            let (name, label, ty_s, ty_v) = self.binding(n)?;
+            bindings.insert(name, label, ty_s);
+            self.intro(name, label, Some(ty_v));
+        }
+        Some(bindings)
+    }
+
+    /// Elaborates the arguments to a toplevel declaration with parameters.
+    ///
+    /// Returns the syntax for the arguments together with the environment in
+    /// which to evaluate the body of the declaration.
+    fn args(
+        &mut self,
+        tv: TopVarName,
+        params: &Row<TyS>,
+        args_n: &[&FNtn],
+    ) -> Option<(Vec<TmS>, Env)> {
+        if args_n.len() != params.len() {
+            return self.error(format!(
+                "wrong number of args for {tv}, expected {}, got {}",
+                params.len(),
+                args_n.len()
+            ));
+        }
+        let mut arg_stxs = Vec::new();
+        let mut env = Env::nil();
+        for (arg_n, (_, (_, arg_ty_s))) in args_n.iter().zip(params.iter()) {
+            let arg_ty_v = self.evaluator().with_env(env.clone()).eval_ty(arg_ty_s);
+            let (arg_s, arg_v) = self.chk(&arg_ty_v, arg_n);
+            arg_stxs.push(arg_s);
+            env = env.snoc(arg_v);
+        }
+        Some((arg_stxs, env))
+    }
+
     fn lookup_ty(&mut self, name: VarName) -> (TyS, TyV) {
         let qname = QualifiedName::single(name);
         if let Some(ob_type) = self.theory().basic_ob_type(qname) {
@@ -379,6 +551,9 @@ impl<'a> Elaborator<'a> {
                         ))
                     }
                 }
+                TopDecl::TypeDef(_) => {
+                    self.ty_error(format!("{name} must be applied to arguments"))
+                }
                 TopDecl::Def(_) | TopDecl::DefConst(_) => {
                     self.ty_error(format!("{name} refers to a term not a type"))
                 }
@@ -459,6 +634,25 @@ impl<'a> Elaborator<'a> {
                 let (tm_s, tm_v, ty_v) = elab.syn(tm_n);
                 (TyS::sing(elab.evaluator().quote_ty(&ty_v), tm_s), TyV::sing(ty_v, tm_v))
             }
+            App1(L(_, Var(tv)), L(_, Tuple(args_n)))
+                if matches!(elab.toplevel.lookup(name_seg(*tv)), Some(TopDecl::TypeDef(_))) =>
+            {
+                let tv = name_seg(*tv);
+                let Some(TopDecl::TypeDef(d)) = elab.toplevel.lookup(tv) else {
+                    unreachable!()
+                };
+                if d.theory != elab.theory {
+                    let msg = format!(
+                        "{tv} refers to a type in theory {}, expected a type in theory {}",
+                        d.theory, elab.theory
+                    );
+                    return elab.ty_error(msg);
+                }
+                let Some((arg_stxs, env)) = elab.args(tv, &d.args, args_n) else {
+                    return elab.ty_hole();
+                };
+                (TyS::topapp(tv, arg_stxs), elab.evaluator().with_env(env).eval_ty(&d.body))
+            }
             App1(mt_n, L(_, Tuple(domcod_n))) => {
                 let [dom_n, cod_n] = domcod_n.as_slice() else {
                     return elab.ty_error("expected two arguments for morphism type");
@@ -471,8 +665,11 @@ impl<'a> Elaborator<'a> {
                 (TyS::morphism(mt.clone(), dom_s, cod_s), TyV::morphism(mt.clone(), dom_v, cod_v))
             }
             Tuple(field_ns) => {
-                let mut field_ty_vs = Vec::<(FieldName, (LabelSegment, TyV))>::new();
+                let mut field_ty_vs = Vec::<(FieldName, (LabelSegment, TyS, TyV))>::new();
                 let mut failed = false;
+                // The record closes over the environment *outside* of `self`,
+                // because evaluating a field type binds `self` afresh.
+                let env = elab.ctx.env.clone();
                 let self_var = elab.intro(name_seg("self"), label_seg("self"), None).unwrap_neu();
                 let c = elab.checkpoint();
                 for field_n in field_ns.iter() {
@@ -486,8 +683,8 @@ impl<'a> Elaborator<'a> {
                         failed = true;
                         continue;
                     };
-                    let (_, ty_v) = elab.ty(ty_n);
-                    field_ty_vs.push((name, (label, ty_v.clone())));
+                    let (ty_s, ty_v) = elab.ty(ty_n);
+                    field_ty_vs.push((name, (label, ty_s, ty_v.clone())));
                     elab.ctx.push_scope(name, label, Some(ty_v.clone()));
                     elab.ctx.env =
                         elab.ctx.env.snoc(TmV::neu(TmN::proj(self_var.clone(), name, label), ty_v));
@@ -498,9 +695,11 @@ impl<'a> Elaborator<'a> {
                 elab.reset_to(c);
                 let field_tys: Row<_> = field_ty_vs
                     .iter()
-                    .map(|(name, (label, ty_v))| (*name, (*label, elab.evaluator().quote_ty(ty_v))))
+                    .map(|(name, (label, ty_s, ty_v))| {
+                        (*name, (*label, elab.evaluator().quote_field_ty(ty_s, ty_v)))
+                    })
                     .collect();
-                let r_v = RecordV::new(elab.ctx.env.clone(), field_tys.clone(), Dtry::empty());
+                let r_v = RecordV::new(env, field_tys.clone(), Dtry::empty());
                 (TyS::record(field_tys), TyV::record(r_v))
             }
             App2(L(_, Keyword("&")), ty_n, L(_, Tuple(specialization_ns))) => {
@@ -565,7 +764,9 @@ impl<'a> Elaborator<'a> {
             )
         } else if let Some(d) = self.toplevel.lookup(name) {
             match d {
-                TopDecl::Type(_) => self.syn_error(format!("{name} refers type, not term")),
+                TopDecl::Type(_) | TopDecl::TypeDef(_) => {
+                    self.syn_error(format!("{name} refers type, not term"))
+                }
                 TopDecl::DefConst(d) => (TmS::topvar(name), d.val.clone(), d.ty.clone()),
                 TopDecl::Def(_) => self.syn_error(format!("{name} must be applied to arguments")),
             }
@@ -670,21 +871,9 @@ impl<'a> Elaborator<'a> {
                 let Some(TopDecl::Def(d)) = elab.toplevel.lookup(tv) else {
                     return elab.syn_error(format!("no such toplevel def {tv}"));
                 };
-                let mut arg_stxs = Vec::new();
-                let mut env = Env::nil();
-                if args_n.len() != d.args.len() {
-                    return elab.syn_error(format!(
-                        "wrong number of args for {tv}, expected {}, got {}",
-                        d.args.len(),
-                        args_n.len()
-                    ));
-                }
-                for (arg_n, (_, (_, arg_ty_s))) in args_n.iter().zip(d.args.iter()) {
-                    let arg_ty_v = elab.evaluator().with_env(env.clone()).eval_ty(arg_ty_s);
-                    let (arg_s, arg_v) = elab.chk(&arg_ty_v, arg_n);
-                    arg_stxs.push(arg_s);
-                    env = env.snoc(arg_v);
-                }
+                let Some((arg_stxs, env)) = elab.args(tv, &d.args, args_n) else {
+                    return elab.syn_hole();
+                };
                 let eval = elab.evaluator().with_env(env.clone());
                 (TmS::topapp(tv, arg_stxs), eval.eval_tm(&d.body), eval.eval_ty(&d.ret_ty))
             }
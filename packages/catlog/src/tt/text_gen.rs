@@ -0,0 +1,477 @@
+//! Generate DoubleTT source text from notebook documents.
+//!
+//! This is roughly the inverse of [notebook generation](super::notebook_gen): a
+//! model document, together with the documents that it instantiates, is
+//! elaborated by the [notebook elaborator](super::notebook_elab) and the
+//! resulting record types are printed as `.dbltt` source, one type declaration
+//! per document.
+//!
+//! Labels in notebooks are arbitrary strings, so they need not be valid
+//! identifiers, nor even be unique. Before printing, the labels of each
+//! document are sanitized and deduplicated, yielding a [`Namespace`] through
+//! which all references to fields are printed.
+
+use std::collections::HashSet;
+
+use catcolab_document_types::current as nb;
+
+use super::notebook_elab::Elaborator;
+use super::notebook_gen::document_theory;
+use super::text_elab::TT_PARSE_CONFIG;
+use super::{eval::*, prelude::*, stx::*, theory::*, toplevel::*, val::*};
+use crate::zero::{Namespace, QualifiedName};
+
+/// Converts a model document into DoubleTT source text.
+///
+/// The documents instantiated by the model, directly or indirectly, must be
+/// given in `dependencies`, keyed by their reference IDs. They are declared
+/// before the model itself, which is declared last.
+pub fn notebook_to_text(
+    doc: &nb::ModelDocumentContent,
+    dependencies: &HashMap<String, nb::ModelDocumentContent>,
+) -> Result<String, String> {
+    let mut generator = TextGenerator {
+        dependencies,
+        toplevel: Toplevel::new(std_theories()),
+        names: HashMap::new(),
+        visiting: HashSet::new(),
+        theory: None,
+        out: String::new(),
+    };
+    generator.declare(None, doc)?;
+    Ok(generator.out)
+}
+
+struct TextGenerator<'a> {
+    dependencies: &'a HashMap<String, nb::ModelDocumentContent>,
+    toplevel: Toplevel,
+    /// Names of the declared types, keyed by reference ID.
+    names: HashMap<Ustr, String>,
+    visiting: HashSet<Ustr>,
+    theory: Option<Theory>,
+    out: String,
+}
+
+impl TextGenerator<'_> {
+    fn declare(
+        &mut self,
+        ref_id: Option<Ustr>,
+        doc: &nb::ModelDocumentContent,
+    ) -> Result<(), String> {
+        for judgment in doc.notebook.formal_content() {
+            let nb::ModelJudgment::Instantiation(i_decl) = judgment else {
+                continue;
+            };
+            let Some(link) = &i_decl.model else {
+                continue;
+            };
+            let dep_id = ustr(&link.stable_ref.id);
+            if self.names.contains_key(&dep_id) {
+                continue;
+            }
+            let Some(dep) = self.dependencies.get(dep_id.as_str()) else {
+                return Err(format!("instantiated model {dep_id} was not provided"));
+            };
+            if !self.visiting.insert(dep_id) {
+                return Err(format!("model {dep_id} instantiates itself"));
+            }
+            self.declare(Some(dep_id), dep)?;
+            self.visiting.remove(&dep_id);
+        }
+
+        let Some(theory) = document_theory(&doc.theory) else {
+            return Err(format!("notebook theory {} has no DoubleTT counterpart", doc.theory));
+        };
+        let mut elab = Elaborator::new(theory.clone(), &self.toplevel, ref_id.unwrap_or_default());
+        let (ty_s, ty_v) = elab.notebook(doc.notebook.formal_content());
+        if let Some(error) = elab.errors().first() {
+            return Err(format!("model {} failed to elaborate: {error:?}", doc.name));
+        }
+
+        let name = fresh_ident(&doc.name, |s| {
+            self.names.values().any(|name| name == s)
+                || theory.definition.basic_ob_type(QualifiedName::from(s)).is_some()
+        });
+        let printer = Printer {
+            theory: &theory.definition,
+            names: &self.names,
+            namespace: namespace(&self.toplevel, &ty_v),
+        };
+        let TyS_::Record(fields) = &*ty_s else {
+            unreachable!("notebooks should elaborate to record types")
+        };
+        let decl = t(format!("type {name} := ")) + printer.record(fields)?;
+
+        if !self.out.is_empty() {
+            self.out.push('\n');
+        }
+        if self.theory.as_ref() != Some(&theory) {
+            self.out.push_str(&format!("set_theory {}\n\n", theory.name));
+            self.theory = Some(theory.clone());
+        }
+        self.out.push_str(&format!("{}\n", decl.pretty()));
+        if let Some(ref_id) = ref_id {
+            self.names.insert(ref_id, name);
+            self.toplevel
+                .declarations
+                .insert(NameSegment::Text(ref_id), TopDecl::Type(Type::new(theory, ty_s, ty_v)));
+        }
+        Ok(())
+    }
+}
+
+/// Constructs a namespace of sanitized labels for the fields of a record type.
+fn namespace(toplevel: &Toplevel, ty: &TyV) -> Namespace {
+    let (self_n, eval) = Evaluator::empty(toplevel).bind_self(ty.clone());
+    let self_v = eval.eta_neu(&self_n, ty);
+    field_namespace(&eval, &self_v, ty)
+}
+
+fn field_namespace(eval: &Evaluator, val: &TmV, ty: &TyV) -> Namespace {
+    let mut namespace = Namespace::new_for_uuid();
+    let TyV_::Record(r) = &**ty else {
+        return namespace;
+    };
+    let mut taken = HashSet::new();
+    for (name, (label, _)) in r.fields.iter() {
+        if let NameSegment::Uuid(uuid) = name {
+            let ident = fresh_ident(&label.to_string(), |s| taken.contains(s));
+            namespace.set_label(*uuid, label_seg(ident.as_str()));
+            taken.insert(ident);
+        }
+        let field_ty = eval.field_ty(ty, val, *name);
+        if let TyV_::Record(_) = &*field_ty {
+            let field_val = eval.proj(val, *name, *label);
+            namespace.add_inner(*name, field_namespace(eval, &field_val, &field_ty));
+        }
+    }
+    namespace
+}
+
+/// Turns a label into an identifier that is not a keyword and not taken.
+fn fresh_ident(label: &str, is_taken: impl Fn(&str) -> bool) -> String {
+    let mut base: String = label
+        .trim()
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '_' })
+        .collect();
+    if !base.starts_with(|c: char| c.is_alphabetic() || c == '_') {
+        base.insert(0, '_');
+    }
+    let is_free =
+        |s: &str| !(TT_PARSE_CONFIG.is_keyword(s) || TT_PARSE_CONFIG.is_toplevel(s) || is_taken(s));
+    if is_free(&base) {
+        return base;
+    }
+    (2..).map(|i| format!("{base}_{i}")).find(|s| is_free(s)).unwrap()
+}
+
+/// Printer for the syntax of a record type elaborated from a notebook.
+///
+/// Field types are expected to be in the scope of `self`.
+struct Printer<'a> {
+    theory: &'a TheoryDef,
+    names: &'a HashMap<Ustr, String>,
+    namespace: Namespace,
+}
+
+impl Printer<'_> {
+    fn record<'a>(&self, fields: &Row<TyS>) -> Result<D<'a>, String> {
+        let fields = fields
+            .iter()
+            .map(|(name, (_, ty))| {
+                let ty = self.ty(&[*name], ty)?;
+                Ok(binop(t(":"), t(self.label(&[*name])?), ty))
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        Ok(t("[")
+            + (hardline() + intersperse(fields, t(",") + hardline())).indented()
+            + hardline()
+            + t("]"))
+    }
+
+    /// Prints the type of the field at the given path.
+    fn ty<'a>(&self, path: &[NameSegment], ty: &TyS) -> Result<D<'a>, String> {
+        match &**ty {
+            TyS_::TopVar(NameSegment::Text(ref_id)) if self.names.contains_key(ref_id) => {
+                Ok(t(self.names[ref_id].clone()))
+            }
+            TyS_::Object(ob_type) => self.ob_type(ob_type),
+            TyS_::Morphism(mor_type, dom, cod) => {
+                Ok(self.mor_type(mor_type)? + tuple([self.tm(dom, false)?, self.tm(cod, false)?]))
+            }
+            TyS_::Sing(_, tm) => Ok(unop(t("@sing"), self.tm(tm, true)?)),
+            TyS_::Id(_, lhs, rhs) => {
+                Ok(binop(t("=="), self.tm(lhs, false)?, self.tm(rhs, false)?).parens())
+            }
+            TyS_::Specialize(base, specializations) => {
+                let specializations = specializations
+                    .iter()
+                    .map(|(spec_path, ty)| {
+                        let mut full_path = path.to_vec();
+                        full_path.extend(spec_path.iter().map(|(name, _)| *name));
+                        let labels = self.labels(&full_path)?;
+                        let spec_label: String =
+                            labels[path.len()..].iter().map(|label| format!(".{label}")).collect();
+                        Ok(match &**ty {
+                            TyS_::Sing(_, tm) => binop(t(":="), t(spec_label), self.tm(tm, false)?),
+                            _ => binop(t(":"), t(spec_label), self.ty(&full_path, ty)?),
+                        })
+                    })
+                    .collect::<Result<Vec<_>, String>>()?;
+                Ok(binop(t("&"), self.ty(path, base)?, tuple(specializations)))
+            }
+            TyS_::Unit => Ok(t("Unit")),
+            _ => Err(format!("type {ty} has no textual counterpart")),
+        }
+    }
+
+    /// Prints a term, with parentheses if it is compound and `atomic` is set.
+    fn tm<'a>(&self, tm: &TmS, atomic: bool) -> Result<D<'a>, String> {
+        let parens = |d: D<'a>| if atomic { d.parens() } else { d };
+        match &**tm {
+            TmS_::Var(..) | TmS_::Proj(..) => Ok(t(self.label(&self.field_path(tm)?)?)),
+            TmS_::Id(ob) => Ok(unop(t("@id"), self.tm(ob, true)?).parens()),
+            TmS_::Tab(mor) => Ok(unop(t("@tab"), self.tm(mor, true)?).parens()),
+            TmS_::Compose(f, g) => Ok(parens(binop(t("*"), self.tm(f, true)?, self.tm(g, true)?))),
+            TmS_::ObApp(name, x) => Ok(parens(unop(t(format!("@{name}")), self.tm(x, true)?))),
+            TmS_::List(elems) => Ok(tuple(
+                elems.iter().map(|elem| self.tm(elem, false)).collect::<Result<Vec<_>, _>>()?,
+            )),
+            _ => Err(format!("term {tm} has no textual counterpart")),
+        }
+    }
+
+    /// Gets the path of the field of `self` that a term refers to.
+    fn field_path(&self, tm: &TmS) -> Result<Vec<NameSegment>, String> {
+        match &**tm {
+            TmS_::Var(_, name, _) if *name == name_seg("self") => Ok(Vec::new()),
+            TmS_::Proj(tm, field, _) => {
+                let mut path = self.field_path(tm)?;
+                path.push(*field);
+                Ok(path)
+            }
+            _ => Err(format!("term {tm} is not a field")),
+        }
+    }
+
+    fn labels(&self, path: &[NameSegment]) -> Result<Vec<String>, String> {
+        let name = QualifiedName::from(path.to_vec());
+        let Some(label) = self.namespace.label(&name) else {
+            return Err(format!("field {name} has no label"));
+        };
+        Ok(label.segments().map(|segment| segment.to_string()).collect())
+    }
+
+    fn label(&self, path: &[NameSegment]) -> Result<String, String> {
+        Ok(self.labels(path)?.join("."))
+    }
+
+    fn ob_type<'a>(&self, ob_type: &ObType) -> Result<D<'a>, String> {
+        let s = ob_type.to_string();
+        if self.theory.basic_ob_type(QualifiedName::from(s.as_str())).as_ref() == Some(ob_type) {
+            Ok(t(s))
+        } else {
+            Err(format!("object type {ob_type} has no textual counterpart"))
+        }
+    }
+
+    fn mor_type<'a>(&self, mor_type: &MorType) -> Result<D<'a>, String> {
+        let src = self.theory.src_type(mor_type);
+        if self.theory.hom_type(src.clone()).as_ref() == Some(mor_type) {
+            return Ok((t("Hom") + s() + self.ob_type(&src)?).parens());
+        }
+        let s = mor_type.to_string();
+        if self.theory.basic_mor_type(QualifiedName::from(s.as_str())).as_ref() == Some(mor_type) {
+            Ok(t(s))
+        } else {
+            Err(format!("morphism type {mor_type} has no textual counterpart"))
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use expect_test::{Expect, expect};
+    use std::cell::RefCell;
+    use std::fs;
+
+    use super::*;
+    use crate::tt::{
+        batch::{self, BatchOutput},
+        notebook_gen::{NotebookGenerator, UuidSource},
+        text_elab::*,
+    };
+
+    /// Converts a document to text, which should then elaborate successfully.
+    fn to_text(
+        doc: &nb::ModelDocumentContent,
+        dependencies: &HashMap<String, nb::ModelDocumentContent>,
+        expected: Expect,
+    ) {
+        let text = notebook_to_text(doc, dependencies).unwrap();
+        expected.assert_eq(&text);
+        let output = BatchOutput::Snapshot(RefCell::new(String::new()));
+        assert!(batch::elaborate(&text, "generated.dbltt", &output).unwrap());
+    }
+
+    fn example(name: &str) -> nb::ModelDocumentContent {
+        let src = fs::read_to_string(format!("examples/tt/notebook/{name}.json")).unwrap();
+        serde_json::from_str(&src).unwrap()
+    }
+
+    /// Generates the documents for the given top-level types, keyed by name.
+    fn generate(src: &str, names: &[&str]) -> HashMap<String, nb::ModelDocumentContent> {
+        let reporter = Reporter::new();
+        let toplevel = TT_PARSE_CONFIG
+            .with_parsed_top(src, reporter.clone(), |topntns| {
+                let mut toplevel = Toplevel::new(std_theories());
+                let mut topelab = TopElaborator::new(reporter.clone());
+                for topntn in topntns.iter() {
+                    if let Some(TopElabResult::Declaration(name, decl)) =
+                        topelab.elab(&toplevel, topntn)
+                    {
+                        toplevel.declarations.insert(name, decl);
+                    }
+                }
+                Some(toplevel)
+            })
+            .unwrap();
+        assert!(!reporter.errored());
+
+        // Link instantiations to documents by name, as the dependencies are keyed.
+        let mut generator =
+            NotebookGenerator::new(UuidSource::Sequential(0)).with_resolver(|name| {
+                Some(nb::StableRef {
+                    id: name.to_string(),
+                    version: None,
+                    server: "localhost".to_string(),
+                })
+            });
+        names
+            .iter()
+            .map(|name| {
+                let doc = generator.document(&toplevel, name_seg(*name)).unwrap();
+                // Documents are not `Clone`, so copy them through JSON.
+                let doc = serde_json::from_value(serde_json::to_value(doc).unwrap()).unwrap();
+                (name.to_string(), doc)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn notebook_examples() {
+        let deps = HashMap::new();
+        to_text(
+            &example("commutative_square"),
+            &deps,
+            expect![[r#"
+                set_theory ThSchema
+
+                type Commutative_Square := [
+                  NW : Entity,
+                  NE : Entity,
+                  SW : Entity,
+                  SE : Entity,
+                  t : (Hom Entity)[NW, NE],
+                  l : (Hom Entity)[NW, SW],
+                  r : (Hom Entity)[NE, SE],
+                  b : (Hom Entity)[SW, SE],
+                  comm : (t * r == l * b)
+                ]
+            "#]],
+        );
+        to_text(
+            &example("sir_petri"),
+            &deps,
+            expect![[r#"
+            set_theory ThSymMonoidalCategory
+
+            type SIR := [
+              S : Object,
+              I : Object,
+              R : Object,
+              infect : (Hom Object)[@tensor [S, I], @tensor [I, I]],
+              recover : (Hom Object)[@tensor [I], @tensor [R]]
+            ]
+        "#]],
+        );
+    }
+
+    #[test]
+    fn instantiations() {
+        let src = "
+set_theory ThSchema
+type Arrow := [ src : Entity, tgt : Entity, f : (Hom Entity)[src, tgt] ]
+type Composable := [
+    first : Arrow,
+    second : Arrow & [ .src : @sing first.tgt ],
+    g : (Hom Entity)[first.src, second.tgt],
+    comm : (first.f * second.f == g)
+]
+";
+        let mut docs = generate(src, &["Arrow", "Composable"]);
+        let doc = docs.remove("Composable").unwrap();
+        to_text(
+            &doc,
+            &docs,
+            expect![[r#"
+            set_theory ThSchema
+
+            type Arrow := [
+              src : Entity,
+              tgt : Entity,
+              f : (Hom Entity)[src, tgt]
+            ]
+
+            type Composable := [
+              first : Arrow,
+              second : Arrow & [.src := first.tgt],
+              g : (Hom Entity)[first.src, second.tgt],
+              comm : (first.f * second.f == g)
+            ]
+        "#]],
+        );
+
+        let err = notebook_to_text(&doc, &HashMap::new()).unwrap_err();
+        expect!["instantiated model Arrow was not provided"].assert_eq(&err);
+    }
+
+    #[test]
+    fn sanitized_labels() {
+        let mut doc = example("sir_petri");
+        for cell in doc.notebook.cell_contents.values_mut() {
+            if let nb::NotebookCell::Formal { content, .. } = cell {
+                match content {
+                    nb::ModelJudgment::Object(ob_decl) if ob_decl.name == "R" => {
+                        ob_decl.name = "S".into();
+                    }
+                    nb::ModelJudgment::Object(ob_decl) if ob_decl.name == "I" => {
+                        ob_decl.name = "1 infected".into();
+                    }
+                    nb::ModelJudgment::Morphism(mor_decl) => {
+                        mor_decl.name = "type".into();
+                    }
+                    _ => {}
+                }
+            }
+        }
+        doc.name = "Object".into();
+        to_text(
+            &doc,
+            &HashMap::new(),
+            expect![[r#"
+            set_theory ThSymMonoidalCategory
+
+            type Object_2 := [
+              S : Object,
+              _1_infected : Object,
+              S_2 : Object,
+              type_2 :
+                (Hom Object)[@tensor [S, _1_infected], @tensor [_1_infected, _1_infected]],
+              type_3 : (Hom Object)[@tensor [_1_infected], @tensor [S_2]]
+            ]
+        "#]],
+        );
+    }
+}
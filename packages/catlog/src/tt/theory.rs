@@ -19,7 +19,7 @@ use crate::dbl::{
     theory::{DblTheory, DblTheoryKind, NonUnital, Unital},
 };
 use crate::one::QualifiedPath;
-use crate::stdlib::theories;
+use crate::stdlib::{DiscreteDblTheoryMap, theories, theory_morphisms};
 use crate::zero::{QualifiedName, name};
 
 /// A theory supported by DoubleTT, comprising a name and a definition.
@@ -304,6 +304,10 @@ pub fn std_theories() -> HashMap<QualifiedName, Theory> {
         (name("ThSchema"), TheoryDef::discrete(theories::th_schema())),
         (name("ThCategory"), TheoryDef::discrete(theories::th_category())),
         (name("ThSignedCategory"), TheoryDef::discrete(theories::th_signed_category())),
+        (
+            name("ThNullableSignedCategory"),
+            TheoryDef::discrete(theories::th_nullable_signed_category()),
+        ),
         (name("ThCategoryLinks"), TheoryDef::discrete_tab(theories::th_category_links())),
         (name("ThMulticategory"), TheoryDef::modal_unital(theories::th_multicategory())),
         (
@@ -315,3 +319,25 @@ pub fn std_theories() -> HashMap<QualifiedName, Theory> {
     .map(|(name, def)| (name.clone(), Theory::new(name, def)))
     .collect()
 }
+
+/// Construct a library of standard maps between theories.
+///
+/// The maps are indexed by the names of their domain and codomain theories.
+pub fn std_theory_maps() -> HashMap<(QualifiedName, QualifiedName), DiscreteDblTheoryMap> {
+    [
+        (
+            (name("ThCategory"), name("ThSchema")),
+            theory_morphisms::th_category_to_schema(),
+        ),
+        (
+            (name("ThSchema"), name("ThCategory")),
+            theory_morphisms::th_schema_to_category(),
+        ),
+        (
+            (name("ThSignedCategory"), name("ThNullableSignedCategory")),
+            theory_morphisms::th_signed_category_to_nullable_signed_category(),
+        ),
+    ]
+    .into_iter()
+    .collect()
+}
@@ -12,6 +12,8 @@ use crate::zero::QualifiedName;
 pub enum TopDecl {
     /// See [Type].
     Type(Type),
+    /// See [TypeDef].
+    TypeDef(TypeDef),
     /// See [DefConst].
     DefConst(DefConst),
     /// See [Def].
@@ -32,6 +34,23 @@ pub struct Type {
     pub val: TyV,
 }
 
+/// A toplevel declaration of a type parameterised by terms.
+///
+/// Unlike [Type], the body cannot be evaluated ahead of time, because it
+/// depends on the values of the arguments. Applications `name[a, b, ...]` are
+/// evaluated by evaluating the body in an environment with values for the
+/// arguments.
+#[derive(Constructor, Clone)]
+pub struct TypeDef {
+    /// The theory for the type.
+    pub theory: Theory,
+    /// The arguments for the type.
+    pub args: Row<TyS>,
+    /// The body of the type (to be evaluated in an environment with values for
+    /// the arguments).
+    pub body: TyS,
+}
+
 /// A toplevel declaration of a term in the empty context.
 ///
 /// Also stores the evaluation of that term, and the evaluation of the
@@ -76,6 +95,17 @@ impl TopDecl {
         }
     }
 
+    /// Unwraps the parameterised type for a toplevel declaration, or panics.
+    ///
+    /// This should only be used after type checking, when we know that a toplevel
+    /// variable name does in fact point to a parameterised type.
+    pub fn unwrap_type_def(self) -> TypeDef {
+        match self {
+            TopDecl::TypeDef(d) => d,
+            _ => panic!("top-level should be a parameterised type declaration"),
+        }
+    }
+
     /// Unwraps the term for a toplevel declaration of a term, or panics.
     ///
     /// This should only be used after type checking, when we know that a toplevel
@@ -97,7 +127,7 @@ impl TopDecl {
 }
 
 /// Storage for toplevel declarations.
-#[derive(Default)]
+#[derive(Clone, Default)]
 pub struct Toplevel {
     /// Library of theories, indexed by name.
     pub theory_library: HashMap<QualifiedName, Theory>,
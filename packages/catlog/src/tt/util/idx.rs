@@ -14,7 +14,7 @@
 use derive_more::{Deref, From};
 
 /// Forward indices (aka DeBruijn levels).
-#[derive(Copy, Clone, PartialEq, Eq, Debug, Deref, From)]
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug, Deref, From)]
 pub struct FwdIdx(usize);
 
 impl FwdIdx {
@@ -180,7 +180,7 @@ impl TyV {
 }
 
 /// Inner enum for [TmN].
-#[derive(PartialEq, Eq)]
+#[derive(PartialEq, Eq, Hash)]
 pub enum TmN_ {
     /// Variable.
     Var(FwdIdx, VarName, LabelSegment),
@@ -189,7 +189,7 @@ pub enum TmN_ {
 }
 
 /// Neutrals for [terms](TmV), dereferences to [TmN_].
-#[derive(Clone, Deref, PartialEq, Eq)]
+#[derive(Clone, Deref, PartialEq, Eq, Hash)]
 #[deref(forward)]
 pub struct TmN(Rc<TmN_>);
 
@@ -22,13 +22,12 @@ use crate::zero::QualifiedName;
 /// to fields of arbitrary depth. In this function, any specializations more
 /// than one level deep are ignored. To capture these, one might look for a
 /// "nested UWD" data structure.
-pub fn record_to_uwd(ty: &TyV) -> Option<UWD<ObType, QualifiedName>> {
+pub fn record_to_uwd(toplevel: &Toplevel, ty: &TyV) -> Option<UWD<ObType, QualifiedName>> {
     let TyV_::Record(record_v) = &**ty else {
         return None;
     };
 
-    let toplevel = Toplevel::default();
-    let eval = Evaluator::empty(&toplevel);
+    let eval = Evaluator::empty(toplevel);
     let (tm_n, eval) = eval.bind_self(ty.clone());
     let tm_v = eval.eta_neu(&tm_n, ty);
 
@@ -67,6 +67,16 @@ where
         self.0.variables()
     }
 
+    /// Iterates over the coefficients of the polynomial.
+    pub fn coefficients(&self) -> impl Iterator<Item = &Coef> {
+        (&self.0).into_iter().map(|(coef, _)| coef)
+    }
+
+    /// Iterates over the terms (coefficient-monomial pairs) of the polynomial.
+    pub fn terms(&self) -> impl Iterator<Item = (&Coef, &Monomial<Var, Exp>)> {
+        (&self.0).into_iter()
+    }
+
     /// Maps the coefficients of the polynomial.
     ///
     /// In the usual situations when the coefficients from commutative rigs and the
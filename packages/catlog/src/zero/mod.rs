@@ -3,9 +3,11 @@
 pub mod alg;
 pub mod column;
 pub mod qualified;
+pub mod rational;
 pub mod rig;
 pub mod set;
 
 pub use self::column::*;
 pub use self::qualified::*;
+pub use self::rational::*;
 pub use self::set::*;
@@ -0,0 +1,269 @@
+//! Exact rational numbers.
+//!
+//! Symbolic manipulations of [polynomials](super::alg::Polynomial), such as
+//! normalization and collecting like terms, should not accumulate rounding errors.
+//! The [`Rational`] type serves as an exact coefficient for such manipulations,
+//! with conversion to floating point deferred until numerical evaluation.
+
+use std::fmt::Display;
+use std::iter::{Product, Sum};
+use std::ops::{Add, AddAssign, Div, Mul, MulAssign, Neg, Sub, SubAssign};
+
+use num_rational::Rational64;
+use num_traits::{One, Signed, ToPrimitive, Zero};
+
+use super::rig::*;
+
+/// An exact rational number.
+///
+/// Rationals are stored in lowest terms with a positive denominator, so that
+/// equality is structural. As coefficients they form a [commutative
+/// ring](super::rig::CommRing), in fact a field. Arithmetic panics on overflow of
+/// the 64-bit numerator or denominator.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Rational(Rational64);
+
+impl Rational {
+    /// Constructs a rational number from a numerator and denominator.
+    ///
+    /// Panics if the denominator is zero.
+    pub fn new(numer: i64, denom: i64) -> Self {
+        Self(Rational64::new(numer, denom))
+    }
+
+    /// Constructs a rational number from an integer.
+    pub fn from_integer(n: i64) -> Self {
+        Self(Rational64::from_integer(n))
+    }
+
+    /// Approximates a floating point number by a rational number, if finite.
+    pub fn approximate(x: f64) -> Option<Self> {
+        Rational64::approximate_float(x).map(Self)
+    }
+
+    /// Numerator in lowest terms.
+    pub fn numer(&self) -> i64 {
+        *self.0.numer()
+    }
+
+    /// Denominator in lowest terms, which is always positive.
+    pub fn denom(&self) -> i64 {
+        *self.0.denom()
+    }
+
+    /// Is the rational number an integer?
+    pub fn is_integer(&self) -> bool {
+        self.0.is_integer()
+    }
+
+    /// Converts to a single-precision floating point number.
+    pub fn to_f32(&self) -> f32 {
+        self.0.to_f32().unwrap()
+    }
+
+    /// Converts to a double-precision floating point number.
+    pub fn to_f64(&self) -> f64 {
+        self.0.to_f64().unwrap()
+    }
+}
+
+impl From<i64> for Rational {
+    fn from(n: i64) -> Self {
+        Self::from_integer(n)
+    }
+}
+
+impl From<i32> for Rational {
+    fn from(n: i32) -> Self {
+        Self::from_integer(n.into())
+    }
+}
+
+impl From<Rational> for f32 {
+    fn from(q: Rational) -> Self {
+        q.to_f32()
+    }
+}
+
+impl From<Rational> for f64 {
+    fn from(q: Rational) -> Self {
+        q.to_f64()
+    }
+}
+
+impl Display for Rational {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl DisplayCoef for Rational {
+    fn has_negative_sign(&self) -> bool {
+        self.0.is_negative()
+    }
+    fn needs_parentheses(&self) -> bool {
+        !self.is_integer()
+    }
+}
+
+impl Zero for Rational {
+    fn zero() -> Self {
+        Self(Rational64::zero())
+    }
+    fn is_zero(&self) -> bool {
+        self.0.is_zero()
+    }
+}
+
+impl One for Rational {
+    fn one() -> Self {
+        Self(Rational64::one())
+    }
+}
+
+impl Neg for Rational {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self(-self.0)
+    }
+}
+
+impl Add for Rational {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self(self.0 + rhs.0)
+    }
+}
+
+impl Sub for Rational {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Self(self.0 - rhs.0)
+    }
+}
+
+impl Mul for Rational {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        Self(self.0 * rhs.0)
+    }
+}
+
+impl Div for Rational {
+    type Output = Self;
+
+    fn div(self, rhs: Self) -> Self {
+        Self(self.0 / rhs.0)
+    }
+}
+
+impl AddAssign for Rational {
+    fn add_assign(&mut self, rhs: Self) {
+        self.0 += rhs.0;
+    }
+}
+
+impl SubAssign for Rational {
+    fn sub_assign(&mut self, rhs: Self) {
+        self.0 -= rhs.0;
+    }
+}
+
+impl MulAssign for Rational {
+    fn mul_assign(&mut self, rhs: Self) {
+        self.0 *= rhs.0;
+    }
+}
+
+impl Sum for Rational {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::zero(), |acc, q| acc + q)
+    }
+}
+
+impl Product for Rational {
+    fn product<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::one(), |acc, q| acc * q)
+    }
+}
+
+impl AdditiveMonoid for Rational {}
+impl AbGroup for Rational {}
+impl Monoid for Rational {}
+impl CommMonoid for Rational {}
+impl Rig for Rational {}
+impl CommRig for Rational {}
+impl Ring for Rational {}
+impl CommRing for Rational {}
+
+/// The rationals as a module over themselves.
+impl RigModule for Rational {
+    type Rig = Rational;
+}
+
+impl Module for Rational {
+    type Ring = Rational;
+}
+
+/// Scalar multiplication of floating point numbers by rationals.
+///
+/// Allows polynomials with rational coefficients to be evaluated at floating
+/// point values.
+impl Mul<Rational> for f32 {
+    type Output = f32;
+
+    fn mul(self, rhs: Rational) -> f32 {
+        self * rhs.to_f32()
+    }
+}
+
+/// Scalar multiplication of floating point numbers by rationals.
+impl Mul<Rational> for f64 {
+    type Output = f64;
+
+    fn mul(self, rhs: Rational) -> f64 {
+        self * rhs.to_f64()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::zero::alg::Polynomial;
+
+    #[test]
+    fn arithmetic() {
+        let (half, third) = (Rational::new(1, 2), Rational::new(2, 6));
+        assert_eq!(third, Rational::new(1, 3));
+        assert_eq!(half + third, Rational::new(5, 6));
+        assert_eq!(half - third, Rational::new(1, 6));
+        assert_eq!(half * third, Rational::new(1, 6));
+        assert_eq!(half / third, Rational::new(3, 2));
+        assert_eq!((-half).to_string(), "-1/2");
+        assert_eq!(Rational::from(3).to_string(), "3");
+
+        // Repeated tenths cancel exactly, unlike with floating point.
+        let tenth = Rational::new(1, 10);
+        let sum: Rational = std::iter::repeat_n(tenth, 10).sum();
+        assert!(sum.is_one());
+        assert_eq!(Rational::approximate(0.25), Some(Rational::new(1, 4)));
+    }
+
+    #[test]
+    fn polynomials() {
+        let x = || Polynomial::<_, Rational, u8>::generator('x');
+        let y = || Polynomial::<_, Rational, u8>::generator('y');
+        let p =
+            (x() * Rational::new(1, 3) + y()) * Rational::new(3, 2) + -x() * Rational::new(1, 2);
+        assert_eq!(p.clone().normalize().to_string(), "(3/2) y");
+        assert_eq!(p.eval(|var| if *var == 'x' { 1.0 } else { 2.0f32 }), 3.0);
+
+        // Tenths of a variable sum exactly to the variable.
+        let tenths: Polynomial<_, _, _> = (0..10).map(|_| x() * Rational::new(1, 10)).sum();
+        assert_eq!((tenths + -x()).normalize().to_string(), "0");
+    }
+}
@@ -0,0 +1,46 @@
+use sqlx::{PgConnection, Postgres};
+use sqlx_migrator::Operation;
+use sqlx_migrator::error::Error;
+use sqlx_migrator::migration;
+use sqlx_migrator::vec_box;
+
+pub(crate) struct AddSnapshotValidation;
+
+migration!(
+    Postgres,
+    AddSnapshotValidation,
+    "backend",
+    "20261001000000_add_snapshot_validation",
+    vec_box![],
+    vec_box![MigrationOperation]
+);
+
+struct MigrationOperation;
+#[async_trait::async_trait]
+impl Operation<Postgres> for MigrationOperation {
+    async fn up(&self, conn: &mut PgConnection) -> Result<(), Error> {
+        sqlx::query(
+            "
+            ALTER TABLE snapshots
+            ADD COLUMN validation JSONB NULL;
+            ",
+        )
+        .execute(conn)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, conn: &mut PgConnection) -> Result<(), Error> {
+        sqlx::query(
+            "
+            ALTER TABLE snapshots
+            DROP COLUMN validation;
+            ",
+        )
+        .execute(conn)
+        .await?;
+
+        Ok(())
+    }
+}